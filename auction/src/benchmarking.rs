@@ -151,7 +151,7 @@ benchmarks! {
 
 	}: _(RawOrigin::Signed(alice), nft_id, marketplace_id, start_block, end_block, start_price, Some(buy_now_price))
 	verify {
-		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_listed, true);
+		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_locked_by(&AUCTION_LOCK), true);
 	}
 
 	 cancel_auction {
@@ -163,7 +163,7 @@ benchmarks! {
 
 	}: _(RawOrigin::Signed(bob), nft_id)
 	verify {
-		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_listed, false);
+		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_locked_by(&AUCTION_LOCK), false);
 	}
 
 	end_auction {
@@ -182,7 +182,7 @@ benchmarks! {
 	verify {
 		let eve: T::AccountId = get_account::<T>("EVE");
 		let nft = T::NFTExt::get_nft(nft_id).unwrap();
-		assert_eq!(nft.state.is_listed, false);
+		assert_eq!(nft.state.is_locked_by(&AUCTION_LOCK), false);
 		assert_eq!(nft.owner, eve);
 	}
 
@@ -231,7 +231,7 @@ benchmarks! {
 	}: _(RawOrigin::Signed(charlie.clone()), nft_id, buy_it_price)
 	verify {
 		let nft = T::NFTExt::get_nft(nft_id).unwrap();
-		assert_eq!(nft.state.is_listed, false);
+		assert_eq!(nft.state.is_locked_by(&AUCTION_LOCK), false);
 		assert_eq!(nft.owner, charlie);
 	}
 
@@ -36,10 +36,10 @@ use frame_support::{
 use frame_system::pallet_prelude::*;
 use primitives::{
 	common::CompoundFee,
-	nfts::{NFTData, NFTId},
+	nfts::{split_royalty, NFTId, NFTLock, NFTRestriction},
 };
 use sp_runtime::traits::{AccountIdConversion, Saturating};
-use ternoa_common::traits::{MarketplaceExt, NFTExt};
+use ternoa_common::traits::{MarketplaceExt, NFTExt, OnNFTBurn, OnNFTTransfer};
 use types::{AuctionData, BidderList, DeadlineList};
 pub use weights::WeightInfo;
 
@@ -48,6 +48,9 @@ pub type BalanceOf<T> =
 
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
+/// Lock held on NFTs that are being auctioned.
+pub const AUCTION_LOCK: NFTLock = NFTLock::new(*b"ter/auct", *b"auction_");
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -145,7 +148,7 @@ pub mod pallet {
 				let highest_bid = auction.pop_highest_bid();
 				if let Some((new_owner, paid)) = highest_bid {
 					// Pay the fee
-					let cut = match Self::pay_for_nft(&Self::account_id(), paid, &nft, &auction) {
+					let cut = match Self::pay_for_nft(&Self::account_id(), paid, nft_id, &auction) {
 						Ok(x) => x,
 						Err(_x) => continue,
 					};
@@ -167,7 +170,7 @@ pub mod pallet {
 					Self::emit_auction_completed_event(nft_id, None, None, None);
 				}
 
-				nft.state.is_listed = false;
+				_ = nft.state.unlock(&AUCTION_LOCK);
 				_ = T::NFTExt::set_nft(nft_id, nft);
 				Auctions::<T>::remove(nft_id);
 
@@ -283,16 +286,14 @@ pub mod pallet {
 		CannotRemoveBidAtTheEndOfAuction,
 		/// Cannot end the auction if it was not extended.
 		CannotEndAuctionThatWasNotExtended,
-		/// Cannot auction NFTs that are listed for sale.
-		CannotListListedNFTs,
+		/// Cannot auction NFTs locked by another pallet (listed, rented, in transmission, ...).
+		CannotListLockedNFTs,
 		/// Cannot auction NFTs that are not owned by the caller.
 		CannotListNotOwnedNFTs,
 		/// Cannot auction delegated NFTs.
 		CannotListDelegatedNFTs,
 		/// Cannot auction non-created soulbound NFTs.
 		CannotListNotCreatedSoulboundNFTs,
-		/// Cannot auction rented NFTs.
-		CannotListRentedNFTs,
 		/// Cannot claim if the claim does not exist.
 		ClaimDoesNotExist,
 		/// Cannot auction NFTs that do not exit.
@@ -317,8 +318,6 @@ pub mod pallet {
 		AmountTooLow,
 		/// Cannot list because the capsule is not synced.
 		CannotListNotSyncedCapsules,
-		/// Cannot list nfts with transmission protocol.
-		CannotListNFTsInTransmission,
 		/// The provided buy it now price does not match the real one.
 		PriceDoesNotMatch,
 	}
@@ -361,16 +360,7 @@ pub mod pallet {
 			// fetch the data of given nftId.
 			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::CannotListNotOwnedNFTs);
-			ensure!(!nft.state.is_listed, Error::<T>::CannotListListedNFTs);
-			ensure!(!nft.state.is_delegated, Error::<T>::CannotListDelegatedNFTs);
-			ensure!(
-				!(nft.state.is_soulbound && nft.creator != nft.owner),
-				Error::<T>::CannotListNotCreatedSoulboundNFTs
-			);
-			ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotListNotSyncedSecretNFTs);
-			ensure!(!nft.state.is_rented, Error::<T>::CannotListRentedNFTs);
-			ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotListNotSyncedCapsules);
-			ensure!(!nft.state.is_transmission, Error::<T>::CannotListNFTsInTransmission);
+			nft.can_list().map_err(Self::list_restriction_error)?;
 
 			let marketplace = T::MarketplaceExt::get_marketplace(marketplace_id)
 				.ok_or(Error::<T>::MarketplaceNotFound)?;
@@ -396,7 +386,7 @@ pub mod pallet {
 				Ok(())
 			})?;
 
-			nft.state.is_listed = true;
+			nft.state.lock(AUCTION_LOCK).ok_or(Error::<T>::CannotListLockedNFTs)?;
 			T::NFTExt::set_nft(nft_id, nft)?;
 
 			let bidders: BidderList<T::AccountId, BalanceOf<T>, T::BidderListLengthLimit> =
@@ -442,7 +432,7 @@ pub mod pallet {
 			// Remove bidders
 			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
 
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 			T::NFTExt::set_nft(nft_id, nft)?;
 			Auctions::<T>::remove(nft_id);
 			Deadlines::<T>::mutate(|x| x.remove(nft_id));
@@ -465,12 +455,12 @@ pub mod pallet {
 			let (new_owner, paid) =
 				auction.pop_highest_bid().ok_or(Error::<T>::CannotEndAuctionWithoutBids)?;
 
-			let cut = Self::pay_for_nft(&Self::account_id(), paid, &nft, &auction)?;
+			let cut = Self::pay_for_nft(&Self::account_id(), paid, nft_id, &auction)?;
 			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
 
 			// Change the owner
 			nft.owner = new_owner.clone();
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 
 			T::NFTExt::set_nft(nft_id, nft)?;
 			Auctions::<T>::remove(nft_id);
@@ -601,12 +591,12 @@ pub mod pallet {
 			}
 
 			// Pay for NFT
-			let cut = Self::pay_for_nft(&who, paid_amount, &nft, &auction)?;
+			let cut = Self::pay_for_nft(&who, paid_amount, nft_id, &auction)?;
 			// Handle Bidders
 			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
 
 			nft.owner = who.clone();
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 
 			T::NFTExt::set_nft(nft_id, nft)?;
 			Auctions::<T>::remove(nft_id);
@@ -634,6 +624,16 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+	fn list_restriction_error(restriction: NFTRestriction) -> Error<T> {
+		match restriction {
+			NFTRestriction::Locked(_) => Error::<T>::CannotListLockedNFTs,
+			NFTRestriction::Delegated => Error::<T>::CannotListDelegatedNFTs,
+			NFTRestriction::NotCreatedSoulbound => Error::<T>::CannotListNotCreatedSoulboundNFTs,
+			NFTRestriction::SecretSyncing => Error::<T>::CannotListNotSyncedSecretNFTs,
+			NFTRestriction::CapsuleSyncing => Error::<T>::CannotListNotSyncedCapsules,
+		}
+	}
+
 	/// The account ID of the auctions pot.
 	pub fn account_id() -> T::AccountId {
 		T::PalletId::get().into_account_truncating()
@@ -642,11 +642,10 @@ impl<T: Config> Pallet<T> {
 	pub fn pay_for_nft(
 		from: &T::AccountId,
 		amount: BalanceOf<T>,
-		nft: &NFTData<T::AccountId, <<T as Config>::NFTExt as NFTExt>::NFTOffchainDataLimit>,
+		nft_id: NFTId,
 		auction: &AuctionData<T::AccountId, T::BlockNumber, BalanceOf<T>, T::BidderListLengthLimit>,
 	) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
-		let nft_creator = &nft.creator;
-		let nft_royalty = nft.royalty;
+		let nft_royalty = T::NFTExt::get_royalty(nft_id);
 		let auction_creator = &auction.creator;
 		let marketplace_id = auction.marketplace_id;
 
@@ -668,7 +667,10 @@ impl<T: Config> Pallet<T> {
 
 		let exist = if from == &Self::account_id() { AllowDeath } else { KeepAlive };
 		T::Currency::transfer(from, &marketplace.owner, to_marketplace, exist)?;
-		T::Currency::transfer(from, nft_creator, to_nft_creator, exist)?;
+		let splits = T::NFTExt::get_royalty_splits(nft_id);
+		for (beneficiary, amount) in split_royalty(&splits, to_nft_creator) {
+			T::Currency::transfer(from, &beneficiary, amount, exist)?;
+		}
 		T::Currency::transfer(from, auction_creator, to_auction_creator, exist)?;
 
 		Ok((to_marketplace, to_nft_creator, to_auction_creator))
@@ -680,6 +682,22 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Remove the auction of an NFT that changed owner or was burned and let the bidders claim
+	/// their bids back.
+	pub fn remove_auction(nft_id: NFTId) -> Weight {
+		let auction = match Auctions::<T>::take(nft_id) {
+			Some(x) => x,
+			None => return T::DbWeight::get().reads(1),
+		};
+
+		auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
+		Deadlines::<T>::mutate(|x| x.remove(nft_id));
+		Self::deposit_event(Event::AuctionCancelled { nft_id });
+
+		let bidders = auction.get_bidders().len() as u64;
+		T::DbWeight::get().reads_writes(2 + bidders, 2 + bidders)
+	}
+
 	pub fn has_started(now: T::BlockNumber, start_block: T::BlockNumber) -> bool {
 		now >= start_block
 	}
@@ -732,3 +750,28 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 }
+
+impl<T: Config> OnNFTTransfer<T::AccountId> for Pallet<T> {
+	fn on_nft_transfer(nft_id: NFTId, _from: &T::AccountId, _to: &T::AccountId) -> Weight {
+		if !Auctions::<T>::contains_key(nft_id) {
+			return T::DbWeight::get().reads(1)
+		}
+		_ = T::NFTExt::unlock_nft(nft_id, AUCTION_LOCK);
+
+		Self::remove_auction(nft_id).saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == AUCTION_LOCK
+	}
+}
+
+impl<T: Config> OnNFTBurn<T::AccountId> for Pallet<T> {
+	fn on_nft_burn(nft_id: NFTId, _owner: &T::AccountId) -> Weight {
+		Self::remove_auction(nft_id)
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == AUCTION_LOCK
+	}
+}
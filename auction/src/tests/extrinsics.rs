@@ -22,7 +22,7 @@ use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
 use primitives::{
 	marketplace::{MarketplaceId, MarketplaceType},
-	nfts::NFTId,
+	nfts::{NFTId, NFTLock},
 	CompoundFee, ConfigOp,
 };
 use sp_arithmetic::per_things::Permill;
@@ -31,9 +31,10 @@ use ternoa_common::traits::{MarketplaceExt, NFTExt};
 use crate::{
 	tests::mock,
 	types::{AuctionData, BidderList},
-	Auctions, Claims, Config, Deadlines, Error, Event as AuctionEvent,
+	Auctions, Claims, Config, Deadlines, Error, Event as AuctionEvent, AUCTION_LOCK,
 };
 
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
 const PERCENT_0: Permill = Permill::from_parts(0);
 const PERCENT_20: Permill = Permill::from_parts(200000);
 const ALICE_COLLECTION_ID_0: NFTId = 0;
@@ -62,13 +63,13 @@ pub fn prepare_tests() {
 	NFT::create_nft(
 		alice.clone(),
 		BoundedVec::default(),
-		PERCENT_0,
+		Some(PERCENT_0),
 		Some(ALICE_COLLECTION_ID_0),
 		false,
 	)
 	.unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(bob, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(bob, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 
 	//Create marketplace.
 	Marketplace::create_marketplace(alice.clone(), MarketplaceType::Public).unwrap();
@@ -131,7 +132,8 @@ pub mod create_auction {
 			};
 
 			let _ = deadlines.insert(ALICE_NFT_ID_0, auction.end_block);
-			let state = NFTState::new(false, true, false, false, false, false, false, false, false);
+			let mut state = NFTState::new(false, false, false, false, false, false);
+			state.lock(AUCTION_LOCK).unwrap();
 
 			// Execution
 			let ok = Auction::create_auction(
@@ -253,17 +255,15 @@ pub mod create_auction {
 	}
 
 	#[test]
-	fn cannot_list_listed_nfts() {
+	fn cannot_list_locked_nfts() {
 		ExtBuilder::new_build(None).execute_with(|| {
 			prepare_tests();
 
-			// Set listed.
-			let mut nft = NFT::get_nft(ALICE_NFT_ID_0).unwrap();
-			nft.state.is_listed = true;
-			NFT::set_nft(ALICE_NFT_ID_0, nft).unwrap();
+			// Lock the NFT on behalf of another pallet.
+			NFT::lock_nft(ALICE_NFT_ID_0, TEST_LOCK).unwrap();
 
 			let ok = AuctionBuilder::new().nft_id(ALICE_NFT_ID_0).execute();
-			assert_noop!(ok, Error::<Test>::CannotListListedNFTs);
+			assert_noop!(ok, Error::<Test>::CannotListLockedNFTs);
 		})
 	}
 
@@ -314,21 +314,6 @@ pub mod create_auction {
 		})
 	}
 
-	#[test]
-	fn cannot_list_rented_nfts() {
-		ExtBuilder::new_build(None).execute_with(|| {
-			prepare_tests();
-
-			// Set rented.
-			let mut nft = NFT::get_nft(ALICE_NFT_ID_0).unwrap();
-			nft.state.is_rented = true;
-			NFT::set_nft(ALICE_NFT_ID_0, nft).unwrap();
-
-			let ok = AuctionBuilder::new().nft_id(ALICE_NFT_ID_0).execute();
-			assert_noop!(ok, Error::<Test>::CannotListRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_list_not_synced_capsules() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -345,21 +330,6 @@ pub mod create_auction {
 		})
 	}
 
-	#[test]
-	fn cannot_list_nfts_in_transmission() {
-		ExtBuilder::new_build(None).execute_with(|| {
-			prepare_tests();
-
-			// Set transmission.
-			let mut nft = NFT::get_nft(ALICE_NFT_ID_0).unwrap();
-			nft.state.is_transmission = true;
-			NFT::set_nft(ALICE_NFT_ID_0, nft).unwrap();
-
-			let err = AuctionBuilder::new().nft_id(ALICE_NFT_ID_0).execute();
-			assert_noop!(err, Error::<Test>::CannotListNFTsInTransmission);
-		})
-	}
-
 	#[test]
 	fn marketplace_not_found() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -491,15 +461,22 @@ pub mod create_auction {
 
 			(current_count..limit)
 				.map(|_| {
-					NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false)
-						.unwrap();
+					NFT::create_nft(
+						origin(ALICE),
+						BoundedVec::default(),
+						Some(PERCENT_0),
+						None,
+						false,
+					)
+					.unwrap();
 					NFT::next_nft_id() - 1
 				})
 				.for_each(|x| {
 					AuctionBuilder::new().nft_id(x).execute().unwrap();
 				});
 
-			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 
 			let ok = AuctionBuilder::new().nft_id(NFT::next_nft_id() - 1).execute();
 			assert_noop!(ok, Error::<Test>::MaximumAuctionsLimitReached);
@@ -526,7 +503,7 @@ pub mod cancel_auction {
 
 			// Expected Data
 			deadlines.remove(ALICE_NFT_ID_1);
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 			nft.owner = ALICE;
 
 			// Storage.
@@ -558,7 +535,8 @@ pub mod cancel_auction {
 	fn auction_does_not_exist() {
 		ExtBuilder::new_build(None).execute_with(|| {
 			prepare_tests();
-			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			let ok = Auction::cancel_auction(origin(ALICE), nft_id);
@@ -602,7 +580,8 @@ pub mod end_auction {
 			let eve: mock::RuntimeOrigin = RawOrigin::Signed(EVE).into();
 
 			// Bob creates the NFT
-			NFT::create_nft(origin(BOB), BoundedVec::default(), PERCENT_20, None, false).unwrap();
+			NFT::create_nft(origin(BOB), BoundedVec::default(), Some(PERCENT_20), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			// Bob sends the NFT to EVE
@@ -669,7 +648,7 @@ pub mod end_auction {
 			assert_eq!(pallet_new_balance, charlie_bid);
 
 			// Expected NFT state
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 			nft.owner = DAVE;
 			assert_eq!(NFT::get_nft(nft_id).unwrap(), nft);
 
@@ -709,7 +688,8 @@ pub mod end_auction {
 		ExtBuilder::new_build(None).execute_with(|| {
 			prepare_tests();
 
-			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			let ok = Auction::end_auction(origin(ALICE), nft_id);
@@ -1122,7 +1102,8 @@ pub mod buy_it_now {
 			let dave: mock::RuntimeOrigin = origin(DAVE);
 
 			// Bob creates the NFT.
-			NFT::create_nft(origin(BOB), BoundedVec::default(), PERCENT_20, None, false).unwrap();
+			NFT::create_nft(origin(BOB), BoundedVec::default(), Some(PERCENT_20), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			// Bob gives the NFT to EVE
@@ -1183,7 +1164,7 @@ pub mod buy_it_now {
 			assert_eq!(pallet_new_balance, pallet_balance + loser_bid);
 
 			// Expected NFT state
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&AUCTION_LOCK);
 			nft.owner = CHARLIE;
 			assert_eq!(NFT::get_nft(nft_id).unwrap(), nft);
 
@@ -1206,6 +1187,46 @@ pub mod buy_it_now {
 		})
 	}
 
+	#[test]
+	fn buy_it_now_with_royalty_splits() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			// Bob creates the NFT and shares its royalty with Dave.
+			NFT::create_nft(origin(BOB), BoundedVec::default(), Some(PERCENT_20), None, false)
+				.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			let splits =
+				bounded_vec![(BOB, Permill::from_percent(75)), (DAVE, Permill::from_percent(25))];
+			NFT::set_royalty_splits(origin(BOB), nft_id, Some(splits)).unwrap();
+			NFT::transfer_nft(origin(BOB), nft_id, EVE).unwrap();
+
+			// Eve creates the auction
+			AuctionBuilder::new()
+				.origin(origin(EVE))
+				.nft_id(nft_id)
+				.now_buy(Some(DEFAULT_PRICE + 100))
+				.execute()
+				.unwrap();
+			let auction = Auctions::<Test>::get(nft_id).unwrap();
+			run_to_block(auction.start_block);
+
+			let bob_balance = Balances::free_balance(BOB);
+			let dave_balance = Balances::free_balance(DAVE);
+
+			// Execute buy it now
+			assert_ok!(Auction::buy_it_now(origin(CHARLIE), nft_id, DEFAULT_PRICE + 100));
+
+			// Royalty is split between the beneficiaries.
+			let paid_amount = auction.buy_it_price.unwrap();
+			let marketplace_cut = PERCENT_20 * paid_amount;
+			let royalty_cut: u128 = PERCENT_20 * paid_amount.saturating_sub(marketplace_cut);
+			let dave_cut = Permill::from_percent(25) * royalty_cut;
+			assert_eq!(Balances::free_balance(BOB), bob_balance + royalty_cut - dave_cut);
+			assert_eq!(Balances::free_balance(DAVE), dave_balance + dave_cut);
+		})
+	}
+
 	#[test]
 	fn nft_not_found() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -1217,7 +1238,8 @@ pub mod buy_it_now {
 	#[test]
 	fn auction_does_not_exist() {
 		ExtBuilder::new_build(None).execute_with(|| {
-			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			let ok = Auction::buy_it_now(origin(BOB), nft_id, DEFAULT_PRICE);
@@ -1423,3 +1445,52 @@ impl AuctionBuilder {
 		)
 	}
 }
+
+pub mod nft_hooks {
+	pub use super::*;
+
+	#[test]
+	fn burn_removes_auction() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+			let bid = auction.start_price + 10;
+			Auction::add_bid(origin(BOB), ALICE_NFT_ID_1, bid).unwrap();
+
+			assert_ok!(NFT::burn_nft(origin(ALICE), ALICE_NFT_ID_1));
+
+			// Storage.
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID_1), None);
+			assert_eq!(Auctions::<Test>::get(ALICE_NFT_ID_1), None);
+			assert_eq!(Deadlines::<Test>::get().len(), 0);
+			assert_eq!(Claims::<Test>::get(BOB), Some(bid));
+
+			// Check Events.
+			let event = AuctionEvent::AuctionCancelled { nft_id: ALICE_NFT_ID_1 };
+			let event = RuntimeEvent::Auction(event);
+			assert!(System::events().iter().any(|x| x.event == event));
+		})
+	}
+
+	#[test]
+	fn transfer_removes_auction() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			assert_ok!(NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID_1, BOB));
+
+			// Storage.
+			assert_eq!(Auctions::<Test>::get(ALICE_NFT_ID_1), None);
+			assert_eq!(Deadlines::<Test>::get().len(), 0);
+			let nft = NFT::get_nft(ALICE_NFT_ID_1).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert!(!nft.state.is_locked_by(&AUCTION_LOCK));
+
+			// Check Events.
+			let event = AuctionEvent::AuctionCancelled { nft_id: ALICE_NFT_ID_1 };
+			let event = RuntimeEvent::Auction(event);
+			assert!(System::events().iter().any(|x| x.event == event));
+		})
+	}
+}
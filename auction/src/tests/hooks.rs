@@ -41,8 +41,9 @@ fn on_initialize() {
 		let alice: mock::RuntimeOrigin = origin(ALICE);
 		let bob: mock::RuntimeOrigin = origin(BOB);
 
-		NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-		NFT::create_nft(bob.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+			.unwrap();
+		NFT::create_nft(bob.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 		Marketplace::create_marketplace(alice.clone(), MarketplaceType::Public).unwrap();
 
 		let alice_start_block = 10;
@@ -139,7 +140,8 @@ fn auctions_in_block() {
 		// Create NFTs and auction them
 		for _i in 0..auctions_in_block + offset {
 			// Create NFTs
-			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let id = NFT::next_nft_id() - 1;
 
 			AuctionBuilder::new().nft_id(id).execute().unwrap();
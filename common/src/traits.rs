@@ -16,14 +16,14 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{dispatch::DispatchResult, traits::Get, BoundedVec};
+use frame_support::{dispatch::DispatchResult, traits::Get, weights::Weight, BoundedVec};
 use primitives::{
 	marketplace::{MarketplaceData, MarketplaceId},
-	nfts::{CollectionId, NFTData, NFTId, NFTState},
+	nfts::{AttributeNamespace, CollectionId, NFTData, NFTId, NFTLock, NFTState},
 	tee::ClusterId,
 };
 use sp_runtime::Permill;
-use sp_std::fmt::Debug;
+use sp_std::{fmt::Debug, vec::Vec};
 
 pub trait NFTExt {
 	type AccountId: Clone + PartialEq + Debug;
@@ -71,6 +71,34 @@ pub trait NFTExt {
 	) -> Result<R, E>;
 
 	fn exists(id: NFTId) -> bool;
+
+	/// Returns the royalty paid when an NFT is sold, taking its collection royalty into account.
+	fn get_royalty(id: NFTId) -> Permill;
+
+	/// Returns the royalty beneficiaries of an NFT with their share of the royalty.
+	fn get_royalty_splits(id: NFTId) -> Vec<(Self::AccountId, Permill)>;
+
+	/// Take a named lock on an NFT on behalf of a consuming pallet.
+	fn lock_nft(id: NFTId, lock: NFTLock) -> DispatchResult;
+
+	/// Release a lock previously taken on an NFT.
+	fn unlock_nft(id: NFTId, lock: NFTLock) -> DispatchResult;
+
+	/// Returns the value of an NFT attribute.
+	fn get_nft_attribute(id: NFTId, namespace: AttributeNamespace, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Returns the value of a collection attribute.
+	fn get_collection_attribute(
+		id: CollectionId,
+		namespace: AttributeNamespace,
+		key: &[u8],
+	) -> Option<Vec<u8>>;
+
+	/// Set an NFT attribute in the pallet namespace. No deposit is reserved.
+	fn set_nft_pallet_attribute(id: NFTId, key: &[u8], value: &[u8]) -> DispatchResult;
+
+	/// Clear an NFT attribute of the pallet namespace.
+	fn clear_nft_pallet_attribute(id: NFTId, key: &[u8]) -> DispatchResult;
 }
 
 pub trait MarketplaceExt {
@@ -120,4 +148,77 @@ pub trait TEEExt {
 	) -> DispatchResult;
 
 	fn fill_unregistration_list(address: Self::AccountId, number: u8) -> DispatchResult;
+
+	/// Returns true if the cluster exists.
+	fn cluster_exists(cluster_id: ClusterId) -> bool;
+
+	/// Create an empty cluster and return its id. Only used to prepare benchmarks.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_cluster() -> ClusterId;
+}
+
+/// Hook called by the TEE pallet when an enclave is removed from its cluster.
+pub trait OnEnclaveRemoved<AccountId> {
+	/// Handle the removal of the enclave of an operator and return the consumed weight.
+	fn on_enclave_removed(cluster_id: ClusterId, operator_address: &AccountId) -> Weight;
+}
+
+impl<AccountId> OnEnclaveRemoved<AccountId> for () {
+	fn on_enclave_removed(_cluster_id: ClusterId, _operator_address: &AccountId) -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Hook called by the NFT pallet once an NFT was transferred with one of its transfer calls, or
+/// moved to a new owner with the NFT it is nested in. Changes of owner made by other pallets
+/// through `NFTExt` are not notified, unlike the NFTs nested in them. Implemented by tuples to
+/// notify several pallets.
+pub trait OnNFTTransfer<AccountId> {
+	/// Handle the transfer of an NFT and return the consumed weight.
+	fn on_nft_transfer(nft_id: NFTId, from: &AccountId, to: &AccountId) -> Weight;
+
+	/// Returns true if the handler releases the given lock when the NFT is transferred. Such a
+	/// lock does not prevent the NFT from being transferred.
+	fn releases_lock(lock: &NFTLock) -> bool;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnNFTTransfer<AccountId> for Tuple {
+	fn on_nft_transfer(nft_id: NFTId, from: &AccountId, to: &AccountId) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #(
+			weight = weight.saturating_add(Tuple::on_nft_transfer(nft_id, from, to));
+		)* );
+		weight
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		for_tuples!( #( if Tuple::releases_lock(lock) { return true } )* );
+		false
+	}
+}
+
+/// Hook called by the NFT pallet once an NFT was burned. Implemented by tuples to notify several
+/// pallets.
+pub trait OnNFTBurn<AccountId> {
+	/// Handle the burn of an NFT and return the consumed weight.
+	fn on_nft_burn(nft_id: NFTId, owner: &AccountId) -> Weight;
+
+	/// Returns true if the handler cleans up the given lock when the NFT is burned. Such a lock
+	/// does not prevent the NFT from being burned.
+	fn releases_lock(lock: &NFTLock) -> bool;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnNFTBurn<AccountId> for Tuple {
+	fn on_nft_burn(nft_id: NFTId, owner: &AccountId) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::on_nft_burn(nft_id, owner)); )* );
+		weight
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		for_tuples!( #( if Tuple::releases_lock(lock) { return true } )* );
+		false
+	}
 }
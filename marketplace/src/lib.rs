@@ -33,6 +33,7 @@ use frame_support::{
 		Currency, ExistenceRequirement::KeepAlive, Get, OnUnbalanced, StorageVersion,
 		WithdrawReasons,
 	},
+	weights::Weight,
 	BoundedVec,
 };
 use frame_system::pallet_prelude::*;
@@ -41,12 +42,12 @@ use sp_std::prelude::*;
 
 use primitives::{
 	marketplace::{MarketplaceData, MarketplaceId, MarketplaceType},
-	nfts::{CollectionId, NFTId},
+	nfts::{split_royalty, CollectionId, NFTId, NFTLock, NFTRestriction},
 	CompoundFee, ConfigOp, U8BoundedVec,
 };
 use ternoa_common::{
 	config_op_field_exp,
-	traits::{MarketplaceExt, NFTExt},
+	traits::{MarketplaceExt, NFTExt, OnNFTBurn, OnNFTTransfer},
 };
 pub use weights::WeightInfo;
 
@@ -58,6 +59,9 @@ pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
 
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
+/// Lock held on NFTs listed for sale.
+pub const MARKETPLACE_LOCK: NFTLock = NFTLock::new(*b"ter/mrkt", *b"listsale");
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -193,8 +197,8 @@ pub mod pallet {
 		CannotBuyOwnedNFT,
 		/// Sender is already the marketplace owner
 		CannotTransferMarketplaceToYourself,
-		/// NFT already listed
-		CannotListAlreadytListedNFTs,
+		/// Cannot list NFTs locked by another pallet (listed, rented, in transmission, ...).
+		CannotListLockedNFTs,
 		/// The selected price is too low for commission fee
 		PriceCannotCoverMarketplaceFee,
 		/// Marketplace not found
@@ -215,12 +219,8 @@ pub mod pallet {
 		NotEnoughBalanceToBuy,
 		/// Cannot list because the NFT secret is not synced.
 		CannotListNotSyncedSecretNFTs,
-		/// Cannot list rented NFTs.
-		CannotListRentedNFTs,
 		/// Cannot list because the capsule is not synced.
 		CannotListNotSyncedCapsules,
-		/// Cannot list capsule in transmission.
-		CannotListNFTsInTransmission,
 		/// The provided price does not match the real price
 		PriceDoesNotMatch,
 	}
@@ -373,18 +373,9 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 
 			// Checks
-			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+			let nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-			ensure!(!nft.state.is_listed, Error::<T>::CannotListAlreadytListedNFTs);
-			ensure!(!nft.state.is_delegated, Error::<T>::CannotListDelegatedNFTs);
-			ensure!(
-				!(nft.state.is_soulbound && nft.creator != nft.owner),
-				Error::<T>::CannotListNotCreatedSoulboundNFTs
-			);
-			ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotListNotSyncedSecretNFTs);
-			ensure!(!nft.state.is_rented, Error::<T>::CannotListRentedNFTs);
-			ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotListNotSyncedCapsules);
-			ensure!(!nft.state.is_transmission, Error::<T>::CannotListNFTsInTransmission);
+			nft.can_list().map_err(Self::list_restriction_error)?;
 
 			let marketplace =
 				Marketplaces::<T>::get(marketplace_id).ok_or(Error::<T>::MarketplaceNotFound)?;
@@ -406,8 +397,7 @@ pub mod pallet {
 			// Execute.
 			let sale = Sale::new(who, marketplace_id, price, marketplace.commission_fee);
 			ListedNfts::<T>::insert(nft_id, sale);
-			nft.state.is_listed = true;
-			T::NFTExt::set_nft_state(nft_id, nft.state)?;
+			T::NFTExt::lock_nft(nft_id, MARKETPLACE_LOCK)?;
 
 			let event = Event::NFTListed {
 				nft_id,
@@ -424,15 +414,14 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::unlist_nft())]
 		pub fn unlist_nft(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+			let nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 
 			// Checks.
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
 			ensure!(ListedNfts::<T>::contains_key(nft_id), Error::<T>::NFTNotForSale);
 
 			// Execute.
-			nft.state.is_listed = false;
-			T::NFTExt::set_nft_state(nft_id, nft.state)?;
+			T::NFTExt::unlock_nft(nft_id, MARKETPLACE_LOCK)?;
 			ListedNfts::<T>::remove(nft_id);
 			Self::deposit_event(Event::NFTUnlisted { nft_id });
 
@@ -462,9 +451,12 @@ pub mod pallet {
 			let commission_fee = Self::pay_commission_fee(&who, &marketplace, &sale, price)?;
 			price = price.checked_sub(&commission_fee).ok_or(Error::<T>::InternalMathError)?;
 
-			// Caller pays for royalty, the price is updated.
-			let royalty_value = nft.royalty * price;
-			T::Currency::transfer(&who, &nft.creator, royalty_value, KeepAlive)?;
+			// Caller pays for royalty to its beneficiaries, the price is updated.
+			let royalty_value = T::NFTExt::get_royalty(nft_id) * price;
+			let splits = T::NFTExt::get_royalty_splits(nft_id);
+			for (beneficiary, amount) in split_royalty(&splits, royalty_value) {
+				T::Currency::transfer(&who, &beneficiary, amount, KeepAlive)?;
+			}
 			price = price.checked_sub(&royalty_value).ok_or(Error::<T>::InternalMathError)?;
 
 			// Caller pays the seller the updated price.
@@ -472,7 +464,7 @@ pub mod pallet {
 
 			//Execute.
 			nft.owner = who.clone();
-			nft.state.is_listed = false;
+			_ = nft.state.unlock(&MARKETPLACE_LOCK);
 			T::NFTExt::set_nft(nft_id, nft)?;
 			ListedNfts::<T>::remove(nft_id);
 			let event = Event::NFTSold {
@@ -552,6 +544,26 @@ impl<T: Config> Pallet<T> {
 		}
 		Ok(0u32.into())
 	}
+
+	/// Remove the sale of an NFT that changed owner or was burned.
+	fn remove_sale(nft_id: NFTId) -> Weight {
+		if ListedNfts::<T>::take(nft_id).is_none() {
+			return T::DbWeight::get().reads(1)
+		}
+		Self::deposit_event(Event::NFTUnlisted { nft_id });
+
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	fn list_restriction_error(restriction: NFTRestriction) -> Error<T> {
+		match restriction {
+			NFTRestriction::Locked(_) => Error::<T>::CannotListLockedNFTs,
+			NFTRestriction::Delegated => Error::<T>::CannotListDelegatedNFTs,
+			NFTRestriction::NotCreatedSoulbound => Error::<T>::CannotListNotCreatedSoulboundNFTs,
+			NFTRestriction::SecretSyncing => Error::<T>::CannotListNotSyncedSecretNFTs,
+			NFTRestriction::CapsuleSyncing => Error::<T>::CannotListNotSyncedCapsules,
+		}
+	}
 }
 
 impl<T: Config> MarketplaceExt for Pallet<T> {
@@ -590,3 +602,28 @@ impl<T: Config> MarketplaceExt for Pallet<T> {
 		Ok(())
 	}
 }
+
+impl<T: Config> OnNFTTransfer<T::AccountId> for Pallet<T> {
+	fn on_nft_transfer(nft_id: NFTId, _from: &T::AccountId, _to: &T::AccountId) -> Weight {
+		if !ListedNfts::<T>::contains_key(nft_id) {
+			return T::DbWeight::get().reads(1)
+		}
+		_ = T::NFTExt::unlock_nft(nft_id, MARKETPLACE_LOCK);
+
+		Self::remove_sale(nft_id).saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == MARKETPLACE_LOCK
+	}
+}
+
+impl<T: Config> OnNFTBurn<T::AccountId> for Pallet<T> {
+	fn on_nft_burn(nft_id: NFTId, _owner: &T::AccountId) -> Weight {
+		Self::remove_sale(nft_id)
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == MARKETPLACE_LOCK
+	}
+}
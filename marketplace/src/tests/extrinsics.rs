@@ -19,7 +19,7 @@ use frame_support::{assert_noop, assert_ok, error::BadOrigin, BoundedVec};
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
 use primitives::{
-	nfts::{CollectionId, NFTId, NFTState},
+	nfts::{CollectionId, NFTId, NFTLock, NFTState},
 	ConfigOp,
 };
 use sp_arithmetic::per_things::Permill;
@@ -43,6 +43,7 @@ const PERCENT_100: Permill = Permill::from_parts(1000000);
 const PERCENT_80: Permill = Permill::from_parts(800000);
 const PERCENT_50: Permill = Permill::from_parts(500000);
 const PERCENT_0: Permill = Permill::from_parts(0);
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
 
 fn origin(account: u64) -> mock::RuntimeOrigin {
 	RawOrigin::Signed(account).into()
@@ -64,7 +65,7 @@ fn prepare_tests() {
 	NFT::create_nft(
 		alice.clone(),
 		BoundedVec::default(),
-		PERCENT_0,
+		Some(PERCENT_0),
 		Some(ALICE_COLLECTION_ID),
 		false,
 	)
@@ -77,8 +78,14 @@ fn prepare_tests() {
 	NFT::create_collection(bob.clone(), BoundedVec::default(), None).unwrap();
 
 	//Create bob NFT.
-	NFT::create_nft(bob.clone(), BoundedVec::default(), PERCENT_0, Some(BOB_COLLECTION_ID), false)
-		.unwrap();
+	NFT::create_nft(
+		bob.clone(),
+		BoundedVec::default(),
+		Some(PERCENT_0),
+		Some(BOB_COLLECTION_ID),
+		false,
+	)
+	.unwrap();
 
 	// Create bob marketplace.
 	Marketplace::create_marketplace(bob, MarketplaceType::Public).unwrap();
@@ -813,7 +820,7 @@ mod list_nft {
 				Marketplace::list_nft(alice.clone(), ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10)
 					.unwrap();
 				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
-				assert_noop!(err, Error::<Test>::CannotListAlreadytListedNFTs);
+				assert_noop!(err, Error::<Test>::CannotListLockedNFTs);
 			},
 		)
 	}
@@ -826,8 +833,7 @@ mod list_nft {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 
 				// Set secret to true for Alice's NFT.
-				let nft_state =
-					NFTState::new(false, false, true, false, false, true, false, false, false);
+				let nft_state = NFTState::new(false, true, false, false, true, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
@@ -844,8 +850,7 @@ mod list_nft {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 
 				// Set delegated to true for Alice's NFT.
-				let nft_state =
-					NFTState::new(false, false, false, true, false, false, false, false, false);
+				let nft_state = NFTState::new(false, false, true, false, false, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
@@ -874,19 +879,17 @@ mod list_nft {
 	}
 
 	#[test]
-	fn cannot_list_rented_nfts() {
+	fn cannot_list_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
 			|| {
 				prepare_tests();
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 
-				// Set capsule to true for Alice's NFT.
-				let nft_state =
-					NFTState::new(false, false, false, false, false, false, true, false, false);
-				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+				// Lock Alice's NFT from another pallet.
+				NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 
 				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
-				assert_noop!(err, Error::<Test>::CannotListRentedNFTs);
+				assert_noop!(err, Error::<Test>::CannotListLockedNFTs);
 			},
 		)
 	}
@@ -899,8 +902,7 @@ mod list_nft {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 
 				// Set capsule and capsule syncing to true for Alice's NFT.
-				let nft_state =
-					NFTState::new(true, false, false, false, false, false, false, true, false);
+				let nft_state = NFTState::new(true, false, false, false, false, true);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
@@ -909,24 +911,6 @@ mod list_nft {
 		)
 	}
 
-	#[test]
-	fn cannot_list_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
-			|| {
-				prepare_tests();
-				let alice: mock::RuntimeOrigin = origin(ALICE);
-
-				// Set is_transmission to true for Alice's NFT.
-				let nft_state =
-					NFTState::new(false, false, false, false, false, false, false, false, true);
-				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-				let err = Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10);
-				assert_noop!(err, Error::<Test>::CannotListNFTsInTransmission);
-			},
-		)
-	}
-
 	#[test]
 	fn marketplace_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
@@ -1182,6 +1166,36 @@ mod buy_nft {
 		)
 	}
 
+	#[test]
+	fn buy_nft_with_nested_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let bob: mock::RuntimeOrigin = origin(BOB);
+
+				// Nest an NFT in Alice's NFT.
+				let data = BoundedVec::default();
+				NFT::create_nft(alice.clone(), data, Some(PERCENT_0), None, false).unwrap();
+				let nested_id = NFT::next_nft_id() - 1;
+				NFT::nest_nft(alice.clone(), nested_id, ALICE_NFT_ID).unwrap();
+
+				// The nested NFT cannot be listed on its own.
+				let err = Marketplace::list_nft(alice.clone(), nested_id, ALICE_MARKETPLACE_ID, 10);
+				assert_noop!(err, Error::<Test>::CannotListLockedNFTs);
+
+				// List and buy the parent NFT.
+				Marketplace::list_nft(alice, ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10).unwrap();
+				Marketplace::buy_nft(bob, ALICE_NFT_ID, 10).unwrap();
+
+				// The nested NFT moved with its parent.
+				assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, BOB);
+				assert_eq!(NFT::nfts(nested_id).unwrap().owner, BOB);
+				assert_eq!(NFT::nft_parents(nested_id), Some(ALICE_NFT_ID));
+			},
+		)
+	}
+
 	#[test]
 	fn buy_nft_flat_commission() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
@@ -1347,6 +1361,49 @@ mod buy_nft {
 		)
 	}
 
+	#[test]
+	fn buy_nft_royalty_splits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000), (DAVE, 1000)])
+			.execute_with(|| {
+				prepare_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let alice_balance = Balances::free_balance(ALICE);
+				let bob: mock::RuntimeOrigin = origin(BOB);
+				let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+				let charlie_balance = Balances::free_balance(CHARLIE);
+				let dave_balance = Balances::free_balance(DAVE);
+
+				// Set the royalty of alice's NFT and share it with dave.
+				NFT::set_royalty(alice.clone(), ALICE_NFT_ID, PERCENT_80).unwrap();
+				let splits =
+					BoundedVec::try_from(vec![(ALICE, PERCENT_50), (DAVE, PERCENT_50)]).unwrap();
+				NFT::set_royalty_splits(alice.clone(), ALICE_NFT_ID, Some(splits)).unwrap();
+
+				// Transfer the NFT to charlie, list and buy it.
+				NFT::transfer_nft(alice, ALICE_NFT_ID, CHARLIE).unwrap();
+				Marketplace::list_nft(charlie, ALICE_NFT_ID, BOB_MARKETPLACE_ID, 10).unwrap();
+				Marketplace::buy_nft(bob, ALICE_NFT_ID, 10).unwrap();
+
+				// Beneficiaries check.
+				assert_eq!(Balances::free_balance(ALICE), alice_balance + 4);
+				assert_eq!(Balances::free_balance(DAVE), dave_balance + 4);
+				// Seller check.
+				assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + 2);
+
+				// Events checks.
+				let event = MarketplaceEvent::NFTSold {
+					nft_id: ALICE_NFT_ID,
+					marketplace_id: BOB_MARKETPLACE_ID,
+					buyer: BOB,
+					listed_price: 10,
+					marketplace_cut: 0,
+					royalty_cut: 8,
+				};
+				let event = RuntimeEvent::Marketplace(event);
+				System::assert_last_event(event);
+			})
+	}
+
 	#[test]
 	fn buy_nft_flat_commission_and_royalty() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
@@ -1590,3 +1647,70 @@ mod buy_nft {
 		)
 	}
 }
+
+mod nft_hooks {
+	use super::*;
+
+	#[test]
+	fn transfer_removes_sale() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				Marketplace::list_nft(alice.clone(), ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10)
+					.unwrap();
+
+				assert_ok!(NFT::transfer_nft(alice, ALICE_NFT_ID, BOB));
+
+				// Final state checks.
+				assert_eq!(Marketplace::listed_nfts(ALICE_NFT_ID), None);
+				let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
+				assert_eq!(nft.owner, BOB);
+				assert!(!nft.state.is_locked());
+
+				// Events checks.
+				let event = MarketplaceEvent::NFTUnlisted { nft_id: ALICE_NFT_ID };
+				let event = RuntimeEvent::Marketplace(event);
+				assert!(System::events().iter().any(|x| x.event == event));
+			},
+		)
+	}
+
+	#[test]
+	fn burn_removes_sale() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				Marketplace::list_nft(alice.clone(), ALICE_NFT_ID, ALICE_MARKETPLACE_ID, 10)
+					.unwrap();
+
+				assert_ok!(NFT::burn_nft(alice, ALICE_NFT_ID));
+
+				// Final state checks.
+				assert_eq!(Marketplace::listed_nfts(ALICE_NFT_ID), None);
+				assert_eq!(NFT::nfts(ALICE_NFT_ID), None);
+
+				// Events checks.
+				let event = MarketplaceEvent::NFTUnlisted { nft_id: ALICE_NFT_ID };
+				let event = RuntimeEvent::Marketplace(event);
+				assert!(System::events().iter().any(|x| x.event == event));
+			},
+		)
+	}
+
+	#[test]
+	fn transfer_of_unlisted_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				assert_ok!(NFT::transfer_nft(alice, ALICE_NFT_ID, BOB));
+
+				// Final state checks.
+				assert_eq!(Marketplace::listed_nfts(ALICE_NFT_ID), None);
+				assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, BOB);
+			},
+		)
+	}
+}
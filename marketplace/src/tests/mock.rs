@@ -18,10 +18,12 @@ use frame_support::{
 	parameter_types,
 	traits::{ConstU32, Contains, Currency},
 };
+use primitives::nfts::NFTStoragePayment;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 
 use crate::{self as ternoa_marketplace, Config, NegativeImbalanceOf};
@@ -132,6 +134,7 @@ impl ternoa_tee::Config for Test {
 	type ClusterSize = ClusterSize;
 	type MaxUriLen = MaxUriLen;
 	type ListSizeLimit = ListSizeLimit;
+	type OnEnclaveRemoved = ();
 }
 
 parameter_types! {
@@ -143,6 +146,24 @@ parameter_types! {
 	pub const InitialSecretMintFee: Balance = SECRET_NFT_MINT_FEE;
 	pub const ShardsNumber: u32 = 5;
 	pub const InitialCapsuleMintFee: Balance = CAPSULE_MINT_FEE;
+	pub const BatchSizeLimit: u32 = 10;
+	pub const RoyaltySplitsLimit: u32 = 5;
+	pub const CollectionMintersLimit: u32 = 5;
+	pub const SimultaneousDelegationLimit: u32 = 10;
+	pub const DelegationActionsInBlockLimit: u32 = 10;
+	pub const NFTChildrenLimit: u32 = 5;
+	pub const NestingDepthLimit: u32 = 3;
+	pub const CapsuleRefundRatio: Permill = Permill::from_parts(0);
+	pub const ShardSyncTimeout: u64 = 10;
+	pub const StoragePayment: NFTStoragePayment = NFTStoragePayment::Fee;
+	pub const NFTDepositBase: u64 = 20;
+	pub const NFTDepositPerByte: u64 = 1;
+	pub const MetadataHistoryLimit: u32 = 3;
+	pub const AttributeKeyLimit: u32 = 32;
+	pub const AttributeValueLimit: u32 = 64;
+	pub const AttributeDepositBase: u64 = 10;
+	pub const AttributeDepositPerByte: u64 = 1;
+	pub const AllowlistProofLimit: u32 = 16;
 	// Marketplace parameter types
 	pub const MarketplaceInitialMintFee: Balance = MARKETPLACE_MINT_FEE;
 	pub const OffchainDataLimit: u32 = 150;
@@ -162,7 +183,31 @@ impl ternoa_nft::Config for Test {
 	type InitialSecretMintFee = InitialSecretMintFee;
 	type ShardsNumber = ShardsNumber;
 	type TEEExt = TEE;
+	type OnNFTTransfer = Marketplace;
+	type OnNFTBurn = Marketplace;
 	type InitialCapsuleMintFee = InitialCapsuleMintFee;
+	type BatchSizeLimit = BatchSizeLimit;
+	type RoyaltySplitsLimit = RoyaltySplitsLimit;
+	type CollectionMintersLimit = CollectionMintersLimit;
+	type SimultaneousDelegationLimit = SimultaneousDelegationLimit;
+	type ActionsInBlockLimit = DelegationActionsInBlockLimit;
+	type NFTChildrenLimit = NFTChildrenLimit;
+	type NestingDepthLimit = NestingDepthLimit;
+	type CapsuleRefundRatio = CapsuleRefundRatio;
+	type ShardSyncTimeout = ShardSyncTimeout;
+	type StoragePayment = StoragePayment;
+	type NFTDepositBase = NFTDepositBase;
+	type NFTDepositPerByte = NFTDepositPerByte;
+	type MetadataHistoryLimit = MetadataHistoryLimit;
+	type AttributeKeyLimit = AttributeKeyLimit;
+	type AttributeValueLimit = AttributeValueLimit;
+	type AttributeDepositBase = AttributeDepositBase;
+	type AttributeDepositPerByte = AttributeDepositPerByte;
+	type AllowlistProofLimit = AllowlistProofLimit;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
 }
 
 impl Config for Test {
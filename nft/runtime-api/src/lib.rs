@@ -0,0 +1,40 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for the NFT pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use parity_scale_codec::Codec;
+use primitives::nfts::{CollectionId, NFTId, NFTState};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait NFTApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// NFTs owned by an account, skipping the first `start` ones and returning at most
+		/// `limit`.
+		fn nfts_of(account: AccountId, start: u32, limit: u32) -> Vec<NFTId>;
+
+		/// State of an NFT.
+		fn nft_state(id: NFTId) -> Option<NFTState>;
+
+		/// NFTs in a collection.
+		fn collection_nfts(id: CollectionId) -> Option<Vec<NFTId>>;
+	}
+}
@@ -21,9 +21,9 @@ use crate::Pallet as NFT;
 use frame_benchmarking::{account as benchmark_account, benchmarks, impl_benchmark_test_suite};
 use frame_support::{assert_ok, traits::Currency, BoundedVec};
 use frame_system::RawOrigin;
-use primitives::nfts::NFTState;
+use primitives::nfts::{CollectionMintConfig, MerkleHash, NFTState, NFTVoucher};
 use sp_arithmetic::per_things::Permill;
-use sp_runtime::traits::{Bounded, StaticLookup};
+use sp_runtime::traits::{BlakeTwo256, Bounded, Hash as HashT, StaticLookup};
 use sp_std::prelude::*;
 use ternoa_common::traits::{NFTExt, TEEExt};
 
@@ -33,6 +33,7 @@ pub struct BenchmarkData {
 }
 
 const PERCENT_100: Permill = Permill::from_parts(1000000);
+const PERCENT_0: Permill = Permill::from_parts(0);
 
 pub fn get_account<T: Config>(name: &'static str) -> T::AccountId {
 	let account: T::AccountId = benchmark_account(name, 0, 0);
@@ -43,6 +44,52 @@ pub fn origin<T: Config>(name: &'static str) -> RawOrigin<T::AccountId> {
 	RawOrigin::Signed(get_account::<T>(name))
 }
 
+pub fn mint_config<T: Config>() -> CollectionMintConfig<T::AccountId, BalanceOf<T>, T::BlockNumber>
+{
+	let payout: T::AccountId = get_account::<T>("PAYOUT");
+	T::Currency::make_free_balance_be(&payout, T::Currency::minimum_balance());
+	CollectionMintConfig {
+		price: T::Currency::minimum_balance(),
+		start_block: None,
+		end_block: None,
+		max_per_account: Some(1),
+		payout: Some(payout),
+		allowlist_until: None,
+	}
+}
+
+pub fn create_alice_nft<T: Config>() -> NFTId {
+	let nft_offchain_data =
+		BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+	assert_ok!(NFT::<T>::create_nft(
+		origin::<T>("ALICE").into(),
+		nft_offchain_data,
+		Some(PERCENT_100),
+		None,
+		false,
+	));
+	NFT::<T>::next_nft_id() - 1
+}
+
+/// Nest `depth` new NFTs one in another under `root_id` and return the deepest one.
+pub fn create_nested_chain<T: Config>(root_id: NFTId, depth: u32) -> NFTId {
+	let mut parent_id = root_id;
+	for _ in 0..depth {
+		let nft_id = create_alice_nft::<T>();
+		assert_ok!(NFT::<T>::nest_nft(origin::<T>("ALICE").into(), nft_id, parent_id));
+		parent_id = nft_id;
+	}
+	parent_id
+}
+
+/// Attribute key and value of the maximum length.
+pub fn attribute<T: Config>(
+) -> (U8BoundedVec<T::AttributeKeyLimit>, U8BoundedVec<T::AttributeValueLimit>) {
+	let key = BoundedVec::try_from(vec![1; T::AttributeKeyLimit::get() as usize]).unwrap();
+	let value = BoundedVec::try_from(vec![1; T::AttributeValueLimit::get() as usize]).unwrap();
+	(key, value)
+}
+
 pub fn prepare_benchmarks<T: Config>() -> BenchmarkData {
 	let alice: T::AccountId = get_account::<T>("ALICE");
 	let bob: T::AccountId = get_account::<T>("BOB");
@@ -60,7 +107,7 @@ pub fn prepare_benchmarks<T: Config>() -> BenchmarkData {
 	assert_ok!(NFT::<T>::create_nft(
 		origin::<T>("ALICE").into(),
 		nft_offchain_data,
-		PERCENT_100,
+		Some(PERCENT_100),
 		None,
 		false,
 	));
@@ -76,38 +123,45 @@ pub fn prepare_benchmarks<T: Config>() -> BenchmarkData {
 	}
 }
 
+/// Royalty splits with the maximum number of beneficiaries.
+pub fn royalty_splits<T: Config>() -> RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit> {
+	let limit = T::RoyaltySplitsLimit::get();
+	let share = PERCENT_100.deconstruct() / limit;
+	let splits: Vec<(T::AccountId, Permill)> = (0..limit)
+		.map(|i| {
+			// The first beneficiary takes the rounding leftover.
+			let parts =
+				if i == 0 { PERCENT_100.deconstruct() - share * (limit - 1) } else { share };
+			(benchmark_account("beneficiary", i, 0), Permill::from_parts(parts))
+		})
+		.collect();
+	BoundedVec::try_from(splits).unwrap()
+}
+
 benchmarks! {
 	create_nft {
-		let s in 0 .. T::CollectionSizeLimit::get() - 1;
 		let benchmark_data = prepare_benchmarks::<T>();
-		let alice: T::AccountId = get_account::<T>("ALICE");
 		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
-		// Fill the collection.
-		NFT::<T>::create_filled_collection(alice.clone(), benchmark_data.collection_id, 0, s).unwrap();
-	}: _(origin::<T>("ALICE"), nft_offchain_data, PERCENT_100, Some(benchmark_data.collection_id), false)
+	}: _(origin::<T>("ALICE"), nft_offchain_data, Some(PERCENT_100), Some(benchmark_data.collection_id), false)
 	verify {
 		// Get The NFT id.
 		let nft_id = NFT::<T>::next_nft_id() - 1;
 		// Get The NFT.
 		let nft = NFT::<T>::nfts(nft_id).unwrap();
 		assert_eq!(nft.owner, alice);
-		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().nfts.contains(&nft_id), true);
+		assert_eq!(NFT::<T>::collection_nfts_of(benchmark_data.collection_id, nft_id).is_some(), true);
 		assert_eq!(nft.collection_id, Some(benchmark_data.collection_id));
 	}
 
 	burn_nft {
-		let s in 0 .. T::CollectionSizeLimit::get() - 1;
 		let benchmark_data = prepare_benchmarks::<T>();
 		let alice = origin::<T>("ALICE");
-		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
-		// Fill the collection.
-		NFT::<T>::create_filled_collection(get_account::<T>("ALICE"), benchmark_data.collection_id, benchmark_data.nft_id + 1, s).unwrap();
 		// Add NFT to collection.
 		NFT::<T>::add_nft_to_collection(alice.clone().into(), benchmark_data.nft_id, benchmark_data.collection_id).unwrap();
 	}: _(alice, benchmark_data.nft_id)
 	verify {
 		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id), None);
-		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().nfts.contains(&benchmark_data.nft_id), false);
+		assert_eq!(NFT::<T>::collection_nfts_of(benchmark_data.collection_id, benchmark_data.nft_id).is_some(), false);
 	}
 
 	transfer_nft {
@@ -121,11 +175,13 @@ benchmarks! {
 	}
 
 	delegate_nft {
+		let s in 0 .. T::SimultaneousDelegationLimit::get() - 1;
 		let benchmark_data = prepare_benchmarks::<T>();
 		let alice = origin::<T>("ALICE");
 		let bob: T::AccountId = get_account::<T>("BOB");
 		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
-	}: _(alice, benchmark_data.nft_id, Some(bob_lookup))
+		NFT::<T>::fill_delegation_queue(s, benchmark_data.nft_id + 1, 100u32.into()).unwrap();
+	}: _(alice, benchmark_data.nft_id, Some(bob_lookup), Some(10u32.into()))
 	verify {
 		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().state.is_delegated, true);
 		assert_eq!(NFT::<T>::delegated_nfts(benchmark_data.nft_id), Some(bob));
@@ -192,7 +248,7 @@ benchmarks! {
 	}: _(alice, benchmark_data.nft_id, benchmark_data.collection_id)
 	verify {
 		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().collection_id, Some(benchmark_data.collection_id));
-		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().nfts.contains(&benchmark_data.nft_id), true);
+		assert_eq!(NFT::<T>::collection_nfts_of(benchmark_data.collection_id, benchmark_data.nft_id).is_some(), true);
 	}
 
 	create_secret_nft {
@@ -203,7 +259,7 @@ benchmarks! {
 		let secret_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
 		// Fill the collection.
 		NFT::<T>::create_filled_collection(alice.clone(), benchmark_data.collection_id, 0, s).unwrap();
-	}: _(origin::<T>("ALICE"), nft_offchain_data, secret_offchain_data, PERCENT_100, Some(benchmark_data.collection_id), false)
+	}: _(origin::<T>("ALICE"), nft_offchain_data, secret_offchain_data, Some(PERCENT_100), Some(benchmark_data.collection_id), false)
 	verify {
 		// Get The NFT id.
 		let nft_id = NFT::<T>::next_nft_id() - 1;
@@ -212,7 +268,7 @@ benchmarks! {
 		// Get the secret offchain_data
 		let secret_offchain_data = NFT::<T>::secret_nfts_offchain_data(nft_id);
 		assert_eq!(nft.owner, alice);
-		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().nfts.contains(&nft_id), true);
+		assert_eq!(NFT::<T>::collection_nfts_of(benchmark_data.collection_id, nft_id).is_some(), true);
 		assert_eq!(nft.collection_id, Some(benchmark_data.collection_id));
 		assert_eq!(nft.state.is_secret, true);
 		assert_eq!(nft.state.is_syncing_secret, true);
@@ -281,7 +337,7 @@ benchmarks! {
 		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
 		// Fill the collection.
 		NFT::<T>::create_filled_collection(alice.clone(), benchmark_data.collection_id, 0, s).unwrap();
-	}: _(origin::<T>("ALICE"), nft_offchain_data, capsule_offchain_data, PERCENT_100, Some(benchmark_data.collection_id), false)
+	}: _(origin::<T>("ALICE"), nft_offchain_data, capsule_offchain_data, Some(PERCENT_100), Some(benchmark_data.collection_id), false)
 	verify {
 		// Get The NFT id.
 		let nft_id = NFT::<T>::next_nft_id() - 1;
@@ -290,40 +346,37 @@ benchmarks! {
 		// Get the capsule offchain_data
 		let capsule_offchain_data = NFT::<T>::capsule_offchain_data(nft_id);
 		assert_eq!(nft.owner, alice);
-		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().nfts.contains(&nft_id), true);
+		assert_eq!(NFT::<T>::collection_nfts_of(benchmark_data.collection_id, nft_id).is_some(), true);
 		assert_eq!(nft.collection_id, Some(benchmark_data.collection_id));
 		assert_eq!(nft.state.is_capsule, true);
 		assert_eq!(nft.state.is_syncing_capsule, true);
 		assert!(capsule_offchain_data.is_some());
 	}
 
-	// TODO: add back when we can revert capsule
-	// revert_capsule {
-	// 	let benchmark_data = prepare_benchmarks::<T>();
-	// 	let alice_origin = origin::<T>("ALICE");
-	// 	let bob_origin = origin::<T>("BOB");
-	// 	let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
-	// 	NFT::<T>::convert_to_capsule(alice_origin.clone().into(), benchmark_data.nft_id, capsule_offchain_data).unwrap();
-	// 	T::TEEExt::register_and_assign_enclave(get_account::<T>("ALICE"), get_account::<T>("ALICE"), None).unwrap();
-	// 	T::TEEExt::register_and_assign_enclave(get_account::<T>("BOB"), get_account::<T>("BOB"), None).unwrap();
-	// 	NFT::<T>::add_capsule_shard(alice_origin.into(), benchmark_data.nft_id).unwrap();
-	// 	NFT::<T>::add_capsule_shard(bob_origin.into(), benchmark_data.nft_id).unwrap();
-	// }: _(origin::<T>("ALICE"), benchmark_data.nft_id)
-	// verify {
-	// 	// Get The NFT.
-	// 	let nft = NFT::<T>::nfts(benchmark_data.nft_id).unwrap();
-	// 	let capsule_offchain_data = NFT::<T>::capsule_offchain_data(benchmark_data.nft_id);
-	// 	assert_eq!(nft.state.is_capsule, false);
-	// 	assert_eq!(nft.state.is_syncing_capsule, false);
-	// 	assert!(capsule_offchain_data.is_none());
-	// }
+	revert_capsule {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice_origin = origin::<T>("ALICE");
+		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::convert_to_capsule(alice_origin.into(), benchmark_data.nft_id, capsule_offchain_data).unwrap();
+		let state = NFTState::new(true, false, false, false, false, false);
+		NFT::<T>::set_nft_state(benchmark_data.nft_id, state).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
+	verify {
+		// Get The NFT.
+		let nft = NFT::<T>::nfts(benchmark_data.nft_id).unwrap();
+		let capsule_offchain_data = NFT::<T>::capsule_offchain_data(benchmark_data.nft_id);
+		assert_eq!(nft.state.is_capsule, false);
+		assert_eq!(nft.state.is_syncing_capsule, false);
+		assert!(capsule_offchain_data.is_none());
+		assert!(NFT::<T>::capsule_deposits(benchmark_data.nft_id).is_none());
+	}
 
 	set_capsule_offchaindata {
 		let benchmark_data = prepare_benchmarks::<T>();
 		let alice_origin = origin::<T>("ALICE");
 		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
 		NFT::<T>::convert_to_capsule(alice_origin.into(), benchmark_data.nft_id, capsule_offchain_data.clone()).unwrap();
-		let state = NFTState::new(true, false, false, false, false, false, false, false, false);
+		let state = NFTState::new(true, false, false, false, false, false);
 		NFT::<T>::set_nft_state(benchmark_data.nft_id, state).unwrap();
 	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, capsule_offchain_data)
 	verify {
@@ -366,7 +419,7 @@ benchmarks! {
 		let alice_origin = origin::<T>("ALICE");
 		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
 		NFT::<T>::convert_to_capsule(alice_origin.into(), benchmark_data.nft_id, capsule_offchain_data).unwrap();
-		let state = NFTState::new(true, false, false, false, false, false, false, false, false);
+		let state = NFTState::new(true, false, false, false, false, false);
 		NFT::<T>::set_nft_state(benchmark_data.nft_id, state).unwrap();
 	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
 	verify {
@@ -385,6 +438,501 @@ benchmarks! {
 		let collection = NFT::<T>::collections(benchmark_data.collection_id).unwrap();
 		assert_eq!(collection.offchain_data, collection_offchain_data);
 	}
+
+	approve_transfer {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, bob_lookup, None)
+	verify {
+		assert_eq!(NFT::<T>::nft_approvals(benchmark_data.nft_id), Some((bob, None)));
+	}
+
+	cancel_approval {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob);
+		NFT::<T>::approve_transfer(origin::<T>("ALICE").into(), benchmark_data.nft_id, bob_lookup, None).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
+	verify {
+		assert_eq!(NFT::<T>::nft_approvals(benchmark_data.nft_id), None);
+	}
+
+	set_approval_for_all {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+	}: _(origin::<T>("ALICE"), bob_lookup, true)
+	verify {
+		assert_eq!(NFT::<T>::operator_approvals(alice, bob), Some(()));
+	}
+
+	transfer_from {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+		NFT::<T>::approve_transfer(origin::<T>("ALICE").into(), benchmark_data.nft_id, bob_lookup.clone(), None).unwrap();
+	}: _(origin::<T>("BOB"), benchmark_data.nft_id, bob_lookup)
+	verify {
+		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().owner, bob);
+		assert_eq!(NFT::<T>::nft_approvals(benchmark_data.nft_id), None);
+	}
+
+	create_nfts_batch {
+		let s in 1 .. T::BatchSizeLimit::get().min(T::CollectionSizeLimit::get());
+		let benchmark_data = prepare_benchmarks::<T>();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		let nfts = BoundedVec::try_from(vec![(nft_offchain_data, Some(PERCENT_100), false); s as usize]).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, nfts, BatchMode::AllOrNothing)
+	verify {
+		let collection = NFT::<T>::collections(benchmark_data.collection_id).unwrap();
+		assert_eq!(collection.nfts_count, s);
+	}
+
+	transfer_nfts_batch {
+		let s in 1 .. T::BatchSizeLimit::get();
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let mut transfers = Vec::new();
+		for _ in 0 .. s {
+			let nft_id = <NFT<T> as NFTExt>::create_nft(alice.clone(), BoundedVec::default(), PERCENT_100, None, false).unwrap();
+			transfers.push((nft_id, T::Lookup::unlookup(bob.clone())));
+		}
+		let transfers = BoundedVec::try_from(transfers).unwrap();
+	}: _(origin::<T>("ALICE"), transfers, BatchMode::AllOrNothing)
+	verify {
+		assert_eq!(NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap().owner, bob);
+	}
+
+	set_royalty_splits {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let splits = royalty_splits::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, Some(splits.clone()))
+	verify {
+		assert_eq!(NFT::<T>::nft_royalty_splits(benchmark_data.nft_id), Some(splits));
+	}
+
+	set_collection_royalty_splits {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let splits = royalty_splits::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, Some(splits.clone()))
+	verify {
+		assert_eq!(NFT::<T>::collection_royalty_splits(benchmark_data.collection_id), Some(splits));
+	}
+
+	set_collection_royalty {
+		let benchmark_data = prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, Some(PERCENT_0), Some(PERCENT_100))
+	verify {
+		let collection = NFT::<T>::collections(benchmark_data.collection_id).unwrap();
+		assert_eq!(collection.royalty, Some(PERCENT_0));
+		assert_eq!(collection.max_royalty, Some(PERCENT_100));
+	}
+
+	add_minter {
+		let s in 0 .. T::CollectionMintersLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		for i in 0 .. s {
+			let minter: T::AccountId = benchmark_account("minter", i, 0);
+			NFT::<T>::add_minter(origin::<T>("ALICE").into(), benchmark_data.collection_id, T::Lookup::unlookup(minter)).unwrap();
+		}
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, bob_lookup)
+	verify {
+		assert!(NFT::<T>::collection_minters(benchmark_data.collection_id).contains(&bob));
+	}
+
+	remove_minter {
+		let s in 1 .. T::CollectionMintersLimit::get();
+		let benchmark_data = prepare_benchmarks::<T>();
+		for i in 0 .. s {
+			let minter: T::AccountId = benchmark_account("minter", i, 0);
+			NFT::<T>::add_minter(origin::<T>("ALICE").into(), benchmark_data.collection_id, T::Lookup::unlookup(minter)).unwrap();
+		}
+		let minter: T::AccountId = benchmark_account("minter", s - 1, 0);
+		let minter_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(minter.clone());
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, minter_lookup)
+	verify {
+		assert!(!NFT::<T>::collection_minters(benchmark_data.collection_id).contains(&minter));
+	}
+
+	set_minter_attribution {
+		let benchmark_data = prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, MinterAttribution::CollectionOwner)
+	verify {
+		assert_eq!(NFT::<T>::minter_attributions(benchmark_data.collection_id), MinterAttribution::CollectionOwner);
+	}
+
+	create_nft_for {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+		NFT::<T>::add_minter(origin::<T>("ALICE").into(), benchmark_data.collection_id, bob_lookup.clone()).unwrap();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice.clone(), benchmark_data.collection_id, 0, s).unwrap();
+	}: _(origin::<T>("BOB"), T::Lookup::unlookup(alice.clone()), nft_offchain_data, Some(PERCENT_100), benchmark_data.collection_id, false)
+	verify {
+		let nft = NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap();
+		assert_eq!(nft.owner, alice);
+		assert_eq!(nft.creator, bob);
+	}
+
+	set_mint_config {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let config = mint_config::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, Some(config.clone()))
+	verify {
+		assert_eq!(NFT::<T>::collection_mint_configs(benchmark_data.collection_id), Some(config));
+	}
+
+	set_mint_allowlist {
+		let s in 1 .. T::BatchSizeLimit::get();
+		let benchmark_data = prepare_benchmarks::<T>();
+		let accounts: Vec<T::AccountId> = (0..s).map(|i| benchmark_account("ALLOWED", i, 0)).collect();
+		let accounts: BoundedVec<T::AccountId, T::BatchSizeLimit> = BoundedVec::try_from(accounts).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, accounts.clone(), true)
+	verify {
+		for account in accounts {
+			assert!(NFT::<T>::mint_allowlist(benchmark_data.collection_id, account).is_some());
+		}
+	}
+
+	public_mint {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		NFT::<T>::set_mint_config(origin::<T>("ALICE").into(), benchmark_data.collection_id, Some(mint_config::<T>())).unwrap();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice, benchmark_data.collection_id, 0, s).unwrap();
+	}: _(origin::<T>("BOB"), benchmark_data.collection_id, nft_offchain_data)
+	verify {
+		let nft = NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap();
+		assert_eq!(nft.owner, bob.clone());
+		assert_eq!(NFT::<T>::public_mints(benchmark_data.collection_id, bob), 1);
+	}
+
+	set_allowlist_root {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let root = Some([1; 32]);
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, root)
+	verify {
+		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().allowlist_root, root);
+	}
+
+	allowlist_mint {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		// Build an allowlist with Bob's leaf and a full-length proof.
+		let proof: Vec<MerkleHash> = (0..T::AllowlistProofLimit::get()).map(|i| [i as u8; 32]).collect();
+		let leaf = NFT::<T>::allowlist_leaf(&bob, 1);
+		let root = proof.iter().fold(leaf, |node, sibling| {
+			if node <= *sibling {
+				BlakeTwo256::hash(&[node, *sibling].concat()).into()
+			} else {
+				BlakeTwo256::hash(&[*sibling, node].concat()).into()
+			}
+		});
+		let proof: BoundedVec<MerkleHash, T::AllowlistProofLimit> = BoundedVec::try_from(proof).unwrap();
+		NFT::<T>::set_allowlist_root(origin::<T>("ALICE").into(), benchmark_data.collection_id, Some(root)).unwrap();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice, benchmark_data.collection_id, 0, s).unwrap();
+	}: _(origin::<T>("BOB"), benchmark_data.collection_id, nft_offchain_data, 1, proof)
+	verify {
+		let nft = NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap();
+		assert_eq!(nft.owner, bob.clone());
+		assert_eq!(NFT::<T>::allowlist_claims(benchmark_data.collection_id, bob), 1);
+	}
+
+	redeem_voucher {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let creator = T::BenchmarkHelper::signer();
+		T::Currency::make_free_balance_be(&creator, T::Currency::minimum_balance());
+		// Fill the creator's collection.
+		NFT::<T>::create_filled_collection(creator.clone(), benchmark_data.collection_id, 0, s).unwrap();
+		let voucher = NFTVoucher {
+			creator: creator.clone(),
+			offchain_data: BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap(),
+			royalty: Some(PERCENT_100),
+			collection_id: Some(benchmark_data.collection_id),
+			price: T::Currency::minimum_balance(),
+			expiry: T::BlockNumber::max_value(),
+			nonce: 0,
+		};
+		let signature = T::BenchmarkHelper::sign(&NFT::<T>::voucher_signing_payload(&voucher));
+	}: _(origin::<T>("BOB"), voucher, signature)
+	verify {
+		let nft = NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap();
+		assert_eq!(nft.owner, bob);
+		assert_eq!(nft.creator, creator.clone());
+		assert!(NFT::<T>::redeemed_vouchers(creator, 0).is_some());
+	}
+
+	issue_soulbound {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice.clone(), benchmark_data.collection_id, 0, s).unwrap();
+	}: _(origin::<T>("ALICE"), T::Lookup::unlookup(bob.clone()), nft_offchain_data, Some(benchmark_data.collection_id))
+	verify {
+		let nft = NFT::<T>::nfts(NFT::<T>::next_nft_id() - 1).unwrap();
+		assert_eq!(nft.owner, bob);
+		assert_eq!(nft.creator, alice);
+		assert!(nft.state.is_soulbound);
+	}
+
+	revoke_soulbound {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let nft_id = NFT::<T>::next_nft_id();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice, benchmark_data.collection_id, nft_id + 1, s).unwrap();
+		// Issue a soulbound NFT to Bob.
+		NFT::<T>::issue_soulbound(origin::<T>("ALICE").into(), T::Lookup::unlookup(bob), nft_offchain_data, Some(benchmark_data.collection_id)).unwrap();
+	}: _(origin::<T>("ALICE"), nft_id)
+	verify {
+		assert!(NFT::<T>::nfts(nft_id).is_none());
+	}
+
+	reject_soulbound {
+		let s in 0 .. T::CollectionSizeLimit::get() - 1;
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let nft_id = NFT::<T>::next_nft_id();
+		let nft_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		// Fill the collection.
+		NFT::<T>::create_filled_collection(alice, benchmark_data.collection_id, nft_id + 1, s).unwrap();
+		// Issue a soulbound NFT to Bob.
+		NFT::<T>::issue_soulbound(origin::<T>("ALICE").into(), T::Lookup::unlookup(bob), nft_offchain_data, Some(benchmark_data.collection_id)).unwrap();
+	}: _(origin::<T>("BOB"), nft_id)
+	verify {
+		assert!(NFT::<T>::nfts(nft_id).is_none());
+	}
+
+	nest_nft {
+		let benchmark_data = prepare_benchmarks::<T>();
+		// Build the deepest chain of nested NFTs.
+		let parent_id = create_nested_chain::<T>(benchmark_data.nft_id, T::NestingDepthLimit::get() - 1);
+		let nft_id = create_alice_nft::<T>();
+	}: _(origin::<T>("ALICE"), nft_id, parent_id)
+	verify {
+		assert_eq!(NFT::<T>::nft_parents(nft_id), Some(parent_id));
+	}
+
+	unnest_nft {
+		let benchmark_data = prepare_benchmarks::<T>();
+		// Build the deepest chain of nested NFTs.
+		let nft_id = create_nested_chain::<T>(benchmark_data.nft_id, T::NestingDepthLimit::get());
+	}: _(origin::<T>("ALICE"), nft_id)
+	verify {
+		assert_eq!(NFT::<T>::nft_parents(nft_id), None);
+	}
+
+	set_nft_attribute {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let (key, value) = attribute::<T>();
+		let namespace = AttributeNamespace::Owner;
+		// Worst case: an existing attribute is replaced.
+		assert_ok!(NFT::<T>::set_nft_attribute(origin::<T>("ALICE").into(), benchmark_data.nft_id, namespace, key.clone(), value.clone()));
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, namespace, key.clone(), value.clone())
+	verify {
+		assert_eq!(NFT::<T>::nft_attributes(benchmark_data.nft_id, (namespace, key)).map(|x| x.0), Some(value));
+	}
+
+	clear_nft_attribute {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let (key, value) = attribute::<T>();
+		let namespace = AttributeNamespace::Owner;
+		assert_ok!(NFT::<T>::set_nft_attribute(origin::<T>("ALICE").into(), benchmark_data.nft_id, namespace, key.clone(), value));
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, namespace, key.clone())
+	verify {
+		assert_eq!(NFT::<T>::nft_attributes(benchmark_data.nft_id, (namespace, key)), None);
+	}
+
+	set_collection_attribute {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let (key, value) = attribute::<T>();
+		// Worst case: an existing attribute is replaced.
+		assert_ok!(NFT::<T>::set_collection_attribute(origin::<T>("ALICE").into(), benchmark_data.collection_id, key.clone(), value.clone()));
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, key.clone(), value.clone())
+	verify {
+		let attribute_key = (AttributeNamespace::CollectionOwner, key);
+		assert_eq!(NFT::<T>::collection_attributes(benchmark_data.collection_id, attribute_key).map(|x| x.0), Some(value));
+	}
+
+	clear_collection_attribute {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let (key, value) = attribute::<T>();
+		assert_ok!(NFT::<T>::set_collection_attribute(origin::<T>("ALICE").into(), benchmark_data.collection_id, key.clone(), value));
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, key.clone())
+	verify {
+		let attribute_key = (AttributeNamespace::CollectionOwner, key);
+		assert_eq!(NFT::<T>::collection_attributes(benchmark_data.collection_id, attribute_key), None);
+	}
+
+	lock_collection_attributes {
+		let benchmark_data = prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id)
+	verify {
+		assert_eq!(NFT::<T>::locked_collection_attributes(benchmark_data.collection_id), Some(()));
+	}
+
+	set_nft_offchaindata {
+		let benchmark_data = prepare_benchmarks::<T>();
+		// Fill the history so that the oldest hash is dropped.
+		let offchain_data: U8BoundedVec<T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![2; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		for _ in 0..T::MetadataHistoryLimit::get() {
+			assert_ok!(NFT::<T>::set_nft_offchaindata(origin::<T>("ALICE").into(), benchmark_data.nft_id, offchain_data.clone()));
+		}
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, offchain_data.clone())
+	verify {
+		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().offchain_data, offchain_data);
+	}
+
+	set_collection_metadata_mutability {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let mutability = MetadataMutability::CollectionOwner;
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, mutability)
+	verify {
+		assert_eq!(NFT::<T>::collection_metadata_mutability(benchmark_data.collection_id), mutability);
+	}
+
+	freeze_nft_metadata {
+		let benchmark_data = prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
+	verify {
+		assert_eq!(NFT::<T>::frozen_nft_metadata(benchmark_data.nft_id), Some(()));
+	}
+
+	freeze_collection_metadata {
+		let benchmark_data = prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id)
+	verify {
+		assert_eq!(NFT::<T>::frozen_collection_metadata(benchmark_data.collection_id), Some(()));
+	}
+
+	retry_secret_sync {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice = origin::<T>("ALICE");
+		let secret_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::add_secret(alice.into(), benchmark_data.nft_id, secret_offchain_data).unwrap();
+		let cluster_id = T::TEEExt::create_cluster();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::ShardSyncTimeout::get());
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, cluster_id)
+	verify {
+		assert_eq!(NFT::<T>::secret_syncs(benchmark_data.nft_id).unwrap().1, Some(cluster_id));
+	}
+
+	cancel_secret_sync {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice = origin::<T>("ALICE");
+		let secret_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::add_secret(alice.into(), benchmark_data.nft_id, secret_offchain_data).unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::ShardSyncTimeout::get());
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
+	verify {
+		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().state.is_secret, false);
+	}
+
+	retry_capsule_sync {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice = origin::<T>("ALICE");
+		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::convert_to_capsule(alice.into(), benchmark_data.nft_id, capsule_offchain_data).unwrap();
+		let cluster_id = T::TEEExt::create_cluster();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::ShardSyncTimeout::get());
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, cluster_id)
+	verify {
+		assert_eq!(NFT::<T>::capsule_syncs(benchmark_data.nft_id).unwrap().1, Some(cluster_id));
+	}
+
+	cancel_capsule_sync {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice = origin::<T>("ALICE");
+		let capsule_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::convert_to_capsule(alice.into(), benchmark_data.nft_id, capsule_offchain_data).unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::ShardSyncTimeout::get());
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id)
+	verify {
+		assert_eq!(NFT::<T>::nfts(benchmark_data.nft_id).unwrap().state.is_capsule, false);
+	}
+
+	transfer_collection {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, bob_lookup)
+	verify {
+		assert_eq!(NFT::<T>::collection_transfers(benchmark_data.collection_id), Some(bob));
+	}
+
+	accept_collection_transfer {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+		NFT::<T>::transfer_collection(origin::<T>("ALICE").into(), benchmark_data.collection_id, bob_lookup).unwrap();
+	}: _(origin::<T>("BOB"), benchmark_data.collection_id)
+	verify {
+		assert_eq!(NFT::<T>::collections(benchmark_data.collection_id).unwrap().owner, bob);
+	}
+
+	cancel_collection_transfer {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+		NFT::<T>::transfer_collection(origin::<T>("ALICE").into(), benchmark_data.collection_id, bob_lookup).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id)
+	verify {
+		assert_eq!(NFT::<T>::collection_transfers(benchmark_data.collection_id), None);
+	}
+
+	set_collection_roles {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let bob_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(bob.clone());
+	}: _(origin::<T>("ALICE"), benchmark_data.collection_id, Some(bob_lookup.clone()), Some(bob_lookup))
+	verify {
+		assert_eq!(NFT::<T>::collection_admins(benchmark_data.collection_id), Some(bob.clone()));
+		assert_eq!(NFT::<T>::collection_freezers(benchmark_data.collection_id), Some(bob));
+	}
+
+	rotate_secret {
+		let benchmark_data = prepare_benchmarks::<T>();
+		let alice_origin = origin::<T>("ALICE");
+		let secret_offchain_data: BoundedVec<u8, T::NFTOffchainDataLimit> = BoundedVec::try_from(vec![1; T::NFTOffchainDataLimit::get() as usize]).unwrap();
+		NFT::<T>::add_secret(alice_origin.into(), benchmark_data.nft_id, secret_offchain_data.clone()).unwrap();
+		let state = NFTState::new(false, true, false, false, false, false);
+		NFT::<T>::set_nft_state(benchmark_data.nft_id, state).unwrap();
+	}: _(origin::<T>("ALICE"), benchmark_data.nft_id, Some(secret_offchain_data))
+	verify {
+		// Get The NFT.
+		let nft = NFT::<T>::nfts(benchmark_data.nft_id).unwrap();
+		assert_eq!(nft.state.is_secret, true);
+		assert_eq!(nft.state.is_syncing_secret, true);
+	}
 }
 
 impl_benchmark_test_suite!(NFT, crate::tests::mock::new_test_ext(), crate::tests::mock::Test);
@@ -0,0 +1,213 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementations of the `nonfungibles` traits for the NFT pallet.
+//!
+//! Collections are mapped to `CollectionId` and items to `NFTId`. An item is only reachable
+//! through the collection it belongs to, NFTs without a collection are not exposed.
+
+use super::*;
+use frame_support::{
+	dispatch::DispatchError,
+	traits::tokens::nonfungibles::{Create, Destroy, Inspect, InspectEnumerable, Mutate, Transfer},
+};
+
+impl<T: Config> Pallet<T> {
+	/// Get an NFT only if it belongs to the given collection.
+	fn get_nft_in_collection(
+		collection: &CollectionId,
+		item: &NFTId,
+	) -> Option<NFTData<T::AccountId, T::NFTOffchainDataLimit>> {
+		Nfts::<T>::get(item).filter(|x| x.collection_id == Some(*collection))
+	}
+}
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type ItemId = NFTId;
+	type CollectionId = CollectionId;
+
+	fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<T::AccountId> {
+		Self::get_nft_in_collection(collection, item).map(|x| x.owner)
+	}
+
+	fn collection_owner(collection: &Self::CollectionId) -> Option<T::AccountId> {
+		Collections::<T>::get(collection).map(|x| x.owner)
+	}
+
+	fn can_transfer(collection: &Self::CollectionId, item: &Self::ItemId) -> bool {
+		Self::get_nft_in_collection(collection, item)
+			.map_or(false, |x| x.can_transfer_releasing(T::OnNFTTransfer::releases_lock).is_ok())
+	}
+}
+
+impl<T: Config> InspectEnumerable<T::AccountId> for Pallet<T> {
+	fn collections() -> Box<dyn Iterator<Item = Self::CollectionId>> {
+		Box::new(Collections::<T>::iter_keys())
+	}
+
+	fn items(collection: &Self::CollectionId) -> Box<dyn Iterator<Item = Self::ItemId>> {
+		Box::new(Self::collection_nft_ids(*collection).into_iter())
+	}
+
+	fn owned(who: &T::AccountId) -> Box<dyn Iterator<Item = (Self::CollectionId, Self::ItemId)>> {
+		Box::new(
+			AccountNfts::<T>::iter_key_prefix(who).filter_map(|id| {
+				Nfts::<T>::get(id).and_then(|nft| nft.collection_id).map(|x| (x, id))
+			}),
+		)
+	}
+
+	fn owned_in_collection(
+		collection: &Self::CollectionId,
+		who: &T::AccountId,
+	) -> Box<dyn Iterator<Item = Self::ItemId>> {
+		let who = who.clone();
+		Box::new(
+			Self::collection_nft_ids(*collection)
+				.into_iter()
+				.filter(move |id| Nfts::<T>::get(id).map_or(false, |x| x.owner == who)),
+		)
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		let sender = Nfts::<T>::try_mutate(item, |x| -> Result<T::AccountId, DispatchError> {
+			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.collection_id == Some(*collection), Error::<T>::NFTNotFoundInCollection);
+			ensure!(nft.owner != *destination, Error::<T>::CannotTransferNFTsToYourself);
+			nft.can_transfer_releasing(T::OnNFTTransfer::releases_lock)
+				.map_err(Self::transfer_restriction_error)?;
+
+			// Execute
+			Ok(sp_std::mem::replace(&mut nft.owner, destination.clone()))
+		})?;
+		Self::handle_owner_change(*item, Some(&sender), Some(destination));
+		Self::notify_nft_transfer(*item, &sender, destination);
+
+		let event = Event::NFTTransferred { nft_id: *item, sender, recipient: destination.clone() };
+		Self::deposit_event(event);
+
+		Ok(())
+	}
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	/// Mint an NFT in a collection on behalf of its owner, who is recorded as the creator. The
+	/// item must be the next available NFT id and `who` pays the NFT mint fee or storage deposit.
+	fn mint_into(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		ensure!(*item == NextNFTId::<T>::get(), Error::<T>::InvalidNFTId);
+		let data = Collections::<T>::get(collection).ok_or(Error::<T>::CollectionNotFound)?;
+
+		Self::do_create_nft(
+			data.owner,
+			who.clone(),
+			who,
+			U8BoundedVec::default(),
+			None,
+			Some(*collection),
+			false,
+		)?;
+
+		Ok(())
+	}
+
+	fn burn(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		maybe_check_owner: Option<&T::AccountId>,
+	) -> DispatchResult {
+		let nft = Nfts::<T>::get(item).ok_or(Error::<T>::NFTNotFound)?;
+
+		// Checks
+		ensure!(nft.collection_id == Some(*collection), Error::<T>::NFTNotFoundInCollection);
+		if let Some(owner) = maybe_check_owner {
+			ensure!(nft.owner == *owner, Error::<T>::NotTheNFTOwner);
+		}
+		nft.can_burn_releasing(T::OnNFTBurn::releases_lock)
+			.map_err(Self::burn_restriction_error)?;
+
+		// Execute
+		Self::remove_nft(*item, &nft)
+	}
+}
+
+impl<T: Config> Create<T::AccountId> for Pallet<T> {
+	/// Create a collection owned by `who`. The collection must be the next available collection
+	/// id. Collections have no admin so `admin` is ignored.
+	fn create_collection(
+		collection: &Self::CollectionId,
+		who: &T::AccountId,
+		_admin: &T::AccountId,
+	) -> DispatchResult {
+		ensure!(*collection == NextCollectionId::<T>::get(), Error::<T>::InvalidCollectionId);
+
+		let collection_id = Self::get_next_collection_id();
+		let offchain_data = U8BoundedVec::default();
+		Collections::<T>::insert(
+			collection_id,
+			Collection::new(who.clone(), offchain_data.clone(), None),
+		);
+
+		let event = Event::CollectionCreated {
+			collection_id,
+			owner: who.clone(),
+			offchain_data,
+			limit: None,
+		};
+		Self::deposit_event(event);
+
+		Ok(())
+	}
+}
+
+impl<T: Config> Destroy<T::AccountId> for Pallet<T> {
+	/// Collections can only be destroyed once empty, so no witness is needed.
+	type DestroyWitness = ();
+
+	fn get_destroy_witness(collection: &Self::CollectionId) -> Option<Self::DestroyWitness> {
+		Collections::<T>::contains_key(collection).then(|| ())
+	}
+
+	fn destroy(
+		collection: Self::CollectionId,
+		_witness: Self::DestroyWitness,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> Result<Self::DestroyWitness, DispatchError> {
+		let data = Collections::<T>::get(collection).ok_or(Error::<T>::CollectionNotFound)?;
+
+		// Checks
+		if let Some(owner) = maybe_check_owner {
+			ensure!(data.owner == owner, Error::<T>::NotTheCollectionOwner);
+		}
+		ensure!(data.nfts_count == 0, Error::<T>::CollectionIsNotEmpty);
+
+		// Execute
+		Self::remove_collection(collection);
+
+		Ok(())
+	}
+}
@@ -22,29 +22,45 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
-mod migrations;
+mod impl_nonfungibles;
+pub mod migrations;
+mod types;
 
 pub mod weights;
 
 pub use pallet::*;
+pub use types::*;
 
 use frame_support::{
-	dispatch::DispatchResult,
+	dispatch::{DispatchClass, DispatchResult},
 	ensure,
+	storage::{with_transaction, TransactionOutcome},
 	traits::{
-		Currency, ExistenceRequirement::KeepAlive, Get, OnUnbalanced, StorageVersion,
-		WithdrawReasons,
+		Currency, ExistenceRequirement::KeepAlive, Get, OnUnbalanced, ReservableCurrency,
+		StorageVersion, WithdrawReasons,
 	},
+	weights::Weight,
 	BoundedVec,
 };
 use frame_system::pallet_prelude::*;
+use parity_scale_codec::Encode;
 use primitives::{
-	nfts::{Collection, CollectionId, NFTData, NFTId, NFTState},
+	nfts::{
+		AttributeNamespace, BatchMode, Collection, CollectionId, CollectionMintConfig, MerkleHash,
+		MetadataMutability, MinterAttribution, NFTData, NFTId, NFTLock, NFTRestriction, NFTState,
+		NFTStoragePayment, NFTVoucher, RoyaltySplits,
+	},
 	tee::ClusterId,
 	U8BoundedVec,
 };
 use sp_arithmetic::per_things::Permill;
-use sp_runtime::traits::{CheckedSub, StaticLookup};
+use sp_runtime::{
+	traits::{
+		BlakeTwo256, CheckedSub, Hash as HashT, IdentifyAccount, Saturating, StaticLookup, Verify,
+		Zero,
+	},
+	DispatchError,
+};
 use sp_std::{prelude::*, vec};
 use ternoa_common::{traits, traits::TEEExt};
 
@@ -56,7 +72,32 @@ pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
 	<T as frame_system::Config>::AccountId,
 >>::NegativeImbalance;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(9);
+
+/// Lock held by nested NFTs, which can only move with their parent.
+pub const NESTING_LOCK: NFTLock = NFTLock::new(*b"ter/nft_", *b"nested__");
+
+/// Prefix of the payload signed by the creator of an NFT voucher.
+pub const VOUCHER_SIGNING_PREFIX: &[u8; 18] = b"ternoa/nft-voucher";
+
+/// Signs NFT vouchers in benchmarks, where no keystore is available to the pallet.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId, Signature> {
+	/// Account signing the vouchers.
+	fn signer() -> AccountId;
+	/// Signature of `message` by the signer account.
+	fn sign(message: &[u8]) -> Signature;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper<u64, sp_runtime::testing::TestSignature> for () {
+	fn signer() -> u64 {
+		100
+	}
+	fn sign(message: &[u8]) -> sp_runtime::testing::TestSignature {
+		sp_runtime::testing::TestSignature(Self::signer(), message.to_vec())
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -77,7 +118,7 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 
 		/// Currency type.
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 
 		/// What we do with additional fees.
 		type FeesCollector: OnUnbalanced<NegativeImbalanceOf<Self>>;
@@ -85,6 +126,14 @@ pub mod pallet {
 		/// Link to the TEE pallet.
 		type TEEExt: TEEExt<AccountId = Self::AccountId>;
 
+		/// Handlers notified when an NFT is transferred, as a tuple of pallets. The locks they
+		/// release do not prevent transfers.
+		type OnNFTTransfer: traits::OnNFTTransfer<Self::AccountId>;
+
+		/// Handlers notified when an NFT is burned, as a tuple of pallets. The locks they release
+		/// do not prevent burns.
+		type OnNFTBurn: traits::OnNFTBurn<Self::AccountId>;
+
 		// Constants
 		/// Default fee for minting NFTs.
 		#[pallet::constant]
@@ -94,7 +143,8 @@ pub mod pallet {
 		#[pallet::constant]
 		type NFTOffchainDataLimit: Get<u32>;
 
-		/// Maximum collection length.
+		/// Maximum collection length. This is a policy limit, collection membership is not
+		/// stored in a bounded list.
 		#[pallet::constant]
 		type CollectionSizeLimit: Get<u32>;
 
@@ -113,6 +163,91 @@ pub mod pallet {
 		/// Default fee for minting secret NFTs.
 		#[pallet::constant]
 		type InitialCapsuleMintFee: Get<BalanceOf<Self>>;
+
+		/// Maximum number of items in a batch call.
+		#[pallet::constant]
+		type BatchSizeLimit: Get<u32>;
+
+		/// Maximum number of beneficiaries in a royalty split.
+		#[pallet::constant]
+		type RoyaltySplitsLimit: Get<u32>;
+
+		/// Maximum number of minters of a collection.
+		#[pallet::constant]
+		type CollectionMintersLimit: Get<u32>;
+
+		/// Maximum number of simultaneous delegations with an expiry.
+		#[pallet::constant]
+		type SimultaneousDelegationLimit: Get<u32>;
+
+		/// Maximum number of expired delegations cleared in one block.
+		#[pallet::constant]
+		type ActionsInBlockLimit: Get<u32>;
+
+		/// Maximum number of NFTs nested directly in an NFT.
+		#[pallet::constant]
+		type NFTChildrenLimit: Get<u32>;
+
+		/// Maximum depth of a tree of nested NFTs.
+		#[pallet::constant]
+		type NestingDepthLimit: Get<u32>;
+
+		/// Number of blocks after which the owner of an NFT whose secret or capsule is still
+		/// syncing can retry the sync on another cluster or cancel it.
+		#[pallet::constant]
+		type ShardSyncTimeout: Get<Self::BlockNumber>;
+
+		/// Part of the capsule mint fee held in a deposit and refunded when the capsule is
+		/// reverted.
+		#[pallet::constant]
+		type CapsuleRefundRatio: Get<Permill>;
+
+		/// Whether the storage of minted NFTs is paid with the mint fee or with a deposit.
+		#[pallet::constant]
+		type StoragePayment: Get<NFTStoragePayment>;
+
+		/// Base deposit reserved per NFT when the storage is paid with a deposit.
+		#[pallet::constant]
+		type NFTDepositBase: Get<BalanceOf<Self>>;
+
+		/// Deposit reserved per byte of NFT offchain data when the storage is paid with a
+		/// deposit.
+		#[pallet::constant]
+		type NFTDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// Maximum number of previous offchain data hashes kept for an NFT.
+		#[pallet::constant]
+		type MetadataHistoryLimit: Get<u32>;
+
+		/// Maximum length of an attribute key.
+		#[pallet::constant]
+		type AttributeKeyLimit: Get<u32>;
+
+		/// Maximum length of an attribute value.
+		#[pallet::constant]
+		type AttributeValueLimit: Get<u32>;
+
+		/// Base deposit reserved to set an attribute.
+		#[pallet::constant]
+		type AttributeDepositBase: Get<BalanceOf<Self>>;
+
+		/// Deposit reserved per byte of attribute key and value.
+		#[pallet::constant]
+		type AttributeDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// Maximum number of hashes in a collection mint allowlist Merkle proof.
+		#[pallet::constant]
+		type AllowlistProofLimit: Get<u32>;
+
+		/// Off-chain signature of NFT vouchers, `sp_runtime::MultiSignature` in the runtime.
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+		/// Public key of the voucher signatures, `sp_runtime::MultiSigner` in the runtime.
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// Helper signing NFT vouchers in benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AccountId, Self::OffchainSignature>;
 	}
 
 	/// How much does it cost to mint a NFT (extra fee on top of the tx fees).
@@ -142,6 +277,38 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Index of the NFTs owned by each account.
+	#[pallet::storage]
+	#[pallet::getter(fn account_nfts)]
+	pub type AccountNfts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		NFTId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Account allowed to transfer an NFT on behalf of its owner, with an optional expiry block.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_approvals)]
+	pub type NFTApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::AccountId, Option<T::BlockNumber>), OptionQuery>;
+
+	/// Operators allowed to transfer all the NFTs of an owner.
+	#[pallet::storage]
+	#[pallet::getter(fn operator_approvals)]
+	pub type OperatorApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
 	/// Data related to collections.
 	#[pallet::storage]
 	#[pallet::getter(fn collections)]
@@ -149,16 +316,353 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		CollectionId,
-		Collection<T::AccountId, T::CollectionOffchainDataLimit, T::CollectionSizeLimit>,
+		Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		OptionQuery,
+	>;
+
+	/// NFTs of each collection.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_nfts_of)]
+	pub type CollectionNfts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		NFTId,
+		(),
+		OptionQuery,
+	>;
+
+	/// NFT lists of the collections created before `CollectionNfts`, moved to it over several
+	/// blocks by the storage migration.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_nfts_to_migrate)]
+	pub type CollectionNftsToMigrate<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		BoundedVec<NFTId, T::CollectionSizeLimit>,
+		OptionQuery,
+	>;
+
+	/// Royalty beneficiaries of an NFT. NFTs without splits pay the collection default splits,
+	/// or the creator if the collection has none.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_royalty_splits)]
+	pub type NFTRoyaltySplits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		NFTId,
+		RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>,
+		OptionQuery,
+	>;
+
+	/// Default royalty beneficiaries of the NFTs of a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_royalty_splits)]
+	pub type CollectionRoyaltySplits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>,
+		OptionQuery,
+	>;
+
+	/// NFTs that keep their own royalty instead of following the collection default royalty.
+	#[pallet::storage]
+	#[pallet::getter(fn royalty_overrides)]
+	pub type RoyaltyOverrides<T: Config> = StorageMap<_, Blake2_128Concat, NFTId, (), OptionQuery>;
+
+	/// Accounts allowed to mint into a collection on behalf of its owner.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_minters)]
+	pub type CollectionMinters<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		BoundedVec<T::AccountId, T::CollectionMintersLimit>,
+		ValueQuery,
+	>;
+
+	/// Who is the creator of the NFTs minted by the minters of a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn minter_attributions)]
+	pub type MinterAttributions<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, MinterAttribution, ValueQuery>;
+
+	/// Public mint configuration of collections.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_mint_configs)]
+	pub type CollectionMintConfigs<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		CollectionMintConfig<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Accounts allowed to mint during the allowlist phase of a collection public mint.
+	#[pallet::storage]
+	#[pallet::getter(fn mint_allowlist)]
+	pub type MintAllowlist<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
 		OptionQuery,
 	>;
 
+	/// Collections whose mint allowlist is cleared over several blocks after a transfer or a burn
+	/// of the collection. Their allowlist is considered empty until it is cleared.
+	#[pallet::storage]
+	#[pallet::getter(fn mint_allowlists_to_clear)]
+	pub type MintAllowlistsToClear<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, (), OptionQuery>;
+
+	/// Number of NFTs minted by each account in a collection public mint.
+	#[pallet::storage]
+	#[pallet::getter(fn public_mints)]
+	pub type PublicMints<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Number of NFTs minted by each account through the Merkle allowlist of a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn allowlist_claims)]
+	pub type AllowlistClaims<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Merkle allowlist leaves of a collection that have been fully claimed.
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_allowlist_leaves)]
+	pub type ClaimedAllowlistLeaves<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, CollectionId, Identity, MerkleHash, (), OptionQuery>;
+
+	/// Burned collections whose public mint counters and allowlist claims are cleared over
+	/// several blocks.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_mints_to_clear)]
+	pub type CollectionMintsToClear<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, (), OptionQuery>;
+
+	/// Voucher nonces already redeemed by each creator.
+	#[pallet::storage]
+	#[pallet::getter(fn redeemed_vouchers)]
+	pub type RedeemedVouchers<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u64, (), OptionQuery>;
+
 	/// Host a map of delegated NFTs and the recipient.
 	#[pallet::storage]
 	#[pallet::getter(fn delegated_nfts)]
 	pub type DelegatedNFTs<T: Config> =
 		StorageMap<_, Blake2_128Concat, NFTId, T::AccountId, OptionQuery>;
 
+	/// Block at which the delegation of an NFT expires.
+	#[pallet::storage]
+	#[pallet::getter(fn delegation_expiries)]
+	pub type DelegationExpiries<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, T::BlockNumber, OptionQuery>;
+
+	/// Parent of each nested NFT.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_parents)]
+	pub type NFTParents<T: Config> = StorageMap<_, Blake2_128Concat, NFTId, NFTId, OptionQuery>;
+
+	/// NFTs nested directly in an NFT.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_children)]
+	pub type NFTChildren<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, BoundedVec<NFTId, T::NFTChildrenLimit>, ValueQuery>;
+
+	/// Attributes of NFTs with the account and amount of their deposit.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_attributes)]
+	pub type NFTAttributes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		NFTId,
+		Blake2_128Concat,
+		(AttributeNamespace, U8BoundedVec<T::AttributeKeyLimit>),
+		(U8BoundedVec<T::AttributeValueLimit>, Option<(T::AccountId, BalanceOf<T>)>),
+		OptionQuery,
+	>;
+
+	/// Attributes of collections with the account and amount of their deposit.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_attributes)]
+	pub type CollectionAttributes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		(AttributeNamespace, U8BoundedVec<T::AttributeKeyLimit>),
+		(U8BoundedVec<T::AttributeValueLimit>, Option<(T::AccountId, BalanceOf<T>)>),
+		OptionQuery,
+	>;
+
+	/// Collections whose collection owner attributes can no longer be changed.
+	#[pallet::storage]
+	#[pallet::getter(fn locked_collection_attributes)]
+	pub type LockedCollectionAttributes<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, (), OptionQuery>;
+
+	/// Block at which the secret of an NFT started syncing, with the cluster that must provide
+	/// the shards if the sync was retried.
+	#[pallet::storage]
+	#[pallet::getter(fn secret_syncs)]
+	pub type SecretSyncs<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::BlockNumber, Option<ClusterId>), OptionQuery>;
+
+	/// Secret NFTs whose rotated secret is syncing. Their sync can be retried but not cancelled,
+	/// since the previous secret is no longer held by any enclave.
+	#[pallet::storage]
+	#[pallet::getter(fn secret_rotations)]
+	pub type SecretRotations<T: Config> = StorageMap<_, Blake2_128Concat, NFTId, (), OptionQuery>;
+
+	/// Block at which the capsule of an NFT started syncing, with the cluster that must provide
+	/// the shards if the sync was retried.
+	#[pallet::storage]
+	#[pallet::getter(fn capsule_syncs)]
+	pub type CapsuleSyncs<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::BlockNumber, Option<ClusterId>), OptionQuery>;
+
+	/// Enclaves holding the shards of a synced secret NFT.
+	#[pallet::storage]
+	#[pallet::getter(fn secret_nft_shard_holders)]
+	pub type SecretNftShardHolders<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		NFTId,
+		BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber>,
+		ValueQuery,
+	>;
+
+	/// Enclaves holding the shards of a synced capsule.
+	#[pallet::storage]
+	#[pallet::getter(fn capsule_shard_holders)]
+	pub type CapsuleShardHolders<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		NFTId,
+		BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber>,
+		ValueQuery,
+	>;
+
+	/// NFTs for which an enclave operator holds secret or capsule shards.
+	#[pallet::storage]
+	#[pallet::getter(fn shard_holder_nfts)]
+	pub type ShardHolderNfts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		NFTId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Secret NFTs that lost shard holders and wait for new shards.
+	#[pallet::storage]
+	#[pallet::getter(fn secret_nfts_resharding)]
+	pub type SecretNftsResharding<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (), OptionQuery>;
+
+	/// Capsules that lost shard holders and wait for new shards.
+	#[pallet::storage]
+	#[pallet::getter(fn capsules_resharding)]
+	pub type CapsulesResharding<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (), OptionQuery>;
+
+	/// Secret NFT mint fees reserved until the secret is synced, with the account that paid them.
+	/// The fee used to be withdrawn to the fees collector when the secret was added. Secrets
+	/// that were syncing before this storage was introduced have no entry: their fee has
+	/// already been collected and cancelling their sync refunds nothing. No migration is needed.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_secret_fees)]
+	pub type PendingSecretFees<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Refundable part of the capsule mint fee with the account it is reserved from.
+	#[pallet::storage]
+	#[pallet::getter(fn capsule_deposits)]
+	pub type CapsuleDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Storage deposits of NFTs with the account they are reserved from.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_deposits)]
+	pub type NFTDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Who can update the offchain data of the NFTs of a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_metadata_mutability)]
+	pub type CollectionMetadataMutability<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, MetadataMutability, ValueQuery>;
+
+	/// NFTs whose offchain data can no longer be updated.
+	#[pallet::storage]
+	#[pallet::getter(fn frozen_nft_metadata)]
+	pub type FrozenNFTMetadata<T: Config> = StorageMap<_, Blake2_128Concat, NFTId, (), OptionQuery>;
+
+	/// Collections whose offchain data and NFTs offchain data can no longer be updated.
+	#[pallet::storage]
+	#[pallet::getter(fn frozen_collection_metadata)]
+	pub type FrozenCollectionMetadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, (), OptionQuery>;
+
+	/// Account a collection is being transferred to, until it accepts the transfer.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_transfers)]
+	pub type CollectionTransfers<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, T::AccountId, OptionQuery>;
+
+	/// Account allowed to update the offchain data and the limit of a collection and to close
+	/// it on behalf of its owner.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_admins)]
+	pub type CollectionAdmins<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, T::AccountId, OptionQuery>;
+
+	/// Account allowed to close a collection and to freeze its offchain data on behalf of its
+	/// owner.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_freezers)]
+	pub type CollectionFreezers<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionId, T::AccountId, OptionQuery>;
+
+	/// Hashes of the previous offchain data of NFTs, from the oldest to the latest.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_metadata_history)]
+	pub type NFTMetadataHistory<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		NFTId,
+		BoundedVec<T::Hash, T::MetadataHistoryLimit>,
+		ValueQuery,
+	>;
+
+	/// Queue of the delegations ordered by expiry block.
+	#[pallet::storage]
+	#[pallet::getter(fn delegation_queue)]
+	pub type DelegationQueue<T: Config> =
+		StorageValue<_, Queue<T::BlockNumber, T::SimultaneousDelegationLimit>, ValueQuery>;
+
 	/// How much does it cost to mint a secret NFT (extra fee on top of the tx fees and basic NFT
 	/// fee).
 	#[pallet::storage]
@@ -207,33 +711,172 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
-		/// A new NFT was created.
-		NFTCreated {
-			nft_id: NFTId,
-			owner: T::AccountId,
-			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
-			royalty: Permill,
-			collection_id: Option<CollectionId>,
-			is_soulbound: bool,
-			mint_fee: BalanceOf<T>,
-		},
-		/// An NFT was burned.
-		NFTBurned { nft_id: NFTId },
-		/// An NFT was transferred to someone else.
-		NFTTransferred { nft_id: NFTId, sender: T::AccountId, recipient: T::AccountId },
-		/// An NFT was delegated to someone else.
-		NFTDelegated { nft_id: NFTId, recipient: Option<T::AccountId> },
-		/// Royalty has been changed for an NFT.
-		NFTRoyaltySet { nft_id: NFTId, royalty: Permill },
-		/// NFT mint fee changed.
-		NFTMintFeeSet { fee: BalanceOf<T> },
-		/// A collection was created.
-		CollectionCreated {
-			collection_id: CollectionId,
-			owner: T::AccountId,
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Pre-minted NFTs: id, owner, offchain data, royalty, collection id, soulbound flag,
+		/// secret offchain data and capsule offchain data.
+		/// Secrets and capsules are considered synced.
+		pub nfts: Vec<(
+			NFTId,
+			T::AccountId,
+			Vec<u8>,
+			Permill,
+			Option<CollectionId>,
+			bool,
+			Option<Vec<u8>>,
+			Option<Vec<u8>>,
+		)>,
+		/// Collections: id, owner, offchain data and limit.
+		pub collections: Vec<(CollectionId, T::AccountId, Vec<u8>, Option<u32>)>,
+		/// Initial NFT mint fee.
+		pub nft_mint_fee: BalanceOf<T>,
+		/// Initial secret NFT mint fee.
+		pub secret_nft_mint_fee: BalanceOf<T>,
+		/// Initial capsule mint fee.
+		pub capsule_mint_fee: BalanceOf<T>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				nfts: Default::default(),
+				collections: Default::default(),
+				nft_mint_fee: T::InitialMintFee::get(),
+				secret_nft_mint_fee: T::InitialSecretMintFee::get(),
+				capsule_mint_fee: T::InitialCapsuleMintFee::get(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			NftMintFee::<T>::put(self.nft_mint_fee);
+			SecretNftMintFee::<T>::put(self.secret_nft_mint_fee);
+			CapsuleMintFee::<T>::put(self.capsule_mint_fee);
+
+			for (collection_id, owner, offchain_data, limit) in &self.collections {
+				assert!(
+					!Collections::<T>::contains_key(collection_id),
+					"Duplicate collection id in genesis"
+				);
+				if let Some(limit) = limit {
+					assert!(
+						*limit <= T::CollectionSizeLimit::get(),
+						"Collection limit exceeds the maximum allowed in genesis"
+					);
+				}
+				let offchain_data = offchain_data
+					.clone()
+					.try_into()
+					.expect("Collection offchain data is too long in genesis");
+				let collection = Collection::new(owner.clone(), offchain_data, *limit);
+				Collections::<T>::insert(collection_id, collection);
+			}
+
+			for (
+				nft_id,
+				owner,
+				offchain_data,
+				royalty,
+				collection_id,
+				is_soulbound,
+				secret,
+				capsule,
+			) in &self.nfts
+			{
+				assert!(!Nfts::<T>::contains_key(nft_id), "Duplicate NFT id in genesis");
+				let offchain_data: U8BoundedVec<T::NFTOffchainDataLimit> = offchain_data
+					.clone()
+					.try_into()
+					.expect("NFT offchain data is too long in genesis");
+
+				if let Some(collection_id) = collection_id {
+					Collections::<T>::mutate(collection_id, |x| {
+						let collection = x.as_mut().expect("Unknown NFT collection in genesis");
+						let limit =
+							collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get());
+						assert!(
+							collection.nfts_count < limit,
+							"Collection has too many NFTs in genesis"
+						);
+						collection.nfts_count += 1;
+						CollectionNfts::<T>::insert(collection_id, nft_id, ());
+					});
+				}
+
+				let mut state = NFTState::new_default(*is_soulbound);
+				if let Some(secret) = secret {
+					let secret: U8BoundedVec<T::NFTOffchainDataLimit> = secret
+						.clone()
+						.try_into()
+						.expect("Secret offchain data is too long in genesis");
+					SecretNftsOffchainData::<T>::insert(nft_id, secret);
+					state.is_secret = true;
+				}
+				if let Some(capsule) = capsule {
+					let capsule: U8BoundedVec<T::NFTOffchainDataLimit> = capsule
+						.clone()
+						.try_into()
+						.expect("Capsule offchain data is too long in genesis");
+					CapsuleOffchainData::<T>::insert(nft_id, capsule);
+					state.is_capsule = true;
+				}
+
+				let nft = NFTData::new(
+					owner.clone(),
+					owner.clone(),
+					offchain_data,
+					*royalty,
+					state,
+					*collection_id,
+				);
+				Nfts::<T>::insert(nft_id, nft);
+				AccountNfts::<T>::insert(owner, nft_id, ());
+			}
+
+			let next_nft_id = self.nfts.iter().map(|x| x.0 + 1).max().unwrap_or_default();
+			NextNFTId::<T>::put(next_nft_id);
+			let next_collection_id =
+				self.collections.iter().map(|x| x.0 + 1).max().unwrap_or_default();
+			NextCollectionId::<T>::put(next_collection_id);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A new NFT was created.
+		NFTCreated {
+			nft_id: NFTId,
+			owner: T::AccountId,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+			royalty: Permill,
+			collection_id: Option<CollectionId>,
+			is_soulbound: bool,
+			mint_fee: BalanceOf<T>,
+		},
+		/// An NFT was burned.
+		NFTBurned { nft_id: NFTId },
+		/// An NFT was transferred to someone else.
+		NFTTransferred { nft_id: NFTId, sender: T::AccountId, recipient: T::AccountId },
+		/// An item of a best effort batch call failed and was skipped.
+		NFTBatchItemFailed { index: u32, error: DispatchError },
+		/// An NFT was delegated to someone else.
+		NFTDelegated {
+			nft_id: NFTId,
+			recipient: Option<T::AccountId>,
+			expiry: Option<T::BlockNumber>,
+		},
+		/// Royalty has been changed for an NFT.
+		NFTRoyaltySet { nft_id: NFTId, royalty: Permill },
+		/// NFT mint fee changed.
+		NFTMintFeeSet { fee: BalanceOf<T> },
+		/// A collection was created.
+		CollectionCreated {
+			collection_id: CollectionId,
+			owner: T::AccountId,
 			offchain_data: U8BoundedVec<T::CollectionOffchainDataLimit>,
 			limit: Option<u32>,
 		},
@@ -267,8 +910,8 @@ pub mod pallet {
 		CapsuleShardAdded { nft_id: NFTId, enclave: T::AccountId },
 		/// A capsule has finished syncing shards
 		CapsuleSynced { nft_id: NFTId },
-		/// A capsule was reverted to a regular NFT
-		CapsuleReverted { nft_id: NFTId },
+		/// A capsule was reverted to a regular NFT. Enclaves can delete its key shards.
+		CapsuleReverted { nft_id: NFTId, refund: BalanceOf<T> },
 		/// Capsule mint fee has changed
 		CapsuleMintFeeSet { fee: BalanceOf<T> },
 		/// A user signified that a enclave key update was in progress
@@ -278,18 +921,175 @@ pub mod pallet {
 			collection_id: CollectionId,
 			offchain_data: U8BoundedVec<T::CollectionOffchainDataLimit>,
 		},
+		/// An account was approved to transfer an NFT.
+		TransferApproved {
+			nft_id: NFTId,
+			owner: T::AccountId,
+			delegate: T::AccountId,
+			expiry: Option<T::BlockNumber>,
+		},
+		/// A transfer approval was cancelled.
+		ApprovalCancelled { nft_id: NFTId },
+		/// An operator was allowed or disallowed to transfer all the NFTs of an owner.
+		ApprovalForAllSet { owner: T::AccountId, operator: T::AccountId, approved: bool },
+		/// Royalty beneficiaries have been changed for an NFT.
+		NFTRoyaltySplitsSet {
+			nft_id: NFTId,
+			splits: Option<RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>>,
+		},
+		/// Default royalty beneficiaries have been changed for a collection.
+		CollectionRoyaltySplitsSet {
+			collection_id: CollectionId,
+			splits: Option<RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>>,
+		},
+		/// Default and maximum royalty have been changed for a collection.
+		CollectionRoyaltySet {
+			collection_id: CollectionId,
+			royalty: Option<Permill>,
+			max_royalty: Option<Permill>,
+		},
+		/// A minter was added to a collection.
+		CollectionMinterAdded { collection_id: CollectionId, minter: T::AccountId },
+		/// A minter was removed from a collection.
+		CollectionMinterRemoved { collection_id: CollectionId, minter: T::AccountId },
+		/// The creator attribution of the NFTs minted by minters was changed for a collection.
+		MinterAttributionSet { collection_id: CollectionId, attribution: MinterAttribution },
+		/// The public mint configuration of a collection was changed.
+		CollectionMintConfigSet {
+			collection_id: CollectionId,
+			config: Option<CollectionMintConfig<T::AccountId, BalanceOf<T>, T::BlockNumber>>,
+		},
+		/// Accounts were added to or removed from the allowlist of a collection public mint.
+		MintAllowlistUpdated {
+			collection_id: CollectionId,
+			accounts: BoundedVec<T::AccountId, T::BatchSizeLimit>,
+			allowed: bool,
+		},
+		/// An NFT was bought in a collection public mint.
+		NFTPublicMinted {
+			nft_id: NFTId,
+			collection_id: CollectionId,
+			owner: T::AccountId,
+			price: BalanceOf<T>,
+		},
+		/// The Merkle root of a collection mint allowlist was set or removed.
+		CollectionAllowlistRootSet { collection_id: CollectionId, root: Option<MerkleHash> },
+		/// An NFT was minted through a collection Merkle allowlist.
+		NFTAllowlistMinted { nft_id: NFTId, collection_id: CollectionId, owner: T::AccountId },
+		/// A creator voucher was redeemed and its NFT minted.
+		VoucherRedeemed {
+			nft_id: NFTId,
+			creator: T::AccountId,
+			owner: T::AccountId,
+			price: BalanceOf<T>,
+			nonce: u64,
+		},
+		/// A soulbound NFT was issued to a recipient.
+		SoulboundIssued { nft_id: NFTId, issuer: T::AccountId, recipient: T::AccountId },
+		/// An issued soulbound NFT was revoked and burned by its issuer.
+		SoulboundRevoked { nft_id: NFTId },
+		/// An issued soulbound NFT was rejected and burned by its owner.
+		SoulboundRejected { nft_id: NFTId },
+		/// The delegation of an NFT expired.
+		DelegationExpired { nft_id: NFTId },
+		/// An NFT was nested in a parent NFT.
+		NFTNested { nft_id: NFTId, parent_id: NFTId },
+		/// An NFT was detached from its parent NFT.
+		NFTUnnested { nft_id: NFTId, parent_id: NFTId },
+		/// An attribute of an NFT was set.
+		NFTAttributeSet {
+			nft_id: NFTId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+			value: U8BoundedVec<T::AttributeValueLimit>,
+		},
+		/// An attribute of an NFT was cleared.
+		NFTAttributeCleared {
+			nft_id: NFTId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+		},
+		/// An attribute of a collection was set.
+		CollectionAttributeSet {
+			collection_id: CollectionId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+			value: U8BoundedVec<T::AttributeValueLimit>,
+		},
+		/// An attribute of a collection was cleared.
+		CollectionAttributeCleared {
+			collection_id: CollectionId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+		},
+		/// The collection owner attributes of a collection and of its NFTs were locked.
+		CollectionAttributesLocked { collection_id: CollectionId },
+		/// The offchain data of an NFT was updated.
+		NFTOffchainDataSet { nft_id: NFTId, offchain_data: U8BoundedVec<T::NFTOffchainDataLimit> },
+		/// The metadata mutability policy of a collection was set.
+		CollectionMetadataMutabilitySet {
+			collection_id: CollectionId,
+			mutability: MetadataMutability,
+		},
+		/// The offchain data of an NFT was frozen.
+		NFTMetadataFrozen { nft_id: NFTId },
+		/// The offchain data of a collection and of its NFTs was frozen.
+		CollectionMetadataFrozen { collection_id: CollectionId },
+		/// The secret of an NFT must be synced again by the enclaves of a cluster.
+		SecretSyncRetried { nft_id: NFTId, cluster_id: ClusterId },
+		/// The secret sync of an NFT was cancelled and the secret removed.
+		SecretSyncCancelled { nft_id: NFTId, refund: BalanceOf<T> },
+		/// The capsule of an NFT must be synced again by the enclaves of a cluster.
+		CapsuleSyncRetried { nft_id: NFTId, cluster_id: ClusterId },
+		/// The capsule sync of an NFT was cancelled and the capsule reverted.
+		CapsuleSyncCancelled { nft_id: NFTId, refund: BalanceOf<T> },
+		/// An enclave holding a shard of a secret NFT was removed. The enclaves of the cluster
+		/// must send a new shard.
+		SecretNFTReshardingRequested { nft_id: NFTId, cluster_id: ClusterId },
+		/// A secret NFT has finished resharding.
+		SecretNFTResharded { nft_id: NFTId },
+		/// An enclave holding a shard of a capsule was removed. The enclaves of the cluster must
+		/// send a new shard.
+		CapsuleReshardingRequested { nft_id: NFTId, cluster_id: ClusterId },
+		/// A capsule has finished resharding.
+		CapsuleResharded { nft_id: NFTId },
+		/// The owner of a collection offered to transfer it to another account.
+		CollectionTransferRequested {
+			collection_id: CollectionId,
+			owner: T::AccountId,
+			recipient: T::AccountId,
+		},
+		/// A collection transfer was accepted and the collection changed owner.
+		CollectionTransferred { collection_id: CollectionId, from: T::AccountId, to: T::AccountId },
+		/// A pending collection transfer was cancelled.
+		CollectionTransferCancelled { collection_id: CollectionId },
+		/// The admin and the freezer of a collection were set.
+		CollectionRolesSet {
+			collection_id: CollectionId,
+			admin: Option<T::AccountId>,
+			freezer: Option<T::AccountId>,
+		},
+		/// The secret of an NFT was rotated. The enclaves must discard the shards they hold for
+		/// this NFT and new shards are expected.
+		SecretRotated {
+			nft_id: NFTId,
+			offchain_data: Option<U8BoundedVec<T::NFTOffchainDataLimit>>,
+		},
+		/// The storage deposit of an NFT could not be reserved from its new owner and stays
+		/// reserved from the previous depositor until the NFT changes owner again.
+		NFTDepositNotMoved { nft_id: NFTId, depositor: T::AccountId, owner: T::AccountId },
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// Operation not allowed because the NFT is listed for sale.
-		CannotTransferListedNFTs,
-		/// Operation not allowed because the NFT is listed for sale.
-		CannotBurnListedNFTs,
-		/// Operation not allowed because the NFT is listed for sale.
-		CannotDelegateListedNFTs,
-		/// Operation not allowed because the NFT is listed for sale.
-		CannotSetRoyaltyForListedNFTs,
+		/// Operation not allowed because the NFT is locked by another pallet.
+		CannotTransferLockedNFTs,
+		/// Operation not allowed because the NFT is locked by another pallet.
+		CannotBurnLockedNFTs,
+		/// Operation not allowed because the NFT is locked by another pallet.
+		CannotDelegateLockedNFTs,
+		/// Operation not allowed because the NFT is locked by another pallet.
+		CannotSetRoyaltyForLockedNFTs,
 		/// Operation is not allowed because the NFT is delegated.
 		CannotTransferDelegatedNFTs,
 		/// Operation is not allowed because the NFT secret is not synced.
@@ -302,16 +1102,8 @@ pub mod pallet {
 		CannotTransferNotCreatedSoulboundNFTs,
 		/// Operation is not allowed because the NFT is owned by the caller.
 		CannotTransferNFTsToYourself,
-		/// Operation is not allowed because the NFT is rented
-		CannotTransferRentedNFTs,
-		/// Operation is not allowed because the NFT is rented
-		CannotBurnRentedNFTs,
-		/// Operation is not allowed because the NFT is rented
-		CannotSetRoyaltyForRentedNFTs,
 		/// Operation is not allowed because the NFT is secret and syncing
 		CannotSetRoyaltyForSyncingNFTs,
-		/// Operation is not allowed because the NFT is rented
-		CannotDelegateRentedNFTs,
 		/// Operation is not allowed because the NFT is secret and syncing
 		CannotDelegateSyncingNFTs,
 		/// Operation is not allowed because the collection limit is too low.
@@ -355,63 +1147,43 @@ pub mod pallet {
 		EnclaveAlreadyAddedShard,
 		/// Insufficient balance
 		InsufficientBalance,
-		/// Operation is not permitted because the NFT is listed.
-		CannotAddSecretToListedNFTs,
+		/// Operation is not permitted because the NFT is locked by another pallet.
+		CannotAddSecretToLockedNFTs,
 		/// Operation is not permitted because the NFT is already a secret.
 		CannotAddSecretToSecretNFTs,
-		/// Operation is not permitted because the NFT is rented.
-		CannotAddSecretToRentedNFTs,
 		/// Operation is not permitted because the NFT is delegated.
 		CannotAddSecretToDelegatedNFTs,
 		/// Enclave which posted the shard for the NFT does not belongs to the
 		/// same cluster of the first posted shard.
 		ShareNotFromValidCluster,
-		/// Cannot burn capsule in a transmission protocol.
-		CannotBurnNFTsInTransmission,
 		/// Cannot transfer not synced capsules.
 		CannotTransferNotSyncedCapsules,
-		/// Cannot transfer capsule with transmission protocol.
-		CannotTransferNFTsInTransmission,
 		/// Cannot delegate syncing capsules.
 		CannotDelegateSyncingCapsules,
-		/// Cannot delegate capsules with transmission protocol.
-		CannotDelegateNFTsInTransmission,
 		/// Cannot set royalty for syncing capsules.
 		CannotSetRoyaltyForSyncingCapsules,
-		/// Cannot set royalty for nft in tranmission.
-		CannotSetRoyaltyForNFTsInTransmission,
 		/// Cannot add secret to a syncing capsule.
 		CannotAddSecretToSyncingCapsules,
-		/// Cannot add secret to nfts in transmission
-		CannotAddSecretToNFTsInTransmission,
-		/// Cannot convert a listed NFT to capsule
-		CannotConvertListedNFTs,
-		/// Cannot convert a listed NFT to capsule
-		CannotConvertCapsules,
+		/// Cannot convert an NFT locked by another pallet to capsule
+		CannotConvertLockedNFTs,
 		/// Cannot convert a capsule to capsule
-		CannotConvertRentedNFTs,
+		CannotConvertCapsules,
 		/// Cannot convert a delegated NFT to capsule
 		CannotConvertDelegatedNFTs,
 		/// Cannot convert a syncing secret NFT to capsule
 		CannotConvertSyncingNFTs,
-		/// Cannot convert an NFT in transmission to capsule
-		CannotConvertNFTsInTransmission,
 		/// Operation is not permitted because NFT is not a capsule
 		NFTIsNotCapsule,
-		/// Operation is not permitted because NFT is listed
-		CannotRevertListedNFTs,
-		/// Operation is not permitted because NFT is rented
-		CannotRevertRentedNFTs,
+		/// Operation is not permitted because NFT is locked by another pallet
+		CannotRevertLockedNFTs,
 		/// Operation is not permitted because NFT is delegated
 		CannotRevertDelegatedNFTs,
 		/// Operation is not permitted because NFT secret is syncing
 		CannotRevertSyncingNFTs,
-		/// Operation is not permitted because NFT is in transmission
-		CannotRevertNFTsInTransmission,
-		/// Operation is not permitted because the NFT is listed
-		CannotSetOffchainDataForListedNFTs,
-		/// Operation is not permitted because the NFT is rented
-		CannotSetOffchainDataForRentedNFTs,
+		/// Operation is not permitted because capsule is syncing
+		CannotRevertSyncingCapsules,
+		/// Operation is not permitted because the NFT is locked by another pallet
+		CannotSetOffchainDataForLockedNFTs,
 		/// Operation is not permitted because the NFT is delegated
 		CannotSetOffchainDataForDelegatedNFTs,
 		/// Operation is not permitted because the NFT secret is syncing
@@ -420,182 +1192,223 @@ pub mod pallet {
 		CannotSetOffchainDataForSyncingCapsules,
 		/// Operation is not permitted because capsule has already received all shards.
 		CapsuleHasReceivedAllShards,
-		/// Operation is not permitted because the NFT is listed
-		CannotChangeKeyForListedNFTs,
-		/// Operation is not permitted because the NFT is rented
-		CannotChangeKeyForRentedNFTs,
+		/// Operation is not permitted because the NFT is locked by another pallet
+		CannotChangeKeyForLockedNFTs,
 		/// Operation is not permitted because the NFT is delegated
 		CannotChangeKeyForDelegatedNFTs,
 		/// Operation is not permitted because the NFT secret is syncing
 		CannotChangeKeyForSyncingNFTs,
 		/// Operation is not permitted because the NFT capsule is syncing
 		CannotChangeKeyForSyncingCapsules,
-		/// Operation is not permitted because the NFT is in transmission
-		CannotChangeKeyForNFTsInTransmission,
+		/// Operation is not permitted because the NFT already holds this lock.
+		NFTAlreadyLocked,
+		/// Operation is not permitted because the NFT cannot hold more locks.
+		TooManyNFTLocks,
+		/// Operation is not permitted because the NFT does not hold this lock.
+		NFTLockNotFound,
+		/// NFTs can only be minted with the next available NFT id.
+		InvalidNFTId,
+		/// Collections can only be created with the next available collection id.
+		InvalidCollectionId,
+		/// Cannot approve yourself.
+		CannotApproveYourself,
+		/// The approval expiry block is in the past.
+		ApprovalExpiryInThePast,
+		/// No transfer approval was found for that NFT.
+		ApprovalNotFound,
+		/// The caller is not approved to transfer the NFT or the approval expired.
+		NotApprovedToTransfer,
+		/// Royalty shares must add up to 100%.
+		RoyaltySplitsMustSumTo100Percent,
+		/// A beneficiary appears more than once in the royalty splits.
+		DuplicateRoyaltyBeneficiary,
+		/// The royalty is above the maximum royalty of the collection.
+		RoyaltyExceedsCollectionMaximum,
+		/// The account is already a minter of the collection.
+		AlreadyACollectionMinter,
+		/// The account is not a minter of the collection.
+		NotACollectionMinter,
+		/// The collection has reached its maximum number of minters.
+		TooManyCollectionMinters,
+		/// The collection has no public mint.
+		PublicMintNotConfigured,
+		/// The public mint start block is after its end block.
+		InvalidMintPeriod,
+		/// The public mint has not started yet.
+		PublicMintNotStarted,
+		/// The public mint has ended.
+		PublicMintEnded,
+		/// The account is not in the allowlist of the public mint.
+		NotInMintAllowlist,
+		/// The allowlist of the public mint is still being cleared after a collection transfer.
+		MintAllowlistBeingCleared,
+		/// The account has minted the maximum number of NFTs allowed by the public mint.
+		AccountMintLimitReached,
+		/// The collection has no Merkle allowlist.
+		AllowlistNotConfigured,
+		/// The Merkle proof does not match the collection allowlist root.
+		InvalidAllowlistProof,
+		/// The allowlist leaf has already been fully claimed.
+		AllowlistLeafAlreadyClaimed,
+		/// The account has minted all the NFTs allowed by its allowlist leaf.
+		AllowlistQuantityExceeded,
+		/// The voucher signature does not match its creator.
+		InvalidVoucherSignature,
+		/// The voucher expiry block has passed.
+		VoucherExpired,
+		/// The voucher nonce has already been redeemed.
+		VoucherAlreadyRedeemed,
+		/// The NFT is not a soulbound NFT issued to another account than its creator.
+		NotAnIssuedSoulboundNFT,
+		/// The delegation expiry must be in the future.
+		DelegationExpiryInThePast,
+		/// The maximum number of simultaneous delegations with an expiry has been reached.
+		SimultaneousDelegationLimitReached,
+		/// The NFT is already nested in another NFT.
+		NFTAlreadyNested,
+		/// The NFT is not nested in another NFT.
+		NFTNotNested,
+		/// An NFT cannot be nested in itself or in one of its descendants.
+		CannotNestIntoDescendant,
+		/// The nesting would exceed the maximum depth of nested NFTs.
+		NestingDepthLimitReached,
+		/// The parent NFT cannot have more nested NFTs.
+		TooManyNFTChildren,
+		/// Operation is not allowed because the root of the nested NFTs is locked.
+		CannotNestLockedNFTs,
+		/// Operation is not allowed because the NFT has nested NFTs.
+		CannotBurnNFTsWithChildren,
+		/// Attributes of the pallet namespace can only be set by pallets.
+		CannotSetPalletAttributes,
+		/// The collection owner attributes of the collection are locked.
+		CollectionAttributesLocked,
+		/// Operation is not allowed because the NFT is locked.
+		CannotSetAttributesOfLockedNFTs,
+		/// The NFT does not belong to a collection.
+		NFTNotInACollection,
+		/// No attribute was found with that key.
+		AttributeNotFound,
+		/// The attribute key is too long.
+		AttributeKeyTooLong,
+		/// The attribute value is too long.
+		AttributeValueTooLong,
+		/// The offchain data of the NFT is frozen.
+		NFTMetadataFrozen,
+		/// The offchain data of the collection is frozen.
+		CollectionMetadataFrozen,
+		/// The sync has not reached its timeout yet.
+		SyncTimeoutNotReached,
+		/// The sync of a rotated secret can only be retried, not cancelled.
+		CannotCancelSecretRotation,
+		/// No cluster was found with that id.
+		ClusterNotFound,
+		/// The owner of a collection cannot transfer it to themselves.
+		CannotTransferCollectionToYourself,
+		/// No pending transfer was found for the collection.
+		CollectionTransferNotFound,
+		/// The caller is not the recipient of the pending collection transfer.
+		NotTheCollectionTransferRecipient,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Weight: see `begin_block`
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut read = 1u64;
+			let mut write = 0u64;
+			let mut current_actions = 0;
+			let max_actions = T::ActionsInBlockLimit::get();
+
+			let mut queue = DelegationQueue::<T>::get();
+
+			while let Some(nft_id) = queue.pop_next(now) {
+				// Clear the delegation
+				Nfts::<T>::mutate(nft_id, |x| {
+					if let Some(nft) = x {
+						nft.state.is_delegated = false;
+					}
+				});
+				DelegatedNFTs::<T>::remove(nft_id);
+				DelegationExpiries::<T>::remove(nft_id);
+
+				// Deposit event.
+				let event = Event::DelegationExpired { nft_id };
+				Self::deposit_event(event);
+
+				read += 1;
+				write += 3;
+				current_actions += 1;
+				if current_actions >= max_actions {
+					break
+				}
+			}
+
+			if current_actions > 0 {
+				DelegationQueue::<T>::set(queue);
+				write += 1;
+			}
+			T::DbWeight::get().reads_writes(read, write)
+		}
+
+		/// Move the collection NFT lists left by the storage migration to `CollectionNfts`.
+		fn on_idle(_now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let weight = migrations::v9::migrate_collection_nfts::<T>(remaining_weight);
+			let remaining_weight = remaining_weight.saturating_sub(weight);
+			weight.saturating_add(Self::clear_collection_mints(remaining_weight))
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Create a new NFT with the provided details. An ID will be auto
 		/// generated and logged as an event, The caller of this function
 		/// will become the owner of the new NFT.
-		#[pallet::weight((
-            {
-				if let Some(collection_id) = &collection_id {
-					let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
-					if let Ok(collection) = collection {
-						let s = collection.nfts.len();
-						T::WeightInfo::create_nft(s as u32)
-					} else {
-						T::WeightInfo::create_nft(1)
-					}
-				} else {
-					T::WeightInfo::create_nft(1)
-				}
-            },
-			DispatchClass::Normal
-        ))]
+		/// NFTs can be created in a collection by its owner or by its minters. NFTs created in a
+		/// collection without a royalty follow the default royalty of the collection.
+		#[pallet::weight(T::WeightInfo::create_nft())]
 		pub fn create_nft(
 			origin: OriginFor<T>,
 			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
-			royalty: Permill,
+			royalty: Option<Permill>,
 			collection_id: Option<CollectionId>,
 			is_soulbound: bool,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let mut next_nft_id = None;
-
-			// Checks
-			// The Caller needs to pay the NFT mint fee.
-			let mint_fee = NftMintFee::<T>::get();
-			let reason = WithdrawReasons::FEE;
-			let imbalance = T::Currency::withdraw(&who, mint_fee, reason, KeepAlive)?;
-			T::FeesCollector::on_unbalanced(imbalance);
-
-			// Throws an error if specified collection does not exist, signer is not owner,
-			// collection is close, collection has reached limit.
-			if let Some(collection_id) = &collection_id {
-				Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
-					let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
-					let limit =
-						collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get()) as usize;
-					ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
-					ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
-					ensure!(collection.nfts.len() < limit, Error::<T>::CollectionHasReachedLimit);
-
-					let tmp_nft_id = Self::get_next_nft_id();
-					collection
-						.nfts
-						.try_push(tmp_nft_id)
-						.map_err(|_| Error::<T>::CannotAddMoreNFTsToCollection)?;
-					next_nft_id = Some(tmp_nft_id);
-					Ok(().into())
-				})?;
-			}
-
-			let nft_id = next_nft_id.unwrap_or_else(|| Self::get_next_nft_id());
-			let nft = NFTData::new_default(
+			Self::do_create_nft(
 				who.clone(),
-				offchain_data.clone(),
-				royalty,
-				collection_id.clone(),
-				is_soulbound,
-			);
-			// Execute
-			Nfts::<T>::insert(nft_id, nft);
-			let event = Event::NFTCreated {
-				nft_id,
-				owner: who,
+				who.clone(),
+				&who,
 				offchain_data,
 				royalty,
 				collection_id,
 				is_soulbound,
-				mint_fee,
-			};
-			Self::deposit_event(event);
+			)?;
 
 			Ok(().into())
 		}
 
 		/// Remove an NFT from the storage. This operation is irreversible which means
 		/// once the NFT is removed (burned) from the storage there is no way to
-		/// get it back.
+		/// get it back. The `OnNFTBurn` handlers remove the data they hold about the NFT.
 		/// Must be called by the owner of the NFT.
-		#[pallet::weight((
-            {
-				let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound);
-				if let Ok(nft) = nft {
-					if let Some(collection_id) = &nft.collection_id {
-						let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
-						if let Ok(collection) = collection {
-							let s = collection.nfts.len();
-							T::WeightInfo::burn_nft(s as u32)
-						} else {
-							T::WeightInfo::burn_nft(1)
-						}
-					} else {
-						T::WeightInfo::burn_nft(1)
-					}
-				} else {
-					T::WeightInfo::burn_nft(1)
-				}
-            },
-			DispatchClass::Normal
-        ))]
+		#[pallet::weight(T::WeightInfo::burn_nft())]
 		pub fn burn_nft(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 
 			// Checks
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-			ensure!(!nft.state.is_listed, Error::<T>::CannotBurnListedNFTs);
-			ensure!(!nft.state.is_delegated, Error::<T>::CannotBurnDelegatedNFTs);
-			ensure!(!nft.state.is_rented, Error::<T>::CannotBurnRentedNFTs);
-			ensure!(!nft.state.is_transmission, Error::<T>::CannotBurnNFTsInTransmission);
-
-			// Check for collection to remove nft.
-			if let Some(collection_id) = &nft.collection_id {
-				Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
-					let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
-					let index = collection
-						.nfts
-						.iter()
-						.position(|y| *y == nft_id)
-						.ok_or(Error::<T>::NFTNotFoundInCollection)?;
-					// Execute
-					collection.nfts.swap_remove(index);
-					Ok(().into())
-				})?;
-			}
-
-			// Check for secret nft to remove secret offchain data and shards count.
-			if nft.state.is_secret {
-				SecretNftsOffchainData::<T>::remove(nft_id);
-				if nft.state.is_syncing_secret {
-					SecretNftsShardsCount::<T>::remove(nft_id);
-				}
-			}
-
-			// Check for capsule to remove capsule offchain data and capsule shards count.
-			if nft.state.is_capsule {
-				CapsuleOffchainData::<T>::remove(nft_id);
-				if nft.state.is_syncing_capsule {
-					CapsulesShardsCount::<T>::remove(nft_id);
-				}
-			}
+			nft.can_burn_releasing(T::OnNFTBurn::releases_lock)
+				.map_err(Self::burn_restriction_error)?;
 
 			// Execute
-			Nfts::<T>::remove(nft_id);
-			Self::deposit_event(Event::NFTBurned { nft_id });
+			Self::remove_nft(nft_id, &nft)?;
 
 			Ok(().into())
 		}
 
-		/// Transfer an NFT from an account to another one. Must be called by the
-		/// owner of the NFT.
+		/// Transfer an NFT from an account to another one. The `OnNFTTransfer` handlers remove
+		/// the data they hold about the NFT. Must be called by the owner of the NFT.
 		#[pallet::weight(T::WeightInfo::transfer_nft())]
 		pub fn transfer_nft(
 			origin: OriginFor<T>,
@@ -605,45 +1418,20 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			let recipient = T::Lookup::lookup(recipient)?;
 
-			Nfts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
-				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
-
-				// Checks
-				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-				ensure!(nft.owner != recipient, Error::<T>::CannotTransferNFTsToYourself);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotTransferListedNFTs);
-				ensure!(!nft.state.is_delegated, Error::<T>::CannotTransferDelegatedNFTs);
-				ensure!(
-					!(nft.state.is_soulbound && nft.creator != nft.owner),
-					Error::<T>::CannotTransferNotCreatedSoulboundNFTs
-				);
-				ensure!(
-					!nft.state.is_syncing_secret,
-					Error::<T>::CannotTransferNotSyncedSecretNFTs
-				);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotTransferRentedNFTs);
-				ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotTransferNotSyncedCapsules);
-				ensure!(!nft.state.is_transmission, Error::<T>::CannotTransferNFTsInTransmission);
-
-				// Execute
-				nft.owner = recipient.clone();
-
-				Ok(().into())
-			})?;
-			// Execute
-			let event = Event::NFTTransferred { nft_id, sender: who, recipient };
-			Self::deposit_event(event);
+			Self::do_transfer_nft(who, nft_id, recipient)?;
 
 			Ok(().into())
 		}
 
 		/// Delegate an NFT to a recipient, does not change ownership.
+		/// The delegation is cleared automatically at the expiry block, if any.
 		/// Must be called by NFT owner.
-		#[pallet::weight(T::WeightInfo::delegate_nft())]
+		#[pallet::weight(T::WeightInfo::delegate_nft(DelegationQueue::<T>::get().size() as u32))]
 		pub fn delegate_nft(
 			origin: OriginFor<T>,
 			nft_id: NFTId,
 			recipient: Option<<T::Lookup as StaticLookup>::Source>,
+			expiry: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let recipient_account_id = if let Some(recipient) = recipient {
@@ -652,17 +1440,20 @@ pub mod pallet {
 				who.clone()
 			};
 			let is_delegated = recipient_account_id != who;
+			let expiry = if is_delegated { expiry } else { None };
+			if let Some(expiry) = expiry {
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure!(expiry > now, Error::<T>::DelegationExpiryInThePast);
+			}
 
 			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
 				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
 
 				// Checks
 				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotDelegateListedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotDelegateRentedNFTs);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotDelegateLockedNFTs);
 				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotDelegateSyncingNFTs);
 				ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotDelegateSyncingCapsules);
-				ensure!(!nft.state.is_transmission, Error::<T>::CannotDelegateNFTsInTransmission);
 
 				// Execute
 				nft.state.is_delegated = is_delegated;
@@ -671,13 +1462,27 @@ pub mod pallet {
 			})?;
 
 			// Execute
+			DelegationQueue::<T>::try_mutate(|queue| -> DispatchResult {
+				queue.remove(nft_id);
+				if let Some(expiry) = expiry {
+					queue
+						.insert(nft_id, expiry)
+						.map_err(|_| Error::<T>::SimultaneousDelegationLimitReached)?;
+				}
+				Ok(())
+			})?;
+			if let Some(expiry) = expiry {
+				DelegationExpiries::<T>::insert(nft_id, expiry);
+			} else {
+				DelegationExpiries::<T>::remove(nft_id);
+			}
 			if is_delegated {
 				DelegatedNFTs::<T>::insert(nft_id, recipient_account_id.clone());
 			} else {
 				DelegatedNFTs::<T>::remove(nft_id);
 			}
 			let recipient_event = if is_delegated { Some(recipient_account_id) } else { None };
-			let event = Event::NFTDelegated { nft_id, recipient: recipient_event };
+			let event = Event::NFTDelegated { nft_id, recipient: recipient_event, expiry };
 			Self::deposit_event(event);
 
 			Ok(().into())
@@ -697,20 +1502,15 @@ pub mod pallet {
 				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
 
 				// Checks
-				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-				ensure!(nft.creator == who, Error::<T>::NotTheNFTCreator);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotSetRoyaltyForListedNFTs);
-				ensure!(!nft.state.is_delegated, Error::<T>::CannotSetRoyaltyForDelegatedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotSetRoyaltyForRentedNFTs);
-				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotSetRoyaltyForSyncingNFTs);
-				ensure!(
-					!nft.state.is_syncing_capsule,
-					Error::<T>::CannotSetRoyaltyForSyncingCapsules
-				);
-				ensure!(
-					!nft.state.is_transmission,
-					Error::<T>::CannotSetRoyaltyForNFTsInTransmission
-				);
+				Self::ensure_can_set_royalty(nft, &who)?;
+				if let Some(collection_id) = nft.collection_id {
+					let collection = Collections::<T>::get(collection_id)
+						.ok_or(Error::<T>::CollectionNotFound)?;
+					if let Some(max) = collection.max_royalty {
+						ensure!(royalty <= max, Error::<T>::RoyaltyExceedsCollectionMaximum);
+					}
+					RoyaltyOverrides::<T>::insert(nft_id, ());
+				}
 
 				// Execute
 				nft.royalty = royalty;
@@ -785,19 +1585,17 @@ pub mod pallet {
 
 			// Checks
 			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
-			ensure!(collection.nfts.is_empty(), Error::<T>::CollectionIsNotEmpty);
+			ensure!(collection.nfts_count == 0, Error::<T>::CollectionIsNotEmpty);
 
 			// Execute
-			// Remove collection
-			Collections::<T>::remove(collection_id);
-			Self::deposit_event(Event::CollectionBurned { collection_id });
+			Self::remove_collection(collection_id);
 
 			Ok(().into())
 		}
 
 		/// Makes the collection closed. This means that it is not anymore
 		/// possible to add new NFTs to the collection.
-		/// Can only be called by owner of the collection.
+		/// Can only be called by owner, admin or freezer of the collection.
 		#[pallet::weight(T::WeightInfo::close_collection())]
 		pub fn close_collection(
 			origin: OriginFor<T>,
@@ -807,7 +1605,12 @@ pub mod pallet {
 
 			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
 				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
-				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+				ensure!(
+					collection.owner == who ||
+						Self::is_collection_admin(collection_id, &who) ||
+						Self::is_collection_freezer(collection_id, &who),
+					Error::<T>::NotTheCollectionOwner
+				);
 				collection.is_closed = true;
 
 				Ok(().into())
@@ -819,8 +1622,8 @@ pub mod pallet {
 		}
 
 		/// Set the maximum amount of nfts in the collection.
-		/// Caller must be owner of collection, nfts in that collection must be lower or equal to
-		/// new limit.
+		/// Caller must be owner or admin of collection, nfts in that collection must be lower or
+		/// equal to new limit.
 		#[pallet::weight(T::WeightInfo::limit_collection())]
 		pub fn limit_collection(
 			origin: OriginFor<T>,
@@ -833,13 +1636,13 @@ pub mod pallet {
 				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
 
 				// Checks
-				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
-				ensure!(collection.limit == None, Error::<T>::CollectionLimitAlreadySet);
-				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
 				ensure!(
-					collection.nfts.len() <= limit as usize,
-					Error::<T>::CollectionHasTooManyNFTs
+					collection.owner == who || Self::is_collection_admin(collection_id, &who),
+					Error::<T>::NotTheCollectionOwner
 				);
+				ensure!(collection.limit == None, Error::<T>::CollectionLimitAlreadySet);
+				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
+				ensure!(collection.nfts_count <= limit, Error::<T>::CollectionHasTooManyNFTs);
 				ensure!(
 					limit <= T::CollectionSizeLimit::get(),
 					Error::<T>::CollectionLimitExceededMaximumAllowed
@@ -863,7 +1666,7 @@ pub mod pallet {
             {
 				let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
 				if let Ok(collection) = collection {
-					let s = collection.nfts.len();
+					let s = collection.nfts_count;
 					T::WeightInfo::add_nft_to_collection(s as u32)
 				} else {
 					T::WeightInfo::add_nft_to_collection(1)
@@ -886,7 +1689,10 @@ pub mod pallet {
 				// Checks
 				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
 				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
-				ensure!(collection.nfts.len() < limit, Error::<T>::CollectionHasReachedLimit);
+				ensure!(
+					(collection.nfts_count as usize) < limit,
+					Error::<T>::CollectionHasReachedLimit
+				);
 
 				Nfts::<T>::try_mutate(nft_id, |y| -> DispatchResult {
 					let nft = y.as_mut().ok_or(Error::<T>::NFTNotFound)?;
@@ -894,6 +1700,7 @@ pub mod pallet {
 					//Checks
 					ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
 					ensure!(nft.collection_id == None, Error::<T>::NFTBelongToACollection);
+					Self::apply_collection_royalty(nft_id, collection, Some(nft.royalty))?;
 
 					//Execution
 					nft.collection_id = Some(collection_id);
@@ -902,10 +1709,7 @@ pub mod pallet {
 				})?;
 
 				// Execute
-				collection
-					.nfts
-					.try_push(nft_id)
-					.map_err(|_| Error::<T>::CannotAddMoreNFTsToCollection)?;
+				Self::insert_collection_nft(collection_id, collection, nft_id)?;
 
 				Ok(().into())
 			})?;
@@ -915,7 +1719,9 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Add a secret to a basic NFT.
+		/// Add a secret to a basic NFT. The secret NFT mint fee is reserved from the caller until
+		/// the secret is synced, then sent to the fees collector. It is refunded if the sync is
+		/// cancelled.
 		/// Must be called by NFT owner.
 		#[pallet::weight(T::WeightInfo::add_secret())]
 		pub fn add_secret(
@@ -930,35 +1736,32 @@ pub mod pallet {
 
 				// Checks
 				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotAddSecretToListedNFTs);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotAddSecretToLockedNFTs);
 				ensure!(!nft.state.is_secret, Error::<T>::CannotAddSecretToSecretNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotAddSecretToRentedNFTs);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotAddSecretToDelegatedNFTs);
 				ensure!(
 					!nft.state.is_syncing_capsule,
 					Error::<T>::CannotAddSecretToSyncingCapsules
 				);
-				ensure!(
-					!nft.state.is_transmission,
-					Error::<T>::CannotAddSecretToNFTsInTransmission
-				);
 
-				// The Caller needs to pay the Secret NFT Mint fee.
+				// The Caller needs to pay the Secret NFT Mint fee. It is reserved until the secret
+				// is synced.
 				let secret_nft_mint_fee = SecretNftMintFee::<T>::get();
-				let reason = WithdrawReasons::FEE;
-				let imbalance =
-					T::Currency::withdraw(&who, secret_nft_mint_fee, reason, KeepAlive)?;
-				T::FeesCollector::on_unbalanced(imbalance);
+				T::Currency::reserve(&who, secret_nft_mint_fee)?;
+				PendingSecretFees::<T>::insert(nft_id, (who.clone(), secret_nft_mint_fee));
 
 				// Execute
 				nft.state.is_secret = true;
 				nft.state.is_syncing_secret = true;
+				let now = frame_system::Pallet::<T>::block_number();
+				SecretSyncs::<T>::insert(nft_id, (now, None::<ClusterId>));
 
 				SecretNftsOffchainData::<T>::insert(nft_id, offchain_data.clone());
 
 				Ok(().into())
 			})?;
 
+			Self::update_nft_deposit(nft_id)?;
 			let event = Event::SecretAddedToNFT { nft_id, offchain_data };
 			Self::deposit_event(event);
 			Ok(().into())
@@ -967,12 +1770,14 @@ pub mod pallet {
 		/// Create a new secret NFT with the provided details. An ID will be auto
 		/// generated and logged as an event, The caller of this function
 		/// will become the owner of the new NFT.
+		/// NFTs created in a collection without a royalty follow the default royalty of the
+		/// collection.
 		#[pallet::weight((
             {
 				if let Some(collection_id) = &collection_id {
 					let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
 					if let Ok(collection) = collection {
-						let s = collection.nfts.len();
+						let s = collection.nfts_count;
 						T::WeightInfo::create_secret_nft(s as u32)
 					} else {
 						T::WeightInfo::create_secret_nft(1)
@@ -987,7 +1792,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
 			secret_offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
-			royalty: Permill,
+			royalty: Option<Permill>,
 			collection_id: Option<CollectionId>,
 			is_soulbound: bool,
 		) -> DispatchResultWithPostInfo {
@@ -995,7 +1800,7 @@ pub mod pallet {
 
 			// Check balance
 			ensure!(
-				Self::balance_check(&who, NftMintFee::<T>::get() + SecretNftMintFee::<T>::get()),
+				Self::balance_check(&who, Self::mint_fee() + SecretNftMintFee::<T>::get()),
 				Error::<T>::InsufficientBalance
 			);
 
@@ -1018,7 +1823,27 @@ pub mod pallet {
 			let (cluster_id, operator_address) =
 				T::TEEExt::ensure_enclave(who.clone()).ok_or(Error::<T>::NotARegisteredEnclave)?;
 
+			// Shards sent to replace the ones held by removed enclaves.
+			if SecretNftsResharding::<T>::contains_key(nft_id) {
+				let has_finished_resharding =
+					SecretNftShardHolders::<T>::try_mutate(nft_id, |holders| {
+						Self::add_shard_holder(nft_id, cluster_id, operator_address, holders)
+					})?;
+
+				let event = Event::ShardAdded { nft_id, enclave: who };
+				Self::deposit_event(event);
+
+				if has_finished_resharding {
+					SecretNftsResharding::<T>::remove(nft_id);
+					let event = Event::SecretNFTResharded { nft_id };
+					Self::deposit_event(event);
+				}
+
+				return Ok(Pays::No.into())
+			}
+
 			let mut has_finished_sync = false;
+			let mut synced_shards = None;
 
 			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
 				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
@@ -1026,6 +1851,9 @@ pub mod pallet {
 				// Checks
 				ensure!(nft.state.is_secret, Error::<T>::NFTIsNotSecret);
 				ensure!(nft.state.is_syncing_secret, Error::<T>::NFTAlreadySynced);
+				if let Some((_, Some(sync_cluster_id))) = SecretSyncs::<T>::get(nft_id) {
+					ensure!(cluster_id == sync_cluster_id, Error::<T>::ShareNotFromValidCluster);
+				}
 
 				SecretNftsShardsCount::<T>::try_mutate(nft_id, |maybe_shards| -> DispatchResult {
 					if let Some(shards) = maybe_shards {
@@ -1043,7 +1871,7 @@ pub mod pallet {
 							.map_err(|_| Error::<T>::NFTHasReceivedAllShards)?;
 						if shards.len() == T::ShardsNumber::get() as usize {
 							has_finished_sync = true;
-							*maybe_shards = None;
+							synced_shards = maybe_shards.take();
 						}
 					} else {
 						let mut shards: BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber> =
@@ -1054,6 +1882,7 @@ pub mod pallet {
 
 						if shards.len() == T::ShardsNumber::get() as usize {
 							has_finished_sync = true;
+							synced_shards = Some(shards);
 						} else {
 							*maybe_shards = Some(shards);
 						}
@@ -1061,8 +1890,13 @@ pub mod pallet {
 					Ok(().into())
 				})?;
 
-				if has_finished_sync {
+				if let Some(shards) = synced_shards {
 					nft.state.is_syncing_secret = false;
+					SecretSyncs::<T>::remove(nft_id);
+					SecretRotations::<T>::remove(nft_id);
+					Self::collect_secret_fee(nft_id);
+					Self::set_shard_holders(nft_id, &shards);
+					SecretNftShardHolders::<T>::insert(nft_id, shards);
 				}
 
 				Ok(().into())
@@ -1107,28 +1941,39 @@ pub mod pallet {
 
 				// Checks
 				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotConvertListedNFTs);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotConvertLockedNFTs);
 				ensure!(!nft.state.is_capsule, Error::<T>::CannotConvertCapsules);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotConvertRentedNFTs);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotConvertDelegatedNFTs);
 				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotConvertSyncingNFTs);
-				ensure!(!nft.state.is_transmission, Error::<T>::CannotConvertNFTsInTransmission);
 
-				// The Caller needs to pay the Secret NFT Mint fee.
+				// The Caller needs to pay the Capsule Mint fee, the refundable part is reserved.
 				let capsule_mint_fee = CapsuleMintFee::<T>::get();
+				let refundable = T::CapsuleRefundRatio::get() * capsule_mint_fee;
 				let reason = WithdrawReasons::FEE;
-				let imbalance = T::Currency::withdraw(&who, capsule_mint_fee, reason, KeepAlive)?;
+				let imbalance = T::Currency::withdraw(
+					&who,
+					capsule_mint_fee.saturating_sub(refundable),
+					reason,
+					KeepAlive,
+				)?;
 				T::FeesCollector::on_unbalanced(imbalance);
+				if !refundable.is_zero() {
+					T::Currency::reserve(&who, refundable)?;
+					CapsuleDeposits::<T>::insert(nft_id, (who.clone(), refundable));
+				}
 
 				// Execute
 				nft.state.is_capsule = true;
 				nft.state.is_syncing_capsule = true;
+				let now = frame_system::Pallet::<T>::block_number();
+				CapsuleSyncs::<T>::insert(nft_id, (now, None::<ClusterId>));
 
 				CapsuleOffchainData::<T>::insert(nft_id, offchain_data.clone());
 
 				Ok(().into())
 			})?;
 
+			Self::update_nft_deposit(nft_id)?;
 			let event = Event::NFTConvertedToCapsule { nft_id, offchain_data };
 			Self::deposit_event(event);
 			Ok(().into())
@@ -1137,12 +1982,14 @@ pub mod pallet {
 		/// Create a new capsule with the provided details. An ID will be auto
 		/// generated and logged as an event, The caller of this function
 		/// will become the owner of the new NFT.
+		/// NFTs created in a collection without a royalty follow the default royalty of the
+		/// collection.
 		#[pallet::weight((
             {
 				if let Some(collection_id) = &collection_id {
 					let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
 					if let Ok(collection) = collection {
-						let s = collection.nfts.len();
+						let s = collection.nfts_count;
 						T::WeightInfo::create_capsule(s as u32)
 					} else {
 						T::WeightInfo::create_capsule(1)
@@ -1157,7 +2004,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
 			capsule_offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
-			royalty: Permill,
+			royalty: Option<Permill>,
 			collection_id: Option<CollectionId>,
 			is_soulbound: bool,
 		) -> DispatchResultWithPostInfo {
@@ -1165,7 +2012,7 @@ pub mod pallet {
 
 			// Check balance
 			ensure!(
-				Self::balance_check(&who, NftMintFee::<T>::get() + CapsuleMintFee::<T>::get()),
+				Self::balance_check(&who, Self::mint_fee() + CapsuleMintFee::<T>::get()),
 				Error::<T>::InsufficientBalance
 			);
 
@@ -1179,41 +2026,41 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		// TODO: add back when we can revert capsule
-		// /// Revert a capsule to a regular NFT.
-		// #[pallet::weight(T::WeightInfo::revert_capsule())]
-		// pub fn revert_capsule(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo
-		// { 	let who = ensure_signed(origin)?;
-
-		// 	Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
-		// 		let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+		/// Revert a capsule to a regular NFT. The refundable part of the capsule mint fee is
+		/// released to the account that paid it.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::revert_capsule())]
+		pub fn revert_capsule(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
 
-		// 		// Checks
-		// 		ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-		// 		ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
-		// 		ensure!(!nft.state.is_listed, Error::<T>::CannotRevertListedNFTs);
-		// 		ensure!(!nft.state.is_rented, Error::<T>::CannotRevertRentedNFTs);
-		// 		ensure!(!nft.state.is_delegated, Error::<T>::CannotRevertDelegatedNFTs);
-		// 		ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotRevertSyncingNFTs);
-		// 		ensure!(!nft.state.is_transmission, Error::<T>::CannotRevertNFTsInTransmission);
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
 
-		// 		// Execute
-		// 		if nft.state.is_syncing_capsule {
-		// 			CapsulesShardsCount::<T>::remove(nft_id);
-		// 		}
+				// Checks
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotRevertLockedNFTs);
+				ensure!(!nft.state.is_delegated, Error::<T>::CannotRevertDelegatedNFTs);
+				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotRevertSyncingNFTs);
+				ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotRevertSyncingCapsules);
 
-		// 		CapsuleOffchainData::<T>::remove(nft_id);
+				// Execute
+				CapsulesShardsCount::<T>::remove(nft_id);
+				CapsuleOffchainData::<T>::remove(nft_id);
+				Self::remove_capsule_shard_holders(nft_id);
 
-		// 		nft.state.is_capsule = false;
-		// 		nft.state.is_syncing_capsule = false;
+				nft.state.is_capsule = false;
+				nft.state.is_syncing_capsule = false;
 
-		// 		Ok(().into())
-		// 	})?;
+				Ok(().into())
+			})?;
 
-		// 	let event = Event::CapsuleReverted { nft_id };
-		// 	Self::deposit_event(event);
-		// 	Ok(().into())
-		// }
+			let refund = Self::release_capsule_deposit(nft_id);
+			Self::update_nft_deposit(nft_id)?;
+			let event = Event::CapsuleReverted { nft_id, refund };
+			Self::deposit_event(event);
+			Ok(().into())
+		}
 
 		/// Set the capsule offchain data.
 		#[pallet::weight(T::WeightInfo::set_capsule_offchaindata())]
@@ -1230,8 +2077,7 @@ pub mod pallet {
 				// Checks
 				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
 				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotSetOffchainDataForListedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotSetOffchainDataForRentedNFTs);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotSetOffchainDataForLockedNFTs);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotSetOffchainDataForDelegatedNFTs);
 				ensure!(
 					!nft.state.is_syncing_secret,
@@ -1248,6 +2094,7 @@ pub mod pallet {
 				Ok(().into())
 			})?;
 
+			Self::update_nft_deposit(nft_id)?;
 			let event = Event::CapsuleOffchainDataSet { nft_id, offchain_data };
 			Self::deposit_event(event);
 			Ok(().into())
@@ -1279,7 +2126,27 @@ pub mod pallet {
 			let (cluster_id, operator_address) =
 				T::TEEExt::ensure_enclave(who.clone()).ok_or(Error::<T>::NotARegisteredEnclave)?;
 
+			// Shards sent to replace the ones held by removed enclaves.
+			if CapsulesResharding::<T>::contains_key(nft_id) {
+				let has_finished_resharding =
+					CapsuleShardHolders::<T>::try_mutate(nft_id, |holders| {
+						Self::add_shard_holder(nft_id, cluster_id, operator_address, holders)
+					})?;
+
+				let event = Event::CapsuleShardAdded { nft_id, enclave: who };
+				Self::deposit_event(event);
+
+				if has_finished_resharding {
+					CapsulesResharding::<T>::remove(nft_id);
+					let event = Event::CapsuleResharded { nft_id };
+					Self::deposit_event(event);
+				}
+
+				return Ok(Pays::No.into())
+			}
+
 			let mut has_finished_sync = false;
+			let mut synced_shards = None;
 
 			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
 				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
@@ -1287,6 +2154,9 @@ pub mod pallet {
 				// Checks
 				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
 				ensure!(nft.state.is_syncing_capsule, Error::<T>::NFTAlreadySynced);
+				if let Some((_, Some(sync_cluster_id))) = CapsuleSyncs::<T>::get(nft_id) {
+					ensure!(cluster_id == sync_cluster_id, Error::<T>::ShareNotFromValidCluster);
+				}
 
 				CapsulesShardsCount::<T>::try_mutate(nft_id, |maybe_shards| -> DispatchResult {
 					if let Some(shards) = maybe_shards {
@@ -1304,7 +2174,7 @@ pub mod pallet {
 							.map_err(|_| Error::<T>::CapsuleHasReceivedAllShards)?;
 						if shards.len() == T::ShardsNumber::get() as usize {
 							has_finished_sync = true;
-							*maybe_shards = None;
+							synced_shards = maybe_shards.take();
 						}
 					} else {
 						let mut shards: BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber> =
@@ -1315,6 +2185,7 @@ pub mod pallet {
 
 						if shards.len() == T::ShardsNumber::get() as usize {
 							has_finished_sync = true;
+							synced_shards = Some(shards);
 						} else {
 							*maybe_shards = Some(shards);
 						}
@@ -1322,8 +2193,12 @@ pub mod pallet {
 					Ok(().into())
 				})?;
 
-				if has_finished_sync {
+				if let Some(shards) = synced_shards {
 					nft.state.is_syncing_capsule = false;
+					CapsuleSyncs::<T>::remove(nft_id);
+					Self::remove_capsule_shard_holders(nft_id);
+					Self::set_shard_holders(nft_id, &shards);
+					CapsuleShardHolders::<T>::insert(nft_id, shards);
 				}
 
 				Ok(().into())
@@ -1341,6 +2216,7 @@ pub mod pallet {
 		}
 
 		/// Extrinsic called by capsule owner to signify that new keys will be provided to enclaves.
+		/// The enclaves holding the current shards are forgotten.
 		#[pallet::weight(T::WeightInfo::notify_enclave_key_update())]
 		pub fn notify_enclave_key_update(
 			origin: OriginFor<T>,
@@ -1354,21 +2230,19 @@ pub mod pallet {
 				// Checks
 				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
 				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
-				ensure!(!nft.state.is_listed, Error::<T>::CannotChangeKeyForListedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotChangeKeyForRentedNFTs);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotChangeKeyForLockedNFTs);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotChangeKeyForDelegatedNFTs);
 				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotChangeKeyForSyncingNFTs);
 				ensure!(
 					!nft.state.is_syncing_capsule,
 					Error::<T>::CannotChangeKeyForSyncingCapsules
 				);
-				ensure!(
-					!nft.state.is_transmission,
-					Error::<T>::CannotChangeKeyForNFTsInTransmission
-				);
 
 				// Execute
 				nft.state.is_syncing_capsule = true;
+				let now = frame_system::Pallet::<T>::block_number();
+				CapsuleSyncs::<T>::insert(nft_id, (now, None::<ClusterId>));
+				Self::remove_capsule_shard_holders(nft_id);
 
 				Ok(().into())
 			})?;
@@ -1378,7 +2252,8 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Set the capsule offchain data.
+		/// Set the collection offchain data.
+		/// Must be called by the owner or the admin of the collection.
 		#[pallet::weight(T::WeightInfo::set_collection_offchaindata())]
 		pub fn set_collection_offchaindata(
 			origin: OriginFor<T>,
@@ -1391,7 +2266,14 @@ pub mod pallet {
 				let collection = maybe_collection.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
 
 				// Checks
-				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+				ensure!(
+					collection.owner == who || Self::is_collection_admin(collection_id, &who),
+					Error::<T>::NotTheCollectionOwner
+				);
+				ensure!(
+					!FrozenCollectionMetadata::<T>::contains_key(collection_id),
+					Error::<T>::CollectionMetadataFrozen
+				);
 
 				// Execute
 				collection.offchain_data = offchain_data.clone();
@@ -1403,7 +2285,1443 @@ pub mod pallet {
 			Self::deposit_event(event);
 			Ok(().into())
 		}
-	}
+
+		/// Allow an account to transfer an NFT of the caller until the expiry block, if any.
+		/// The approval is replaced by any later approval and cleared when the NFT changes owner.
+		#[pallet::weight(T::WeightInfo::approve_transfer())]
+		pub fn approve_transfer(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			expiry: Option<T::BlockNumber>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(delegate != who, Error::<T>::CannotApproveYourself);
+			if let Some(expiry) = &expiry {
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure!(*expiry > now, Error::<T>::ApprovalExpiryInThePast);
+			}
+
+			// Execute
+			NFTApprovals::<T>::insert(nft_id, (delegate.clone(), expiry));
+			let event = Event::TransferApproved { nft_id, owner: who, delegate, expiry };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Remove the transfer approval of an NFT. Must be called by the owner of the NFT.
+		#[pallet::weight(T::WeightInfo::cancel_approval())]
+		pub fn cancel_approval(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(NFTApprovals::<T>::contains_key(nft_id), Error::<T>::ApprovalNotFound);
+
+			// Execute
+			NFTApprovals::<T>::remove(nft_id);
+			Self::deposit_event(Event::ApprovalCancelled { nft_id });
+
+			Ok(().into())
+		}
+
+		/// Allow or disallow an operator to transfer all the NFTs of the caller.
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: <T::Lookup as StaticLookup>::Source,
+			approved: bool,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let operator = T::Lookup::lookup(operator)?;
+
+			// Checks
+			ensure!(operator != who, Error::<T>::CannotApproveYourself);
+
+			// Execute
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, ());
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
+			}
+			let event = Event::ApprovalForAllSet { owner: who, operator, approved };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Transfer an NFT on behalf of its owner. The caller must be approved for that NFT or
+		/// be an operator of the owner.
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			recipient: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let recipient = T::Lookup::lookup(recipient)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(Self::is_approved(&who, nft_id, &nft.owner), Error::<T>::NotApprovedToTransfer);
+
+			// Execute
+			Self::do_transfer_nft(nft.owner, nft_id, recipient)?;
+
+			Ok(().into())
+		}
+
+		/// Create multiple NFTs in a collection the caller can mint into, as its owner or as one of
+		/// its minters. The mint fee of the created NFTs is charged once in aggregate. In best
+		/// effort mode, the NFTs that cannot be created are skipped and not charged.
+		/// NFTs created without a royalty follow the default royalty of the collection.
+		#[pallet::weight(T::WeightInfo::create_nfts_batch(nfts.len() as u32))]
+		pub fn create_nfts_batch(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			nfts: BoundedVec<
+				(U8BoundedVec<T::NFTOffchainDataLimit>, Option<Permill>, bool),
+				T::BatchSizeLimit,
+			>,
+			mode: BatchMode,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(
+				Self::can_mint_into(collection_id, &collection, &who),
+				Error::<T>::NotTheCollectionOwner
+			);
+			ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
+			let creator = Self::minted_nft_creator(collection_id, &collection, &who);
+			if mode == BatchMode::AllOrNothing {
+				let limit = collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get());
+				let room = limit.saturating_sub(collection.nfts_count) as usize;
+				ensure!(nfts.len() <= room, Error::<T>::CollectionHasReachedLimit);
+				let total_fee = Self::mint_fee().saturating_mul((nfts.len() as u32).into());
+				ensure!(Self::balance_check(&who, total_fee), Error::<T>::InsufficientBalance);
+			}
+
+			// Execute
+			let mut created: u32 = 0;
+			for (index, (offchain_data, royalty, is_soulbound)) in nfts.into_iter().enumerate() {
+				// Each NFT is created in its own storage layer so that a failed item is rolled
+				// back without affecting the others.
+				let result = with_transaction(|| {
+					let result = Self::do_create_batch_nft(
+						collection_id,
+						&mut collection,
+						&who,
+						&creator,
+						offchain_data,
+						royalty,
+						is_soulbound,
+					);
+					match result {
+						Ok(()) => TransactionOutcome::Commit(Ok(())),
+						Err(error) => TransactionOutcome::Rollback(Err(error)),
+					}
+				});
+
+				match result {
+					Ok(()) => created = created.saturating_add(1),
+					Err(error) => match mode {
+						BatchMode::AllOrNothing => return Err(error.into()),
+						BatchMode::BestEffort => {
+							let event = Event::NFTBatchItemFailed { index: index as u32, error };
+							Self::deposit_event(event);
+						},
+					},
+				}
+			}
+			Collections::<T>::insert(collection_id, collection);
+
+			// The caller needs to pay the mint fee of every created NFT.
+			Self::withdraw_mint_fee(&who, created)?;
+
+			Ok(().into())
+		}
+
+		/// Transfer multiple NFTs owned by the caller. In best effort mode, the transfers that
+		/// fail are skipped.
+		#[pallet::weight(T::WeightInfo::transfer_nfts_batch(transfers.len() as u32))]
+		pub fn transfer_nfts_batch(
+			origin: OriginFor<T>,
+			transfers: BoundedVec<(NFTId, <T::Lookup as StaticLookup>::Source), T::BatchSizeLimit>,
+			mode: BatchMode,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			for (index, (nft_id, recipient)) in transfers.into_iter().enumerate() {
+				let result = T::Lookup::lookup(recipient)
+					.map_err(DispatchError::from)
+					.and_then(|recipient| Self::do_transfer_nft(who.clone(), nft_id, recipient));
+
+				if let Err(error) = result {
+					match mode {
+						BatchMode::AllOrNothing => return Err(error.into()),
+						BatchMode::BestEffort => {
+							let event = Event::NFTBatchItemFailed { index: index as u32, error };
+							Self::deposit_event(event);
+						},
+					}
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Set how the royalty of an NFT is split between beneficiaries. `None` removes the
+		/// splits so that the collection default, or the creator, gets the royalty.
+		/// Can only be called if the NFT is owned and has been created by the caller.
+		#[pallet::weight(T::WeightInfo::set_royalty_splits())]
+		pub fn set_royalty_splits(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			splits: Option<RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			Self::ensure_can_set_royalty(&nft, &who)?;
+			if let Some(splits) = &splits {
+				Self::ensure_valid_royalty_splits(splits)?;
+			}
+
+			// Execute
+			NFTRoyaltySplits::<T>::set(nft_id, splits.clone());
+			let event = Event::NFTRoyaltySplitsSet { nft_id, splits };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set the default royalty splits of the NFTs of a collection. NFTs with their own
+		/// splits are not affected. Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_collection_royalty_splits())]
+		pub fn set_collection_royalty_splits(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			splits: Option<RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			if let Some(splits) = &splits {
+				Self::ensure_valid_royalty_splits(splits)?;
+			}
+
+			// Execute
+			CollectionRoyaltySplits::<T>::set(collection_id, splits.clone());
+			let event = Event::CollectionRoyaltySplitsSet { collection_id, splits };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set the default and maximum royalty of the NFTs of a collection.
+		/// NFTs follow the new default unless their royalty was overridden, no NFT is rewritten.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_collection_royalty())]
+		pub fn set_collection_royalty(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			royalty: Option<Permill>,
+			max_royalty: Option<Permill>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+
+				// Checks
+				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+				if let (Some(royalty), Some(max)) = (royalty, max_royalty) {
+					ensure!(royalty <= max, Error::<T>::RoyaltyExceedsCollectionMaximum);
+				}
+
+				// Execute
+				collection.royalty = royalty;
+				collection.max_royalty = max_royalty;
+
+				Ok(())
+			})?;
+
+			let event = Event::CollectionRoyaltySet { collection_id, royalty, max_royalty };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Allow an account to mint NFTs into a collection.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::add_minter(T::CollectionMintersLimit::get()))]
+		pub fn add_minter(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			minter: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let minter = T::Lookup::lookup(minter)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+
+			// Execute
+			CollectionMinters::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				ensure!(!x.contains(&minter), Error::<T>::AlreadyACollectionMinter);
+				x.try_push(minter.clone()).map_err(|_| Error::<T>::TooManyCollectionMinters)?;
+				Ok(())
+			})?;
+			let event = Event::CollectionMinterAdded { collection_id, minter };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Remove an account from the minters of a collection.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::remove_minter(T::CollectionMintersLimit::get()))]
+		pub fn remove_minter(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			minter: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let minter = T::Lookup::lookup(minter)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+
+			// Execute
+			CollectionMinters::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				let index =
+					x.iter().position(|y| *y == minter).ok_or(Error::<T>::NotACollectionMinter)?;
+				x.remove(index);
+				Ok(())
+			})?;
+			let event = Event::CollectionMinterRemoved { collection_id, minter };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set whether the minter or the collection owner is the creator of the NFTs minted by
+		/// the minters of a collection. Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_minter_attribution())]
+		pub fn set_minter_attribution(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			attribution: MinterAttribution,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+
+			// Execute
+			MinterAttributions::<T>::insert(collection_id, attribution);
+			let event = Event::MinterAttributionSet { collection_id, attribution };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Create an NFT in a collection and give it to the recipient. Must be called by the
+		/// owner or a minter of the collection, the caller pays the mint fee.
+		/// NFTs created without a royalty follow the default royalty of the collection.
+		#[pallet::weight((
+            {
+				let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+				if let Ok(collection) = collection {
+					let s = collection.nfts_count;
+					T::WeightInfo::create_nft_for(s as u32)
+				} else {
+					T::WeightInfo::create_nft_for(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn create_nft_for(
+			origin: OriginFor<T>,
+			recipient: <T::Lookup as StaticLookup>::Source,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+			royalty: Option<Permill>,
+			collection_id: CollectionId,
+			is_soulbound: bool,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let recipient = T::Lookup::lookup(recipient)?;
+			Self::do_create_nft(
+				who.clone(),
+				recipient,
+				&who,
+				offchain_data,
+				royalty,
+				Some(collection_id),
+				is_soulbound,
+			)?;
+
+			Ok(().into())
+		}
+
+		/// Set or remove the public mint configuration of a collection.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_mint_config())]
+		pub fn set_mint_config(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			config: Option<CollectionMintConfig<T::AccountId, BalanceOf<T>, T::BlockNumber>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			if let Some(config) = &config {
+				if let (Some(start), Some(end)) = (config.start_block, config.end_block) {
+					ensure!(start <= end, Error::<T>::InvalidMintPeriod);
+				}
+			}
+
+			// Execute
+			CollectionMintConfigs::<T>::set(collection_id, config.clone());
+			let event = Event::CollectionMintConfigSet { collection_id, config };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Add accounts to or remove accounts from the allowlist of a collection public mint.
+		/// Must be called by the owner of the collection, once the allowlist of its previous owner
+		/// is cleared.
+		#[pallet::weight(T::WeightInfo::set_mint_allowlist(accounts.len() as u32))]
+		pub fn set_mint_allowlist(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			accounts: BoundedVec<T::AccountId, T::BatchSizeLimit>,
+			allowed: bool,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			ensure!(
+				!MintAllowlistsToClear::<T>::contains_key(collection_id),
+				Error::<T>::MintAllowlistBeingCleared
+			);
+
+			// Execute
+			for account in accounts.iter() {
+				if allowed {
+					MintAllowlist::<T>::insert(collection_id, account, ());
+				} else {
+					MintAllowlist::<T>::remove(collection_id, account);
+				}
+			}
+			let event = Event::MintAllowlistUpdated { collection_id, accounts, allowed };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Buy an NFT in a collection public mint. The caller pays the mint price to the payout
+		/// account and the NFT mint fee. The collection owner is the creator of the NFT and the
+		/// collection is closed once sold out.
+		#[pallet::weight((
+            {
+				let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+				if let Ok(collection) = collection {
+					let s = collection.nfts_count;
+					T::WeightInfo::public_mint(s as u32)
+				} else {
+					T::WeightInfo::public_mint(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn public_mint(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let config = CollectionMintConfigs::<T>::get(collection_id)
+				.ok_or(Error::<T>::PublicMintNotConfigured)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			// Checks
+			ensure!(config.has_started(now), Error::<T>::PublicMintNotStarted);
+			ensure!(!config.has_ended(now), Error::<T>::PublicMintEnded);
+			if config.is_allowlist_phase(now) {
+				ensure!(
+					!MintAllowlistsToClear::<T>::contains_key(collection_id) &&
+						MintAllowlist::<T>::contains_key(collection_id, &who),
+					Error::<T>::NotInMintAllowlist
+				);
+			}
+			let minted = PublicMints::<T>::get(collection_id, &who);
+			if let Some(max) = config.max_per_account {
+				ensure!(minted < max, Error::<T>::AccountMintLimitReached);
+			}
+
+			let (nft_id, is_sold_out) = Self::do_collection_mint(
+				&who,
+				collection_id,
+				offchain_data,
+				config.price,
+				config.payout.as_ref(),
+			)?;
+
+			// Execute
+			PublicMints::<T>::insert(collection_id, &who, minted.saturating_add(1));
+			let event =
+				Event::NFTPublicMinted { nft_id, collection_id, owner: who, price: config.price };
+			Self::deposit_event(event);
+			if is_sold_out {
+				Self::deposit_event(Event::CollectionClosed { collection_id });
+			}
+
+			Ok(().into())
+		}
+
+		/// Set or remove the Merkle root of a collection mint allowlist. Each leaf is the hash of
+		/// an allowlisted account and the number of NFTs it can mint.
+		#[pallet::weight(T::WeightInfo::set_allowlist_root())]
+		pub fn set_allowlist_root(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			root: Option<MerkleHash>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+				collection.allowlist_root = root;
+
+				Ok(())
+			})?;
+			let event = Event::CollectionAllowlistRootSet { collection_id, root };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Mint an NFT in a collection through its Merkle allowlist. The caller proves that the
+		/// leaf made of its account and `quantity` belongs to the allowlist and can mint up to
+		/// `quantity` NFTs. The collection owner is the creator of the NFT.
+		#[pallet::weight((
+            {
+				let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+				if let Ok(collection) = collection {
+					let s = collection.nfts_count;
+					T::WeightInfo::allowlist_mint(s as u32)
+				} else {
+					T::WeightInfo::allowlist_mint(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn allowlist_mint(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+			quantity: u32,
+			proof: BoundedVec<MerkleHash, T::AllowlistProofLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			let root = collection.allowlist_root.ok_or(Error::<T>::AllowlistNotConfigured)?;
+
+			// Checks
+			let leaf = Self::allowlist_leaf(&who, quantity);
+			ensure!(
+				Self::verify_merkle_proof(root, leaf, &proof),
+				Error::<T>::InvalidAllowlistProof
+			);
+			ensure!(
+				!ClaimedAllowlistLeaves::<T>::contains_key(collection_id, leaf),
+				Error::<T>::AllowlistLeafAlreadyClaimed
+			);
+			let claimed = AllowlistClaims::<T>::get(collection_id, &who);
+			ensure!(claimed < quantity, Error::<T>::AllowlistQuantityExceeded);
+
+			let (nft_id, is_sold_out) =
+				Self::do_collection_mint(&who, collection_id, offchain_data, Zero::zero(), None)?;
+
+			// Execute
+			let claimed = claimed.saturating_add(1);
+			AllowlistClaims::<T>::insert(collection_id, &who, claimed);
+			if claimed >= quantity {
+				ClaimedAllowlistLeaves::<T>::insert(collection_id, leaf, ());
+			}
+			let event = Event::NFTAllowlistMinted { nft_id, collection_id, owner: who };
+			Self::deposit_event(event);
+			if is_sold_out {
+				Self::deposit_event(Event::CollectionClosed { collection_id });
+			}
+
+			Ok(().into())
+		}
+
+		/// Redeem a voucher signed off-chain by an NFT creator over `voucher_signing_payload`. The
+		/// caller pays the voucher price to the creator and the NFT mint fee, and becomes the owner
+		/// of the minted NFT. Each voucher nonce can only be redeemed once per creator.
+		/// NFTs created in a collection without a royalty follow the default royalty of the
+		/// collection.
+		#[pallet::weight((
+            {
+				if let Some(collection_id) = &voucher.collection_id {
+					let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+					if let Ok(collection) = collection {
+						let s = collection.nfts_count;
+						T::WeightInfo::redeem_voucher(s as u32)
+					} else {
+						T::WeightInfo::redeem_voucher(1)
+					}
+				} else {
+					T::WeightInfo::redeem_voucher(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn redeem_voucher(
+			origin: OriginFor<T>,
+			voucher: NFTVoucher<
+				T::AccountId,
+				BalanceOf<T>,
+				T::BlockNumber,
+				T::NFTOffchainDataLimit,
+			>,
+			signature: T::OffchainSignature,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			// Checks
+			ensure!(now <= voucher.expiry, Error::<T>::VoucherExpired);
+			ensure!(
+				!RedeemedVouchers::<T>::contains_key(&voucher.creator, voucher.nonce),
+				Error::<T>::VoucherAlreadyRedeemed
+			);
+			ensure!(
+				signature.verify(&Self::voucher_signing_payload(&voucher)[..], &voucher.creator),
+				Error::<T>::InvalidVoucherSignature
+			);
+
+			// Execute
+			if !voucher.price.is_zero() {
+				T::Currency::transfer(&who, &voucher.creator, voucher.price, KeepAlive)?;
+			}
+			let nft_id = Self::do_create_nft(
+				voucher.creator.clone(),
+				who.clone(),
+				&who,
+				voucher.offchain_data,
+				voucher.royalty,
+				voucher.collection_id,
+				false,
+			)?;
+			RedeemedVouchers::<T>::insert(&voucher.creator, voucher.nonce, ());
+			let event = Event::VoucherRedeemed {
+				nft_id,
+				creator: voucher.creator,
+				owner: who,
+				price: voucher.price,
+				nonce: voucher.nonce,
+			};
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Issue a soulbound NFT to a recipient. The caller is the creator of the NFT and pays
+		/// the mint fee while the recipient owns it and cannot transfer it.
+		#[pallet::weight((
+            {
+				if let Some(collection_id) = &collection_id {
+					let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+					if let Ok(collection) = collection {
+						let s = collection.nfts_count;
+						T::WeightInfo::issue_soulbound(s as u32)
+					} else {
+						T::WeightInfo::issue_soulbound(1)
+					}
+				} else {
+					T::WeightInfo::issue_soulbound(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn issue_soulbound(
+			origin: OriginFor<T>,
+			recipient: <T::Lookup as StaticLookup>::Source,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+			collection_id: Option<CollectionId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let recipient = T::Lookup::lookup(recipient)?;
+			let nft_id = Self::do_create_nft(
+				who.clone(),
+				recipient.clone(),
+				&who,
+				offchain_data,
+				Some(Permill::from_parts(0)),
+				collection_id,
+				true,
+			)?;
+			let event = Event::SoulboundIssued { nft_id, issuer: who, recipient };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Revoke a soulbound NFT issued to another account, burning it.
+		/// Must be called by the creator of the NFT.
+		#[pallet::weight((
+            {
+				let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound);
+				if let Ok(nft) = nft {
+					if let Some(collection_id) = &nft.collection_id {
+						let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+						if let Ok(collection) = collection {
+							let s = collection.nfts_count;
+							T::WeightInfo::revoke_soulbound(s as u32)
+						} else {
+							T::WeightInfo::revoke_soulbound(1)
+						}
+					} else {
+						T::WeightInfo::revoke_soulbound(1)
+					}
+				} else {
+					T::WeightInfo::revoke_soulbound(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn revoke_soulbound(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.creator == who, Error::<T>::NotTheNFTCreator);
+			ensure!(Self::is_issued_soulbound(&nft), Error::<T>::NotAnIssuedSoulboundNFT);
+			nft.can_burn_releasing(T::OnNFTBurn::releases_lock)
+				.map_err(Self::burn_restriction_error)?;
+
+			// Execute
+			Self::remove_nft(nft_id, &nft)?;
+			Self::deposit_event(Event::SoulboundRevoked { nft_id });
+
+			Ok(().into())
+		}
+
+		/// Reject a soulbound NFT issued to the caller, burning it.
+		/// Must be called by the owner of the NFT.
+		#[pallet::weight((
+            {
+				let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound);
+				if let Ok(nft) = nft {
+					if let Some(collection_id) = &nft.collection_id {
+						let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound);
+						if let Ok(collection) = collection {
+							let s = collection.nfts_count;
+							T::WeightInfo::reject_soulbound(s as u32)
+						} else {
+							T::WeightInfo::reject_soulbound(1)
+						}
+					} else {
+						T::WeightInfo::reject_soulbound(1)
+					}
+				} else {
+					T::WeightInfo::reject_soulbound(1)
+				}
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn reject_soulbound(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(Self::is_issued_soulbound(&nft), Error::<T>::NotAnIssuedSoulboundNFT);
+			nft.can_burn_releasing(T::OnNFTBurn::releases_lock)
+				.map_err(Self::burn_restriction_error)?;
+
+			// Execute
+			Self::remove_nft(nft_id, &nft)?;
+			Self::deposit_event(Event::SoulboundRejected { nft_id });
+
+			Ok(().into())
+		}
+
+		/// Nest an NFT in a parent NFT. The nested NFT follows the owner of its parent and
+		/// cannot be transferred, listed or rented on its own until it is unnested.
+		/// Must be called by the owner of both NFTs.
+		#[pallet::weight(T::WeightInfo::nest_nft())]
+		pub fn nest_nft(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			parent_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let parent = Nfts::<T>::get(parent_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(parent.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(!NFTParents::<T>::contains_key(nft_id), Error::<T>::NFTAlreadyNested);
+			let ancestors = Self::ancestors_of(parent_id);
+			ensure!(
+				parent_id != nft_id && !ancestors.contains(&nft_id),
+				Error::<T>::CannotNestIntoDescendant
+			);
+			let root_id = ancestors.last().copied().unwrap_or(parent_id);
+			let root = Nfts::<T>::get(root_id).ok_or(Error::<T>::NFTNotFound)?;
+			ensure!(!root.state.is_locked(), Error::<T>::CannotNestLockedNFTs);
+			let depth = (ancestors.len() as u32)
+				.saturating_add(1)
+				.saturating_add(Self::nesting_height(nft_id));
+			ensure!(depth <= T::NestingDepthLimit::get(), Error::<T>::NestingDepthLimitReached);
+
+			Nfts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				nft.can_transfer().map_err(Self::transfer_restriction_error)?;
+				nft.state.lock(NESTING_LOCK).ok_or(Error::<T>::CannotNestLockedNFTs)?;
+
+				Ok(())
+			})?;
+
+			// Execute
+			NFTChildren::<T>::try_mutate(parent_id, |x| -> DispatchResult {
+				x.try_push(nft_id).map_err(|_| Error::<T>::TooManyNFTChildren)?;
+				Ok(())
+			})?;
+			NFTParents::<T>::insert(nft_id, parent_id);
+			let event = Event::NFTNested { nft_id, parent_id };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Detach a nested NFT from its parent NFT, giving it back to the owner of the parent.
+		/// Must be called by the owner of the NFT.
+		#[pallet::weight(T::WeightInfo::unnest_nft())]
+		pub fn unnest_nft(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let parent_id = NFTParents::<T>::get(nft_id).ok_or(Error::<T>::NFTNotNested)?;
+
+			// Checks
+			let root_id = Self::ancestors_of(nft_id).last().copied().unwrap_or(parent_id);
+			let root = Nfts::<T>::get(root_id).ok_or(Error::<T>::NFTNotFound)?;
+			ensure!(!root.state.is_locked(), Error::<T>::CannotNestLockedNFTs);
+
+			Nfts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				nft.state.unlock(&NESTING_LOCK).ok_or(Error::<T>::NFTLockNotFound)?;
+
+				Ok(())
+			})?;
+
+			// Execute
+			NFTChildren::<T>::mutate_exists(parent_id, |x| {
+				if let Some(children) = x {
+					children.retain(|id| *id != nft_id);
+					if children.is_empty() {
+						*x = None;
+					}
+				}
+			});
+			NFTParents::<T>::remove(nft_id);
+			let event = Event::NFTUnnested { nft_id, parent_id };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set an attribute of an NFT and reserve its deposit. Attributes of the owner namespace
+		/// are set by the NFT owner and the ones of the collection owner namespace by the owner
+		/// of the NFT collection.
+		#[pallet::weight(T::WeightInfo::set_nft_attribute())]
+		pub fn set_nft_attribute(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+			value: U8BoundedVec<T::AttributeValueLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			Self::ensure_can_set_nft_attribute(&nft, namespace, &who)?;
+
+			// Execute
+			let attribute_key = (namespace, key.clone());
+			let old = NFTAttributes::<T>::get(nft_id, &attribute_key).and_then(|x| x.1);
+			let deposit = Self::reserve_attribute_deposit(&who, old, key.len(), value.len())?;
+			NFTAttributes::<T>::insert(nft_id, attribute_key, (value.clone(), deposit));
+			let event = Event::NFTAttributeSet { nft_id, namespace, key, value };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Clear an attribute of an NFT and release its deposit.
+		#[pallet::weight(T::WeightInfo::clear_nft_attribute())]
+		pub fn clear_nft_attribute(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			namespace: AttributeNamespace,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			Self::ensure_can_set_nft_attribute(&nft, namespace, &who)?;
+			let (_, deposit) = NFTAttributes::<T>::take(nft_id, (namespace, key.clone()))
+				.ok_or(Error::<T>::AttributeNotFound)?;
+
+			// Execute
+			Self::unreserve_attribute_deposit(deposit);
+			let event = Event::NFTAttributeCleared { nft_id, namespace, key };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set an attribute of a collection in the collection owner namespace and reserve its
+		/// deposit. Must be called by the collection owner.
+		#[pallet::weight(T::WeightInfo::set_collection_attribute())]
+		pub fn set_collection_attribute(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+			value: U8BoundedVec<T::AttributeValueLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			// Checks
+			Self::ensure_can_set_collection_attribute(collection_id, &who)?;
+
+			// Execute
+			let namespace = AttributeNamespace::CollectionOwner;
+			let attribute_key = (namespace, key.clone());
+			let old =
+				CollectionAttributes::<T>::get(collection_id, &attribute_key).and_then(|x| x.1);
+			let deposit = Self::reserve_attribute_deposit(&who, old, key.len(), value.len())?;
+			CollectionAttributes::<T>::insert(
+				collection_id,
+				attribute_key,
+				(value.clone(), deposit),
+			);
+			let event = Event::CollectionAttributeSet { collection_id, namespace, key, value };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Clear an attribute of a collection in the collection owner namespace and release its
+		/// deposit. Must be called by the collection owner.
+		#[pallet::weight(T::WeightInfo::clear_collection_attribute())]
+		pub fn clear_collection_attribute(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			key: U8BoundedVec<T::AttributeKeyLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			// Checks
+			Self::ensure_can_set_collection_attribute(collection_id, &who)?;
+			let namespace = AttributeNamespace::CollectionOwner;
+			let (_, deposit) =
+				CollectionAttributes::<T>::take(collection_id, (namespace, key.clone()))
+					.ok_or(Error::<T>::AttributeNotFound)?;
+
+			// Execute
+			Self::unreserve_attribute_deposit(deposit);
+			let event = Event::CollectionAttributeCleared { collection_id, namespace, key };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Lock the collection owner attributes of a collection and of its NFTs. This operation
+		/// is irreversible. Must be called by the collection owner.
+		#[pallet::weight(T::WeightInfo::lock_collection_attributes())]
+		pub fn lock_collection_attributes(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			// Checks
+			Self::ensure_can_set_collection_attribute(collection_id, &who)?;
+
+			// Execute
+			LockedCollectionAttributes::<T>::insert(collection_id, ());
+			Self::deposit_event(Event::CollectionAttributesLocked { collection_id });
+
+			Ok(().into())
+		}
+
+		/// Update the offchain data of an NFT and keep the hash of the previous one. Depending on
+		/// the mutability policy of its collection, must be called by the creator of the NFT while
+		/// owning it or by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_nft_offchaindata())]
+		pub fn set_nft_offchaindata(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+				// Checks
+				Self::ensure_can_update_nft_metadata(nft_id, nft, &who)?;
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotSetOffchainDataForLockedNFTs);
+				ensure!(!nft.state.is_delegated, Error::<T>::CannotSetOffchainDataForDelegatedNFTs);
+
+				// Execute
+				let old_hash = T::Hashing::hash(&nft.offchain_data);
+				NFTMetadataHistory::<T>::mutate(nft_id, |history| {
+					if history.is_full() {
+						history.remove(0);
+					}
+					let _ = history.try_push(old_hash);
+				});
+				nft.offchain_data = offchain_data.clone();
+
+				Ok(())
+			})?;
+
+			Self::update_nft_deposit(nft_id)?;
+			let event = Event::NFTOffchainDataSet { nft_id, offchain_data };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set who can update the offchain data of the NFTs of a collection. Must be called by the
+		/// owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_collection_metadata_mutability())]
+		pub fn set_collection_metadata_mutability(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			mutability: MetadataMutability,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			ensure!(
+				!FrozenCollectionMetadata::<T>::contains_key(collection_id),
+				Error::<T>::CollectionMetadataFrozen
+			);
+
+			// Execute
+			CollectionMetadataMutability::<T>::insert(collection_id, mutability);
+			let event = Event::CollectionMetadataMutabilitySet { collection_id, mutability };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Freeze the offchain data of an NFT. This operation is irreversible. Must be called by
+		/// an account allowed to update the offchain data of the NFT.
+		#[pallet::weight(T::WeightInfo::freeze_nft_metadata())]
+		pub fn freeze_nft_metadata(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			Self::ensure_can_update_nft_metadata(nft_id, &nft, &who)?;
+
+			// Execute
+			FrozenNFTMetadata::<T>::insert(nft_id, ());
+			Self::deposit_event(Event::NFTMetadataFrozen { nft_id });
+
+			Ok(().into())
+		}
+
+		/// Freeze the offchain data of a collection and of all its NFTs. This operation is
+		/// irreversible. Must be called by the owner or the freezer of the collection.
+		#[pallet::weight(T::WeightInfo::freeze_collection_metadata())]
+		pub fn freeze_collection_metadata(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(
+				collection.owner == who || Self::is_collection_freezer(collection_id, &who),
+				Error::<T>::NotTheCollectionOwner
+			);
+			ensure!(
+				!FrozenCollectionMetadata::<T>::contains_key(collection_id),
+				Error::<T>::CollectionMetadataFrozen
+			);
+
+			// Execute
+			FrozenCollectionMetadata::<T>::insert(collection_id, ());
+			Self::deposit_event(Event::CollectionMetadataFrozen { collection_id });
+
+			Ok(().into())
+		}
+
+		/// Restart the sync of the secret of an NFT on another cluster once the sync timed out.
+		/// The shards received so far are discarded.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::retry_secret_sync())]
+		pub fn retry_secret_sync(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			cluster_id: ClusterId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(nft.state.is_secret, Error::<T>::NFTIsNotSecret);
+			ensure!(nft.state.is_syncing_secret, Error::<T>::NFTAlreadySynced);
+			Self::ensure_sync_timed_out(SecretSyncs::<T>::get(nft_id))?;
+			ensure!(T::TEEExt::cluster_exists(cluster_id), Error::<T>::ClusterNotFound);
+
+			// Execute
+			let now = frame_system::Pallet::<T>::block_number();
+			SecretNftsShardsCount::<T>::remove(nft_id);
+			SecretSyncs::<T>::insert(nft_id, (now, Some(cluster_id)));
+			let event = Event::SecretSyncRetried { nft_id, cluster_id };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Remove the secret of an NFT whose sync timed out and refund the secret NFT mint fee.
+		/// The sync of a rotated secret cannot be cancelled.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::cancel_secret_sync())]
+		pub fn cancel_secret_sync(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+				// Checks
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				ensure!(nft.state.is_secret, Error::<T>::NFTIsNotSecret);
+				ensure!(nft.state.is_syncing_secret, Error::<T>::NFTAlreadySynced);
+				ensure!(
+					!SecretRotations::<T>::contains_key(nft_id),
+					Error::<T>::CannotCancelSecretRotation
+				);
+				Self::ensure_sync_timed_out(SecretSyncs::<T>::get(nft_id))?;
+
+				// Execute
+				SecretNftsShardsCount::<T>::remove(nft_id);
+				SecretNftsOffchainData::<T>::remove(nft_id);
+				SecretSyncs::<T>::remove(nft_id);
+				nft.state.is_secret = false;
+				nft.state.is_syncing_secret = false;
+
+				Ok(())
+			})?;
+
+			let refund = match PendingSecretFees::<T>::take(nft_id) {
+				Some((payer, fee)) => {
+					T::Currency::unreserve(&payer, fee);
+					fee
+				},
+				None => Zero::zero(),
+			};
+			Self::update_nft_deposit(nft_id)?;
+			let event = Event::SecretSyncCancelled { nft_id, refund };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Restart the sync of the capsule of an NFT on another cluster once the sync timed out.
+		/// The shards received so far are discarded.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::retry_capsule_sync())]
+		pub fn retry_capsule_sync(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			cluster_id: ClusterId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+			ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
+			ensure!(nft.state.is_syncing_capsule, Error::<T>::NFTAlreadySynced);
+			Self::ensure_sync_timed_out(CapsuleSyncs::<T>::get(nft_id))?;
+			ensure!(T::TEEExt::cluster_exists(cluster_id), Error::<T>::ClusterNotFound);
+
+			// Execute
+			let now = frame_system::Pallet::<T>::block_number();
+			CapsulesShardsCount::<T>::remove(nft_id);
+			CapsuleSyncs::<T>::insert(nft_id, (now, Some(cluster_id)));
+			let event = Event::CapsuleSyncRetried { nft_id, cluster_id };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Revert a capsule whose sync timed out to a regular NFT and release the refundable part
+		/// of the capsule mint fee.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::cancel_capsule_sync())]
+		pub fn cancel_capsule_sync(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+				// Checks
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
+				ensure!(nft.state.is_syncing_capsule, Error::<T>::NFTAlreadySynced);
+				Self::ensure_sync_timed_out(CapsuleSyncs::<T>::get(nft_id))?;
+
+				// Execute
+				CapsulesShardsCount::<T>::remove(nft_id);
+				CapsuleOffchainData::<T>::remove(nft_id);
+				CapsuleSyncs::<T>::remove(nft_id);
+				Self::remove_capsule_shard_holders(nft_id);
+				nft.state.is_capsule = false;
+				nft.state.is_syncing_capsule = false;
+
+				Ok(())
+			})?;
+
+			let refund = Self::release_capsule_deposit(nft_id);
+			Self::update_nft_deposit(nft_id)?;
+			let event = Event::CapsuleSyncCancelled { nft_id, refund };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Offer to transfer a collection to another account. The collection changes owner once
+		/// the recipient accepts the transfer. Any previous offer is replaced.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::transfer_collection())]
+		pub fn transfer_collection(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			recipient: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let recipient = T::Lookup::lookup(recipient)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			ensure!(recipient != who, Error::<T>::CannotTransferCollectionToYourself);
+
+			// Execute
+			CollectionTransfers::<T>::insert(collection_id, recipient.clone());
+			let event = Event::CollectionTransferRequested { collection_id, owner: who, recipient };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Accept the transfer of a collection. The admin, the freezer, the minters, the minter
+		/// attribution, the metadata mutability, the royalty splits and the mint allowlists of the
+		/// collection are reset and its public mint proceeds go to the new owner. The mint
+		/// allowlist is cleared over the next blocks. The locked attributes and the frozen metadata
+		/// are kept, as they are irreversible commitments to the holders of the NFTs.
+		/// Must be called by the recipient of the transfer.
+		#[pallet::weight(T::WeightInfo::accept_collection_transfer())]
+		pub fn accept_collection_transfer(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let recipient = CollectionTransfers::<T>::get(collection_id)
+				.ok_or(Error::<T>::CollectionTransferNotFound)?;
+
+			// Checks
+			ensure!(recipient == who, Error::<T>::NotTheCollectionTransferRecipient);
+
+			// Execute
+			let from =
+				Collections::<T>::try_mutate(collection_id, |x| -> Result<_, DispatchError> {
+					let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+					collection.allowlist_root = None;
+					Ok(sp_std::mem::replace(&mut collection.owner, who.clone()))
+				})?;
+			CollectionTransfers::<T>::remove(collection_id);
+			CollectionAdmins::<T>::remove(collection_id);
+			CollectionFreezers::<T>::remove(collection_id);
+			CollectionMinters::<T>::remove(collection_id);
+			MinterAttributions::<T>::remove(collection_id);
+			CollectionMetadataMutability::<T>::remove(collection_id);
+			CollectionRoyaltySplits::<T>::remove(collection_id);
+			MintAllowlistsToClear::<T>::insert(collection_id, ());
+			CollectionMintConfigs::<T>::mutate(collection_id, |x| {
+				if let Some(config) = x {
+					config.payout = None;
+				}
+			});
+			let event = Event::CollectionTransferred { collection_id, from, to: who };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Cancel the pending transfer of a collection.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::cancel_collection_transfer())]
+		pub fn cancel_collection_transfer(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			ensure!(
+				CollectionTransfers::<T>::contains_key(collection_id),
+				Error::<T>::CollectionTransferNotFound
+			);
+
+			// Execute
+			CollectionTransfers::<T>::remove(collection_id);
+			Self::deposit_event(Event::CollectionTransferCancelled { collection_id });
+
+			Ok(().into())
+		}
+
+		/// Set the admin and the freezer of a collection, or clear them with `None`. The admin
+		/// can update the offchain data and the limit of the collection and close it, the freezer
+		/// can close the collection and freeze its offchain data. Neither can transfer it.
+		/// Must be called by the owner of the collection.
+		#[pallet::weight(T::WeightInfo::set_collection_roles())]
+		pub fn set_collection_roles(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			admin: Option<<T::Lookup as StaticLookup>::Source>,
+			freezer: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let admin = admin.map(T::Lookup::lookup).transpose()?;
+			let freezer = freezer.map(T::Lookup::lookup).transpose()?;
+			let collection =
+				Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+			// Checks
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+
+			// Execute
+			CollectionAdmins::<T>::set(collection_id, admin.clone());
+			CollectionFreezers::<T>::set(collection_id, freezer.clone());
+			let event = Event::CollectionRolesSet { collection_id, admin, freezer };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Rotate the secret of a synced secret NFT, replacing its secret offchain data if
+		/// provided. The NFT is synced again and needs a new set of shards from a single cluster.
+		/// If this sync times out, it can be retried on another cluster but not cancelled.
+		/// Must be called by NFT owner.
+		#[pallet::weight(T::WeightInfo::rotate_secret())]
+		pub fn rotate_secret(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			offchain_data: Option<U8BoundedVec<T::NFTOffchainDataLimit>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+				// Checks
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				ensure!(nft.state.is_secret, Error::<T>::NFTIsNotSecret);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotChangeKeyForLockedNFTs);
+				ensure!(!nft.state.is_delegated, Error::<T>::CannotChangeKeyForDelegatedNFTs);
+				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotChangeKeyForSyncingNFTs);
+				ensure!(
+					!nft.state.is_syncing_capsule,
+					Error::<T>::CannotChangeKeyForSyncingCapsules
+				);
+
+				// Execute
+				nft.state.is_syncing_secret = true;
+				let now = frame_system::Pallet::<T>::block_number();
+				SecretSyncs::<T>::insert(nft_id, (now, None::<ClusterId>));
+				SecretRotations::<T>::insert(nft_id, ());
+				SecretNftsShardsCount::<T>::remove(nft_id);
+				Self::remove_secret_shard_holders(nft_id);
+				if let Some(offchain_data) = &offchain_data {
+					SecretNftsOffchainData::<T>::insert(nft_id, offchain_data);
+				}
+
+				Ok(())
+			})?;
+
+			if offchain_data.is_some() {
+				Self::update_nft_deposit(nft_id)?;
+			}
+			let event = Event::SecretRotated { nft_id, offchain_data };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+	}
 }
 
 impl<T: Config> traits::NFTExt for Pallet<T> {
@@ -1413,120 +3731,1148 @@ impl<T: Config> traits::NFTExt for Pallet<T> {
 	type CollectionSizeLimit = T::CollectionSizeLimit;
 	type ShardsNumber = T::ShardsNumber;
 
-	fn set_nft_state(nft_id: NFTId, nft_state: NFTState) -> DispatchResult {
-		Nfts::<T>::try_mutate(nft_id, |data| -> DispatchResult {
-			let data = data.as_mut().ok_or(Error::<T>::NFTNotFound)?;
-			data.state = nft_state;
+	fn set_nft_state(nft_id: NFTId, nft_state: NFTState) -> DispatchResult {
+		Nfts::<T>::try_mutate(nft_id, |data| -> DispatchResult {
+			let data = data.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+			data.state = nft_state;
+
+			Ok(())
+		})?;
+
+		Ok(())
+	}
+
+	fn create_filled_collection(
+		owner: Self::AccountId,
+		collection_id: CollectionId,
+		start_nft_id: NFTId,
+		amount_in_collection: u32,
+	) -> DispatchResult {
+		//Create full collection
+		let collection_offchain_data: U8BoundedVec<Self::CollectionOffchainDataLimit> =
+			U8BoundedVec::try_from(vec![
+				1;
+				Self::CollectionOffchainDataLimit::get()
+					.try_into()
+					.unwrap()
+			])
+			.expect("It will never happen.");
+
+		let mut collection = Collection::<Self::AccountId, Self::CollectionOffchainDataLimit>::new(
+			owner.clone(),
+			collection_offchain_data,
+			None,
+		);
+
+		for nft_id in start_nft_id..amount_in_collection + start_nft_id {
+			CollectionNfts::<T>::insert(collection_id, nft_id, ());
+		}
+
+		collection.nfts_count = amount_in_collection;
+		Collections::<T>::insert(collection_id, collection);
+
+		Ok(())
+	}
+
+	fn get_nft(id: NFTId) -> Option<NFTData<Self::AccountId, Self::NFTOffchainDataLimit>> {
+		Nfts::<T>::get(id)
+	}
+
+	fn set_nft(
+		id: NFTId,
+		nft_data: NFTData<Self::AccountId, Self::NFTOffchainDataLimit>,
+	) -> DispatchResult {
+		let old_owner = Nfts::<T>::get(id).map(|x| x.owner);
+		Self::handle_owner_change(id, old_owner.as_ref(), Some(&nft_data.owner));
+		Nfts::<T>::insert(id, nft_data);
+
+		Ok(())
+	}
+
+	fn create_nft(
+		owner: Self::AccountId,
+		offchain_data: BoundedVec<u8, Self::NFTOffchainDataLimit>,
+		royalty: Permill,
+		collection_id: Option<CollectionId>,
+		is_soulbound: bool,
+	) -> Result<NFTId, DispatchResult> {
+		let nft_id = Self::get_next_nft_id();
+		Self::reserve_nft_deposit(nft_id, &owner, offchain_data.len()).map_err(Err)?;
+		let nft_state = NFTState::new_default(is_soulbound);
+		let nft = NFTData::new(
+			owner.clone(),
+			owner.clone(),
+			offchain_data,
+			royalty,
+			nft_state,
+			collection_id,
+		);
+		Nfts::<T>::insert(nft_id, nft);
+		AccountNfts::<T>::insert(owner, nft_id, ());
+
+		Ok(nft_id)
+	}
+
+	fn mutate_nft<
+		R,
+		E,
+		F: FnOnce(&mut Option<NFTData<Self::AccountId, Self::NFTOffchainDataLimit>>) -> Result<R, E>,
+	>(
+		id: NFTId,
+		f: F,
+	) -> Result<R, E> {
+		Nfts::<T>::try_mutate(id, |x| {
+			let old_owner = x.as_ref().map(|nft| nft.owner.clone());
+			let result = f(x)?;
+			Self::handle_owner_change(id, old_owner.as_ref(), x.as_ref().map(|nft| &nft.owner));
+
+			Ok(result)
+		})
+	}
+
+	fn get_royalty(id: NFTId) -> Permill {
+		Nfts::<T>::get(id).map_or(Permill::from_parts(0), |nft| Self::royalty_of(id, &nft))
+	}
+
+	fn get_royalty_splits(id: NFTId) -> Vec<(Self::AccountId, Permill)> {
+		Nfts::<T>::get(id).map_or_else(Vec::new, |nft| Self::royalty_splits_of(id, &nft))
+	}
+
+	fn exists(id: NFTId) -> bool {
+		Nfts::<T>::contains_key(id)
+	}
+
+	fn lock_nft(id: NFTId, lock: NFTLock) -> DispatchResult {
+		Nfts::<T>::try_mutate(id, |x| -> DispatchResult {
+			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+			ensure!(!nft.state.is_locked_by(&lock), Error::<T>::NFTAlreadyLocked);
+			nft.state.lock(lock).ok_or(Error::<T>::TooManyNFTLocks)?;
+
+			Ok(())
+		})
+	}
+
+	fn unlock_nft(id: NFTId, lock: NFTLock) -> DispatchResult {
+		Nfts::<T>::try_mutate(id, |x| -> DispatchResult {
+			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+			nft.state.unlock(&lock).ok_or(Error::<T>::NFTLockNotFound)?;
+
+			Ok(())
+		})
+	}
+
+	fn get_nft_attribute(id: NFTId, namespace: AttributeNamespace, key: &[u8]) -> Option<Vec<u8>> {
+		let key = U8BoundedVec::<T::AttributeKeyLimit>::try_from(key.to_vec()).ok()?;
+		NFTAttributes::<T>::get(id, (namespace, key)).map(|x| x.0.into_inner())
+	}
+
+	fn get_collection_attribute(
+		id: CollectionId,
+		namespace: AttributeNamespace,
+		key: &[u8],
+	) -> Option<Vec<u8>> {
+		let key = U8BoundedVec::<T::AttributeKeyLimit>::try_from(key.to_vec()).ok()?;
+		CollectionAttributes::<T>::get(id, (namespace, key)).map(|x| x.0.into_inner())
+	}
+
+	fn set_nft_pallet_attribute(id: NFTId, key: &[u8], value: &[u8]) -> DispatchResult {
+		ensure!(Nfts::<T>::contains_key(id), Error::<T>::NFTNotFound);
+		let key = U8BoundedVec::<T::AttributeKeyLimit>::try_from(key.to_vec())
+			.map_err(|_| Error::<T>::AttributeKeyTooLong)?;
+		let value = U8BoundedVec::<T::AttributeValueLimit>::try_from(value.to_vec())
+			.map_err(|_| Error::<T>::AttributeValueTooLong)?;
+		let namespace = AttributeNamespace::Pallet;
+		NFTAttributes::<T>::insert(id, (namespace, key.clone()), (value.clone(), None));
+		Self::deposit_event(Event::NFTAttributeSet { nft_id: id, namespace, key, value });
+
+		Ok(())
+	}
+
+	fn clear_nft_pallet_attribute(id: NFTId, key: &[u8]) -> DispatchResult {
+		let key = U8BoundedVec::<T::AttributeKeyLimit>::try_from(key.to_vec())
+			.map_err(|_| Error::<T>::AttributeNotFound)?;
+		let namespace = AttributeNamespace::Pallet;
+		NFTAttributes::<T>::take(id, (namespace, key.clone()))
+			.ok_or(Error::<T>::AttributeNotFound)?;
+		Self::deposit_event(Event::NFTAttributeCleared { nft_id: id, namespace, key });
+
+		Ok(())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn get_next_nft_id() -> NFTId {
+		let nft_id = NextNFTId::<T>::get();
+		let next_id = nft_id
+			.checked_add(1)
+			.expect("If u32 is not enough we should crash for safety; qed.");
+		NextNFTId::<T>::put(next_id);
+
+		nft_id
+	}
+
+	fn get_next_collection_id() -> NFTId {
+		let collection_id = NextCollectionId::<T>::get();
+		let next_id = collection_id
+			.checked_add(1)
+			.expect("If u32 is not enough we should crash for safety; qed.");
+		NextCollectionId::<T>::put(next_id);
+
+		collection_id
+	}
+
+	/// Remove an empty collection with its roles, mint settings and attributes, releasing the
+	/// attribute deposits. Its mint allowlist and mint counters are cleared over the next blocks.
+	/// The burn checks must be done by the caller.
+	fn remove_collection(collection_id: CollectionId) {
+		Collections::<T>::remove(collection_id);
+		CollectionNftsToMigrate::<T>::remove(collection_id);
+		CollectionRoyaltySplits::<T>::remove(collection_id);
+		CollectionMinters::<T>::remove(collection_id);
+		MinterAttributions::<T>::remove(collection_id);
+		CollectionMintConfigs::<T>::remove(collection_id);
+		LockedCollectionAttributes::<T>::remove(collection_id);
+		CollectionMetadataMutability::<T>::remove(collection_id);
+		FrozenCollectionMetadata::<T>::remove(collection_id);
+		CollectionTransfers::<T>::remove(collection_id);
+		CollectionAdmins::<T>::remove(collection_id);
+		CollectionFreezers::<T>::remove(collection_id);
+		for (_, (_, deposit)) in CollectionAttributes::<T>::drain_prefix(collection_id) {
+			Self::unreserve_attribute_deposit(deposit);
+		}
+		MintAllowlistsToClear::<T>::insert(collection_id, ());
+		CollectionMintsToClear::<T>::insert(collection_id, ());
+		Self::deposit_event(Event::CollectionBurned { collection_id });
+	}
+
+	/// Clear the mint allowlists queued by collection transfers and burns, then the public mint
+	/// counters of the burned collections, without exceeding the given weight. Returns the
+	/// consumed weight.
+	fn clear_collection_mints(limit: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let mut weight = db_weight.reads(2);
+
+		while let Some(collection_id) = MintAllowlistsToClear::<T>::iter_keys().next() {
+			let is_cleared = Self::clear_keys(&mut weight, limit, |keys| {
+				let result = MintAllowlist::<T>::clear_prefix(collection_id, keys, None);
+				(result.unique, result.maybe_cursor.is_none())
+			});
+			if !is_cleared {
+				return weight
+			}
+			MintAllowlistsToClear::<T>::remove(collection_id);
+			weight = weight.saturating_add(db_weight.writes(1));
+		}
+
+		while let Some(collection_id) = CollectionMintsToClear::<T>::iter_keys().next() {
+			let is_cleared = Self::clear_keys(&mut weight, limit, |keys| {
+				let result = PublicMints::<T>::clear_prefix(collection_id, keys, None);
+				(result.unique, result.maybe_cursor.is_none())
+			}) && Self::clear_keys(&mut weight, limit, |keys| {
+				let result = AllowlistClaims::<T>::clear_prefix(collection_id, keys, None);
+				(result.unique, result.maybe_cursor.is_none())
+			}) && Self::clear_keys(&mut weight, limit, |keys| {
+				let result = ClaimedAllowlistLeaves::<T>::clear_prefix(collection_id, keys, None);
+				(result.unique, result.maybe_cursor.is_none())
+			});
+			if !is_cleared {
+				return weight
+			}
+			CollectionMintsToClear::<T>::remove(collection_id);
+			weight = weight.saturating_add(db_weight.writes(1));
+		}
+
+		weight
+	}
+
+	/// Remove as many keys with `clear` as the weight left under the limit allows, keeping room
+	/// to dequeue the cleared collection, and add the consumed weight. `clear` returns the number
+	/// of removed keys and whether all the keys are removed, which is the returned value.
+	fn clear_keys(
+		weight: &mut Weight,
+		limit: Weight,
+		clear: impl FnOnce(u32) -> (u32, bool),
+	) -> bool {
+		let db_weight = T::DbWeight::get();
+		let reserved = weight.saturating_add(db_weight.reads_writes(1, 1));
+		let keys = limit.ref_time().saturating_sub(reserved.ref_time()) /
+			db_weight.writes(1).ref_time().max(1);
+		if keys == 0 {
+			return false
+		}
+
+		let (removed, is_cleared) = clear(u32::try_from(keys).unwrap_or(u32::MAX));
+		*weight = weight.saturating_add(db_weight.reads_writes(1, removed.into()));
+		is_cleared
+	}
+
+	/// Remove an NFT with its collection entry, secret and capsule data.
+	/// The burn checks must be done by the caller.
+	fn remove_nft(
+		nft_id: NFTId,
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+	) -> DispatchResult {
+		ensure!(NFTChildren::<T>::get(nft_id).is_empty(), Error::<T>::CannotBurnNFTsWithChildren);
+
+		// Check for collection to remove nft.
+		if let Some(collection_id) = &nft.collection_id {
+			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+				collection.nfts_count = collection.nfts_count.saturating_sub(1);
+				CollectionNfts::<T>::remove(collection_id, nft_id);
+				Ok(())
+			})?;
+		}
+
+		// Check for secret nft to remove secret offchain data and shards count.
+		if nft.state.is_secret {
+			SecretNftsOffchainData::<T>::remove(nft_id);
+			if nft.state.is_syncing_secret {
+				SecretNftsShardsCount::<T>::remove(nft_id);
+				SecretSyncs::<T>::remove(nft_id);
+				SecretRotations::<T>::remove(nft_id);
+				Self::collect_secret_fee(nft_id);
+			}
+		}
+
+		// Check for capsule to remove capsule offchain data and capsule shards count.
+		if nft.state.is_capsule {
+			Self::release_capsule_deposit(nft_id);
+			CapsuleOffchainData::<T>::remove(nft_id);
+			if nft.state.is_syncing_capsule {
+				CapsulesShardsCount::<T>::remove(nft_id);
+				CapsuleSyncs::<T>::remove(nft_id);
+			}
+		}
+
+		// Forget the enclaves holding the shards of the NFT.
+		Self::remove_capsule_shard_holders(nft_id);
+		Self::remove_secret_shard_holders(nft_id);
+
+		for (_, (_, deposit)) in NFTAttributes::<T>::drain_prefix(nft_id) {
+			Self::unreserve_attribute_deposit(deposit);
+		}
+		Nfts::<T>::remove(nft_id);
+		NFTRoyaltySplits::<T>::remove(nft_id);
+		FrozenNFTMetadata::<T>::remove(nft_id);
+		NFTMetadataHistory::<T>::remove(nft_id);
+		if let Some((depositor, deposit)) = NFTDeposits::<T>::take(nft_id) {
+			T::Currency::unreserve(&depositor, deposit);
+		}
+		RoyaltyOverrides::<T>::remove(nft_id);
+		Self::handle_owner_change(nft_id, Some(&nft.owner), None);
+		Self::notify_nft_burn(nft_id, &nft.owner);
+		Self::deposit_event(Event::NFTBurned { nft_id });
+
+		Ok(())
+	}
+
+	/// Checks that the offchain data of an NFT is not frozen and that an account is allowed to
+	/// update it by the mutability policy of its collection.
+	fn ensure_can_update_nft_metadata(
+		nft_id: NFTId,
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		ensure!(!FrozenNFTMetadata::<T>::contains_key(nft_id), Error::<T>::NFTMetadataFrozen);
+
+		let mutability = match nft.collection_id {
+			Some(collection_id) => {
+				ensure!(
+					!FrozenCollectionMetadata::<T>::contains_key(collection_id),
+					Error::<T>::CollectionMetadataFrozen
+				);
+				CollectionMetadataMutability::<T>::get(collection_id)
+			},
+			None => MetadataMutability::CreatorOwner,
+		};
+		match mutability {
+			MetadataMutability::CreatorOwner => {
+				ensure!(nft.owner == *who, Error::<T>::NotTheNFTOwner);
+				ensure!(nft.creator == *who, Error::<T>::NotTheNFTCreator);
+			},
+			MetadataMutability::CollectionOwner => {
+				let collection_id = nft.collection_id.ok_or(Error::<T>::NFTNotInACollection)?;
+				let collection =
+					Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+				ensure!(collection.owner == *who, Error::<T>::NotTheCollectionOwner);
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Checks that an account can set an attribute of an NFT in a namespace.
+	fn ensure_can_set_nft_attribute(
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+		namespace: AttributeNamespace,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		match namespace {
+			AttributeNamespace::Owner => {
+				ensure!(nft.owner == *who, Error::<T>::NotTheNFTOwner);
+				ensure!(!nft.state.is_locked(), Error::<T>::CannotSetAttributesOfLockedNFTs);
+			},
+			AttributeNamespace::CollectionOwner => {
+				let collection_id = nft.collection_id.ok_or(Error::<T>::NFTNotInACollection)?;
+				Self::ensure_can_set_collection_attribute(collection_id, who)?;
+			},
+			AttributeNamespace::Pallet => return Err(Error::<T>::CannotSetPalletAttributes.into()),
+		}
+
+		Ok(())
+	}
+
+	/// Whether an account is the admin of a collection.
+	fn is_collection_admin(collection_id: CollectionId, who: &T::AccountId) -> bool {
+		CollectionAdmins::<T>::get(collection_id).map_or(false, |x| x == *who)
+	}
+
+	/// Whether an account is the freezer of a collection.
+	fn is_collection_freezer(collection_id: CollectionId, who: &T::AccountId) -> bool {
+		CollectionFreezers::<T>::get(collection_id).map_or(false, |x| x == *who)
+	}
+
+	/// Checks that an account can set the collection owner attributes of a collection.
+	fn ensure_can_set_collection_attribute(
+		collection_id: CollectionId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		let collection =
+			Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+		ensure!(collection.owner == *who, Error::<T>::NotTheCollectionOwner);
+		ensure!(
+			!LockedCollectionAttributes::<T>::contains_key(collection_id),
+			Error::<T>::CollectionAttributesLocked
+		);
+
+		Ok(())
+	}
+
+	/// Reserve the deposit of an attribute from `who`, releasing the deposit previously reserved
+	/// for the same attribute.
+	fn reserve_attribute_deposit(
+		who: &T::AccountId,
+		old_deposit: Option<(T::AccountId, BalanceOf<T>)>,
+		key_len: usize,
+		value_len: usize,
+	) -> Result<Option<(T::AccountId, BalanceOf<T>)>, DispatchError> {
+		let bytes = BalanceOf::<T>::from((key_len + value_len) as u32);
+		let deposit = T::AttributeDepositBase::get()
+			.saturating_add(T::AttributeDepositPerByte::get().saturating_mul(bytes));
+		Self::unreserve_attribute_deposit(old_deposit);
+		T::Currency::reserve(who, deposit)?;
+
+		Ok(Some((who.clone(), deposit)))
+	}
+
+	/// Release the deposit of an attribute, if any.
+	fn unreserve_attribute_deposit(deposit: Option<(T::AccountId, BalanceOf<T>)>) {
+		if let Some((account, amount)) = deposit {
+			T::Currency::unreserve(&account, amount);
+		}
+	}
+
+	/// Ancestors of a nested NFT, from its parent to the root NFT.
+	pub fn ancestors_of(nft_id: NFTId) -> Vec<NFTId> {
+		let mut ancestors = Vec::new();
+		let mut current = nft_id;
+		while let Some(parent_id) = NFTParents::<T>::get(current) {
+			ancestors.push(parent_id);
+			current = parent_id;
+		}
+		ancestors
+	}
+
+	/// Number of levels of NFTs nested under an NFT.
+	fn nesting_height(nft_id: NFTId) -> u32 {
+		NFTChildren::<T>::get(nft_id)
+			.into_iter()
+			.map(|child_id| Self::nesting_height(child_id).saturating_add(1))
+			.max()
+			.unwrap_or(0)
+	}
 
+	/// Fill DelegationQueue with any number of data
+	pub fn fill_delegation_queue(
+		number: u32,
+		nft_id: NFTId,
+		block_number: T::BlockNumber,
+	) -> Result<(), DispatchError> {
+		DelegationQueue::<T>::try_mutate(|x| -> DispatchResult {
+			x.bulk_insert(nft_id, block_number, number)
+				.map_err(|_| Error::<T>::SimultaneousDelegationLimitReached)?;
 			Ok(())
 		})?;
-
 		Ok(())
 	}
 
-	fn create_filled_collection(
-		owner: Self::AccountId,
-		collection_id: CollectionId,
-		start_nft_id: NFTId,
-		amount_in_collection: u32,
+	/// Whether an NFT is soulbound and owned by another account than its creator.
+	fn is_issued_soulbound(nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>) -> bool {
+		nft.state.is_soulbound && nft.creator != nft.owner
+	}
+
+	fn burn_restriction_error(restriction: NFTRestriction) -> Error<T> {
+		match restriction {
+			NFTRestriction::Delegated => Error::<T>::CannotBurnDelegatedNFTs,
+			_ => Error::<T>::CannotBurnLockedNFTs,
+		}
+	}
+
+	/// Keep the `AccountNfts` index in sync with an NFT owner change, clear the transfer
+	/// approval given by the previous owner and move the nested NFTs to the new owner. Each
+	/// moved nested NFT is reported as transferred to the `OnNFTTransfer` handlers and with an
+	/// `NFTTransferred` event.
+	fn handle_owner_change(
+		nft_id: NFTId,
+		old_owner: Option<&T::AccountId>,
+		new_owner: Option<&T::AccountId>,
+	) {
+		if old_owner == new_owner {
+			return
+		}
+		if let Some(old_owner) = old_owner {
+			AccountNfts::<T>::remove(old_owner, nft_id);
+		}
+		NFTApprovals::<T>::remove(nft_id);
+		if let Some(new_owner) = new_owner {
+			AccountNfts::<T>::insert(new_owner, nft_id, ());
+			if T::StoragePayment::get() == NFTStoragePayment::DepositFollowingOwner {
+				Self::move_nft_deposit(nft_id, new_owner);
+			}
+
+			// Nested NFTs follow the owner of their parent.
+			for child_id in NFTChildren::<T>::get(nft_id) {
+				let child_owner = Nfts::<T>::mutate(child_id, |x| {
+					x.as_mut()
+						.map(|child| sp_std::mem::replace(&mut child.owner, new_owner.clone()))
+				});
+				Self::handle_owner_change(child_id, child_owner.as_ref(), Some(new_owner));
+				if let Some(sender) = child_owner.filter(|x| x != new_owner) {
+					Self::notify_nft_transfer(child_id, &sender, new_owner);
+					let recipient = new_owner.clone();
+					let event = Event::NFTTransferred { nft_id: child_id, sender, recipient };
+					Self::deposit_event(event);
+				}
+			}
+		}
+	}
+
+	/// Let the other pallets clean up their data about a transferred NFT.
+	fn notify_nft_transfer(nft_id: NFTId, from: &T::AccountId, to: &T::AccountId) {
+		let weight = T::OnNFTTransfer::on_nft_transfer(nft_id, from, to);
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Normal);
+	}
+
+	/// Let the other pallets clean up their data about a burned NFT.
+	fn notify_nft_burn(nft_id: NFTId, owner: &T::AccountId) {
+		let weight = T::OnNFTBurn::on_nft_burn(nft_id, owner);
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Normal);
+	}
+
+	/// Mint fee charged for an NFT, which is zero when the storage is paid with a deposit.
+	pub fn mint_fee() -> BalanceOf<T> {
+		match T::StoragePayment::get() {
+			NFTStoragePayment::Fee => NftMintFee::<T>::get(),
+			_ => Zero::zero(),
+		}
+	}
+
+	/// Storage deposit of an NFT holding `bytes` of offchain data.
+	pub fn nft_deposit(bytes: usize) -> BalanceOf<T> {
+		let bytes = BalanceOf::<T>::from(bytes as u32);
+		T::NFTDepositBase::get().saturating_add(T::NFTDepositPerByte::get().saturating_mul(bytes))
+	}
+
+	/// Withdraw the mint fee of `count` NFTs from `payer` at once and send it to the fees
+	/// collector. Returns the fee paid per NFT.
+	fn withdraw_mint_fee(payer: &T::AccountId, count: u32) -> Result<BalanceOf<T>, DispatchError> {
+		let mint_fee = Self::mint_fee();
+		let total_fee = mint_fee.saturating_mul(count.into());
+		let reason = WithdrawReasons::FEE;
+		let imbalance = T::Currency::withdraw(payer, total_fee, reason, KeepAlive)?;
+		T::FeesCollector::on_unbalanced(imbalance);
+
+		Ok(mint_fee)
+	}
+
+	/// Reserve the storage deposit of a new NFT from `depositor` if the storage is paid with a
+	/// deposit.
+	fn reserve_nft_deposit(
+		nft_id: NFTId,
+		depositor: &T::AccountId,
+		bytes: usize,
 	) -> DispatchResult {
-		//Create full collection
-		let collection_offchain_data: U8BoundedVec<Self::CollectionOffchainDataLimit> =
-			U8BoundedVec::try_from(vec![
-				1;
-				Self::CollectionOffchainDataLimit::get()
-					.try_into()
-					.unwrap()
-			])
-			.expect("It will never happen.");
+		if T::StoragePayment::get() == NFTStoragePayment::Fee {
+			return Ok(())
+		}
+		let deposit = Self::nft_deposit(bytes);
+		T::Currency::reserve(depositor, deposit)?;
+		NFTDeposits::<T>::insert(nft_id, (depositor.clone(), deposit));
 
-		let mut collection = Collection::<
-			Self::AccountId,
-			Self::CollectionOffchainDataLimit,
-			Self::CollectionSizeLimit,
-		>::new(owner.clone(), collection_offchain_data, None);
+		Ok(())
+	}
 
-		let ids: Vec<u32> = (start_nft_id..amount_in_collection + start_nft_id).collect();
-		let nft_ids: BoundedVec<u32, Self::CollectionSizeLimit> =
-			BoundedVec::try_from(ids).expect("It will never happen.");
+	/// Adjust the storage deposit of an NFT to its current offchain, secret and capsule data. The
+	/// difference is reserved from or released to the account holding the deposit. NFTs minted
+	/// with a fee are not affected.
+	fn update_nft_deposit(nft_id: NFTId) -> DispatchResult {
+		let (depositor, old_deposit) = match NFTDeposits::<T>::get(nft_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+		let nft = Nfts::<T>::get(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+		let bytes = nft.offchain_data.len() +
+			SecretNftsOffchainData::<T>::decode_len(nft_id).unwrap_or(0) +
+			CapsuleOffchainData::<T>::decode_len(nft_id).unwrap_or(0);
+		let deposit = Self::nft_deposit(bytes);
+		if deposit > old_deposit {
+			T::Currency::reserve(&depositor, deposit.saturating_sub(old_deposit))?;
+		} else {
+			T::Currency::unreserve(&depositor, old_deposit.saturating_sub(deposit));
+		}
+		NFTDeposits::<T>::insert(nft_id, (depositor, deposit));
 
-		collection.nfts = nft_ids;
-		Collections::<T>::insert(collection_id, collection);
+		Ok(())
+	}
+
+	/// Checks that a secret or capsule sync started at least `ShardSyncTimeout` blocks ago.
+	/// Syncs without a recorded start are considered timed out.
+	fn ensure_sync_timed_out(sync: Option<(T::BlockNumber, Option<ClusterId>)>) -> DispatchResult {
+		let started_at = sync.map(|x| x.0).unwrap_or_default();
+		let now = frame_system::Pallet::<T>::block_number();
+		ensure!(
+			now >= started_at.saturating_add(T::ShardSyncTimeout::get()),
+			Error::<T>::SyncTimeoutNotReached
+		);
 
 		Ok(())
 	}
 
-	fn get_nft(id: NFTId) -> Option<NFTData<Self::AccountId, Self::NFTOffchainDataLimit>> {
-		Nfts::<T>::get(id)
+	/// Record the enclaves holding the shards of an NFT.
+	fn set_shard_holders(nft_id: NFTId, shards: &[(ClusterId, T::AccountId)]) {
+		for (_, operator_address) in shards {
+			ShardHolderNfts::<T>::insert(operator_address, nft_id, ());
+		}
 	}
 
-	fn set_nft(
-		id: NFTId,
-		nft_data: NFTData<Self::AccountId, Self::NFTOffchainDataLimit>,
+	/// Forget the enclaves holding the capsule shards of an NFT.
+	fn remove_capsule_shard_holders(nft_id: NFTId) {
+		CapsulesResharding::<T>::remove(nft_id);
+		let secret_holders = SecretNftShardHolders::<T>::get(nft_id);
+		for (_, operator_address) in CapsuleShardHolders::<T>::take(nft_id) {
+			if !secret_holders.iter().any(|x| x.1 == operator_address) {
+				ShardHolderNfts::<T>::remove(&operator_address, nft_id);
+			}
+		}
+	}
+
+	/// Forget the enclaves holding the secret shards of an NFT.
+	fn remove_secret_shard_holders(nft_id: NFTId) {
+		SecretNftsResharding::<T>::remove(nft_id);
+		let capsule_holders = CapsuleShardHolders::<T>::get(nft_id);
+		for (_, operator_address) in SecretNftShardHolders::<T>::take(nft_id) {
+			if !capsule_holders.iter().any(|x| x.1 == operator_address) {
+				ShardHolderNfts::<T>::remove(&operator_address, nft_id);
+			}
+		}
+	}
+
+	/// Add an enclave to the shard holders of an NFT being resharded. Returns true once all the
+	/// shards are held again.
+	fn add_shard_holder(
+		nft_id: NFTId,
+		cluster_id: ClusterId,
+		operator_address: T::AccountId,
+		holders: &mut BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber>,
+	) -> Result<bool, DispatchError> {
+		if let Some((holders_cluster_id, _)) = holders.first() {
+			ensure!(cluster_id == *holders_cluster_id, Error::<T>::ShareNotFromValidCluster);
+		}
+		ensure!(
+			!holders.iter().any(|x| x.1 == operator_address),
+			Error::<T>::EnclaveAlreadyAddedShard
+		);
+		holders
+			.try_push((cluster_id, operator_address.clone()))
+			.map_err(|_| Error::<T>::NFTHasReceivedAllShards)?;
+		ShardHolderNfts::<T>::insert(&operator_address, nft_id, ());
+
+		Ok(holders.len() == T::ShardsNumber::get() as usize)
+	}
+
+	/// Remove an enclave from the shard holders of an NFT. Returns true if it was one of them.
+	fn remove_shard_holder(
+		holders: &mut Option<BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber>>,
+		operator_address: &T::AccountId,
+	) -> bool {
+		match holders {
+			Some(holders) => {
+				let len = holders.len();
+				holders.retain(|x| x.1 != *operator_address);
+				holders.len() < len
+			},
+			None => false,
+		}
+	}
+
+	/// Send the reserved secret NFT mint fee of an NFT to the fees collector.
+	fn collect_secret_fee(nft_id: NFTId) {
+		if let Some((payer, fee)) = PendingSecretFees::<T>::take(nft_id) {
+			let (imbalance, _) = T::Currency::slash_reserved(&payer, fee);
+			T::FeesCollector::on_unbalanced(imbalance);
+		}
+	}
+
+	/// Release the refundable part of the capsule mint fee of an NFT. Returns the released
+	/// amount.
+	fn release_capsule_deposit(nft_id: NFTId) -> BalanceOf<T> {
+		match CapsuleDeposits::<T>::take(nft_id) {
+			Some((depositor, deposit)) => {
+				T::Currency::unreserve(&depositor, deposit);
+				deposit
+			},
+			None => Zero::zero(),
+		}
+	}
+
+	/// Move the storage deposit of an NFT to its new owner. The deposit is reserved from the new
+	/// owner and released to the previous depositor. If the new owner cannot afford it, the
+	/// deposit stays with the previous depositor and `NFTDepositNotMoved` is emitted, since the
+	/// owner changes cannot fail.
+	fn move_nft_deposit(nft_id: NFTId, new_owner: &T::AccountId) {
+		let (depositor, deposit) = match NFTDeposits::<T>::get(nft_id) {
+			Some(x) => x,
+			None => return,
+		};
+		if depositor == *new_owner {
+			return
+		}
+		if T::Currency::reserve(new_owner, deposit).is_ok() {
+			T::Currency::unreserve(&depositor, deposit);
+			NFTDeposits::<T>::insert(nft_id, (new_owner.clone(), deposit));
+		} else {
+			let event = Event::NFTDepositNotMoved { nft_id, depositor, owner: new_owner.clone() };
+			Self::deposit_event(event);
+		}
+	}
+
+	/// Royalty can only be changed by the creator while owning the NFT and if the NFT is not in
+	/// use.
+	fn ensure_can_set_royalty(
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+		who: &T::AccountId,
 	) -> DispatchResult {
-		Nfts::<T>::insert(id, nft_data);
+		ensure!(nft.owner == *who, Error::<T>::NotTheNFTOwner);
+		ensure!(nft.creator == *who, Error::<T>::NotTheNFTCreator);
+		ensure!(!nft.state.is_locked(), Error::<T>::CannotSetRoyaltyForLockedNFTs);
+		ensure!(!nft.state.is_delegated, Error::<T>::CannotSetRoyaltyForDelegatedNFTs);
+		ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotSetRoyaltyForSyncingNFTs);
+		ensure!(!nft.state.is_syncing_capsule, Error::<T>::CannotSetRoyaltyForSyncingCapsules);
 
 		Ok(())
 	}
 
-	fn create_nft(
-		owner: Self::AccountId,
-		offchain_data: BoundedVec<u8, Self::NFTOffchainDataLimit>,
-		royalty: Permill,
+	/// Create an NFT minted by `minter` and owned by `owner`. The minter pays the mint fee and
+	/// must be the owner or a minter of the collection, if any.
+	fn do_create_nft(
+		minter: T::AccountId,
+		owner: T::AccountId,
+		fee_payer: &T::AccountId,
+		offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+		royalty: Option<Permill>,
 		collection_id: Option<CollectionId>,
 		is_soulbound: bool,
-	) -> Result<NFTId, DispatchResult> {
-		let nft_state =
-			NFTState::new(false, false, false, false, is_soulbound, false, false, false, false);
+	) -> Result<NFTId, DispatchError> {
+		let mut next_nft_id = None;
+		let mut creator = minter.clone();
+		let mut nft_royalty = royalty.unwrap_or_else(|| Permill::from_parts(0));
+
+		// Checks
+		// The fee payer needs to pay the NFT mint fee.
+		let mint_fee = Self::withdraw_mint_fee(fee_payer, 1)?;
+
+		// Throws an error if specified collection does not exist, minter is not owner or minter,
+		// collection is close, collection has reached limit.
+		if let Some(collection_id) = collection_id {
+			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+				let limit =
+					collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get()) as usize;
+				ensure!(
+					Self::can_mint_into(collection_id, collection, &minter),
+					Error::<T>::NotTheCollectionOwner
+				);
+				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
+				ensure!(
+					(collection.nfts_count as usize) < limit,
+					Error::<T>::CollectionHasReachedLimit
+				);
+
+				let tmp_nft_id = Self::get_next_nft_id();
+				nft_royalty = Self::apply_collection_royalty(tmp_nft_id, collection, royalty)?;
+				Self::insert_collection_nft(collection_id, collection, tmp_nft_id)?;
+				next_nft_id = Some(tmp_nft_id);
+				creator = Self::minted_nft_creator(collection_id, collection, &minter);
+				Ok(())
+			})?;
+		}
+
+		let nft_id = next_nft_id.unwrap_or_else(|| Self::get_next_nft_id());
+		Self::reserve_nft_deposit(nft_id, fee_payer, offchain_data.len())?;
 		let nft = NFTData::new(
 			owner.clone(),
-			owner.clone(),
-			offchain_data,
-			royalty,
-			nft_state,
+			creator,
+			offchain_data.clone(),
+			nft_royalty,
+			NFTState::new_default(is_soulbound),
 			collection_id,
 		);
-		let nft_id = Self::get_next_nft_id();
+		// Execute
 		Nfts::<T>::insert(nft_id, nft);
+		AccountNfts::<T>::insert(&owner, nft_id, ());
+		let event = Event::NFTCreated {
+			nft_id,
+			owner,
+			offchain_data,
+			royalty: nft_royalty,
+			collection_id,
+			is_soulbound,
+			mint_fee,
+		};
+		Self::deposit_event(event);
 
 		Ok(nft_id)
 	}
 
-	fn mutate_nft<
-		R,
-		E,
-		F: FnOnce(&mut Option<NFTData<Self::AccountId, Self::NFTOffchainDataLimit>>) -> Result<R, E>,
-	>(
-		id: NFTId,
-		f: F,
-	) -> Result<R, E> {
-		Nfts::<T>::try_mutate(id, f)
+	/// Payload signed by the creator of a voucher. The genesis hash binds the voucher to this
+	/// chain.
+	pub fn voucher_signing_payload(
+		voucher: &NFTVoucher<T::AccountId, BalanceOf<T>, T::BlockNumber, T::NFTOffchainDataLimit>,
+	) -> Vec<u8> {
+		let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+		(VOUCHER_SIGNING_PREFIX, genesis_hash, voucher).encode()
 	}
 
-	fn exists(id: NFTId) -> bool {
-		Nfts::<T>::contains_key(id)
+	/// Leaf of a collection mint allowlist Merkle tree.
+	pub fn allowlist_leaf(account: &T::AccountId, quantity: u32) -> MerkleHash {
+		BlakeTwo256::hash(&(account, quantity).encode()).into()
 	}
-}
 
-impl<T: Config> Pallet<T> {
-	fn get_next_nft_id() -> NFTId {
-		let nft_id = NextNFTId::<T>::get();
-		let next_id = nft_id
-			.checked_add(1)
-			.expect("If u32 is not enough we should crash for safety; qed.");
-		NextNFTId::<T>::put(next_id);
+	/// Check that `leaf` belongs to the Merkle tree of `root`. Pairs of nodes are sorted before
+	/// being hashed so that the proof does not need to record the position of each node.
+	pub fn verify_merkle_proof(root: MerkleHash, leaf: MerkleHash, proof: &[MerkleHash]) -> bool {
+		let computed = proof.iter().fold(leaf, |node, sibling| {
+			if node <= *sibling {
+				BlakeTwo256::hash(&[node, *sibling].concat()).into()
+			} else {
+				BlakeTwo256::hash(&[*sibling, node].concat()).into()
+			}
+		});
 
-		nft_id
+		computed == root
 	}
 
-	fn get_next_collection_id() -> NFTId {
-		let collection_id = NextCollectionId::<T>::get();
-		let next_id = collection_id
+	/// Mint an NFT of a collection for `buyer`, who pays `price` to the payout account (the
+	/// collection owner by default) and the NFT mint fee. Returns the NFT id and whether the mint
+	/// sold the collection out.
+	fn do_collection_mint(
+		buyer: &T::AccountId,
+		collection_id: CollectionId,
+		offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+		price: BalanceOf<T>,
+		payout: Option<&T::AccountId>,
+	) -> Result<(NFTId, bool), DispatchError> {
+		let nft_id = Self::get_next_nft_id();
+		let (creator, royalty, is_sold_out) =
+			Collections::<T>::try_mutate(collection_id, |x| -> Result<_, DispatchError> {
+				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+				let limit =
+					collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get()) as usize;
+				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
+				ensure!(
+					(collection.nfts_count as usize) < limit,
+					Error::<T>::CollectionHasReachedLimit
+				);
+
+				// The buyer pays the mint price to the payout account.
+				if !price.is_zero() {
+					let payout = payout.unwrap_or(&collection.owner);
+					T::Currency::transfer(buyer, payout, price, KeepAlive)?;
+				}
+
+				// NFTs follow the collection royalty.
+				let royalty = Self::apply_collection_royalty(nft_id, collection, None)?;
+				Self::insert_collection_nft(collection_id, collection, nft_id)?;
+				let is_sold_out = collection.nfts_count as usize >= limit;
+				if is_sold_out {
+					collection.is_closed = true;
+				}
+
+				Ok((collection.owner.clone(), royalty, is_sold_out))
+			})?;
+
+		// The buyer needs to pay the NFT mint fee.
+		let mint_fee = Self::withdraw_mint_fee(buyer, 1)?;
+		Self::reserve_nft_deposit(nft_id, buyer, offchain_data.len())?;
+
+		let nft = NFTData::new(
+			buyer.clone(),
+			creator,
+			offchain_data.clone(),
+			royalty,
+			NFTState::new_default(false),
+			Some(collection_id),
+		);
+		Nfts::<T>::insert(nft_id, nft);
+		AccountNfts::<T>::insert(buyer, nft_id, ());
+		let event = Event::NFTCreated {
+			nft_id,
+			owner: buyer.clone(),
+			offchain_data,
+			royalty,
+			collection_id: Some(collection_id),
+			is_soulbound: false,
+			mint_fee,
+		};
+		Self::deposit_event(event);
+
+		Ok((nft_id, is_sold_out))
+	}
+
+	/// Whether an account is the owner or a minter of a collection.
+	fn can_mint_into(
+		collection_id: CollectionId,
+		collection: &Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		who: &T::AccountId,
+	) -> bool {
+		collection.owner == *who || CollectionMinters::<T>::get(collection_id).contains(who)
+	}
+
+	/// Creator of an NFT minted into a collection, which depends on the collection attribution
+	/// when the NFT is minted by a minter.
+	fn minted_nft_creator(
+		collection_id: CollectionId,
+		collection: &Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		minter: &T::AccountId,
+	) -> T::AccountId {
+		match MinterAttributions::<T>::get(collection_id) {
+			MinterAttribution::CollectionOwner => collection.owner.clone(),
+			MinterAttribution::Minter => minter.clone(),
+		}
+	}
+
+	/// Check an NFT royalty against the maximum of its collection and return it. An NFT created
+	/// with a royalty overrides the collection default royalty, otherwise it follows the default.
+	fn apply_collection_royalty(
+		nft_id: NFTId,
+		collection: &Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		royalty: Option<Permill>,
+	) -> Result<Permill, DispatchError> {
+		let nft_royalty = royalty.or(collection.royalty).unwrap_or_else(|| Permill::from_parts(0));
+		if let Some(max) = collection.max_royalty {
+			ensure!(nft_royalty <= max, Error::<T>::RoyaltyExceedsCollectionMaximum);
+		}
+		if royalty.is_some() {
+			RoyaltyOverrides::<T>::insert(nft_id, ());
+		}
+
+		Ok(nft_royalty)
+	}
+
+	/// Royalty paid when an NFT is sold: the collection default royalty unless the NFT overrides
+	/// it, capped by the collection maximum royalty.
+	pub fn royalty_of(
+		nft_id: NFTId,
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+	) -> Permill {
+		let collection = match nft.collection_id.and_then(Collections::<T>::get) {
+			Some(x) => x,
+			None => return nft.royalty,
+		};
+		let royalty = match collection.royalty {
+			Some(x) if !RoyaltyOverrides::<T>::contains_key(nft_id) => x,
+			_ => nft.royalty,
+		};
+
+		collection.max_royalty.map_or(royalty, |max| royalty.min(max))
+	}
+
+	/// Royalty splits must have distinct beneficiaries whose shares add up to 100%.
+	fn ensure_valid_royalty_splits(
+		splits: &RoyaltySplits<T::AccountId, T::RoyaltySplitsLimit>,
+	) -> DispatchResult {
+		let mut total: u32 = 0;
+		for (index, (beneficiary, share)) in splits.iter().enumerate() {
+			ensure!(
+				!splits.iter().skip(index + 1).any(|(x, _)| x == beneficiary),
+				Error::<T>::DuplicateRoyaltyBeneficiary
+			);
+			total = total.saturating_add(share.deconstruct());
+		}
+		ensure!(
+			total == Permill::one().deconstruct(),
+			Error::<T>::RoyaltySplitsMustSumTo100Percent
+		);
+
+		Ok(())
+	}
+
+	/// Beneficiaries of the royalty of an NFT: its own splits, the default splits of its
+	/// collection or the creator alone.
+	pub fn royalty_splits_of(
+		nft_id: NFTId,
+		nft: &NFTData<T::AccountId, T::NFTOffchainDataLimit>,
+	) -> Vec<(T::AccountId, Permill)> {
+		NFTRoyaltySplits::<T>::get(nft_id)
+			.or_else(|| nft.collection_id.and_then(CollectionRoyaltySplits::<T>::get))
+			.map(|x| x.into_inner())
+			.unwrap_or_else(|| vec![(nft.creator.clone(), Permill::one())])
+	}
+
+	/// Whether an account is allowed to transfer an NFT on behalf of its owner.
+	fn is_approved(who: &T::AccountId, nft_id: NFTId, owner: &T::AccountId) -> bool {
+		if OperatorApprovals::<T>::contains_key(owner, who) {
+			return true
+		}
+		match NFTApprovals::<T>::get(nft_id) {
+			Some((delegate, expiry)) => {
+				let now = frame_system::Pallet::<T>::block_number();
+				delegate == *who && expiry.map_or(true, |x| now < x)
+			},
+			None => false,
+		}
+	}
+
+	/// Transfer an NFT owned by the sender to the recipient.
+	fn do_transfer_nft(
+		sender: T::AccountId,
+		nft_id: NFTId,
+		recipient: T::AccountId,
+	) -> DispatchResult {
+		Nfts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+			// Checks
+			ensure!(nft.owner == sender, Error::<T>::NotTheNFTOwner);
+			ensure!(nft.owner != recipient, Error::<T>::CannotTransferNFTsToYourself);
+			nft.can_transfer_releasing(T::OnNFTTransfer::releases_lock)
+				.map_err(Self::transfer_restriction_error)?;
+
+			// Execute
+			nft.owner = recipient.clone();
+
+			Ok(())
+		})?;
+		Self::handle_owner_change(nft_id, Some(&sender), Some(&recipient));
+		Self::notify_nft_transfer(nft_id, &sender, &recipient);
+
+		let event = Event::NFTTransferred { nft_id, sender, recipient };
+		Self::deposit_event(event);
+
+		Ok(())
+	}
+
+	fn transfer_restriction_error(restriction: NFTRestriction) -> Error<T> {
+		match restriction {
+			NFTRestriction::Locked(_) => Error::<T>::CannotTransferLockedNFTs,
+			NFTRestriction::Delegated => Error::<T>::CannotTransferDelegatedNFTs,
+			NFTRestriction::NotCreatedSoulbound =>
+				Error::<T>::CannotTransferNotCreatedSoulboundNFTs,
+			NFTRestriction::SecretSyncing => Error::<T>::CannotTransferNotSyncedSecretNFTs,
+			NFTRestriction::CapsuleSyncing => Error::<T>::CannotTransferNotSyncedCapsules,
+		}
+	}
+
+	/// NFTs owned by an account, skipping the first `start` ones and returning at most `limit`.
+	pub fn nfts_of(account: T::AccountId, start: u32, limit: u32) -> Vec<NFTId> {
+		AccountNfts::<T>::iter_key_prefix(account)
+			.skip(start as usize)
+			.take(limit as usize)
+			.collect()
+	}
+
+	/// State of an NFT.
+	pub fn nft_state(id: NFTId) -> Option<NFTState> {
+		Nfts::<T>::get(id).map(|x| x.state)
+	}
+
+	/// NFTs in a collection.
+	pub fn collection_nfts(id: CollectionId) -> Option<Vec<NFTId>> {
+		Collections::<T>::contains_key(id).then(|| Self::collection_nft_ids(id))
+	}
+
+	/// Members of a collection, including the ones not yet moved to `CollectionNfts` by the
+	/// storage migration.
+	pub fn collection_nft_ids(collection_id: CollectionId) -> Vec<NFTId> {
+		let mut nfts: Vec<NFTId> = CollectionNfts::<T>::iter_key_prefix(collection_id).collect();
+		let to_migrate = CollectionNftsToMigrate::<T>::get(collection_id).unwrap_or_default();
+		nfts.extend(to_migrate.into_iter().filter(|nft_id| {
+			Nfts::<T>::get(nft_id).map_or(false, |x| x.collection_id == Some(collection_id))
+		}));
+		nfts
+	}
+
+	/// Create one NFT of a batch in a collection. Every check that can fail happens before the
+	/// collection is updated, so that a failed item leaves it untouched. The mint fee is charged
+	/// by the caller for the whole batch.
+	fn do_create_batch_nft(
+		collection_id: CollectionId,
+		collection: &mut Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		owner: &T::AccountId,
+		creator: &T::AccountId,
+		offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
+		royalty: Option<Permill>,
+		is_soulbound: bool,
+	) -> DispatchResult {
+		let limit = collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get());
+		ensure!(collection.nfts_count < limit, Error::<T>::CollectionHasReachedLimit);
+
+		let nft_id = Self::get_next_nft_id();
+		let royalty = Self::apply_collection_royalty(nft_id, collection, royalty)?;
+
+		Self::reserve_nft_deposit(nft_id, owner, offchain_data.len())?;
+
+		Self::insert_collection_nft(collection_id, collection, nft_id)?;
+		let nft = NFTData::new(
+			owner.clone(),
+			creator.clone(),
+			offchain_data.clone(),
+			royalty,
+			NFTState::new_default(is_soulbound),
+			Some(collection_id),
+		);
+		Nfts::<T>::insert(nft_id, nft);
+		AccountNfts::<T>::insert(owner, nft_id, ());
+
+		let event = Event::NFTCreated {
+			nft_id,
+			owner: owner.clone(),
+			offchain_data,
+			royalty,
+			collection_id: Some(collection_id),
+			is_soulbound,
+			mint_fee: Self::mint_fee(),
+		};
+		Self::deposit_event(event);
+
+		Ok(())
+	}
+
+	/// Add an NFT to the members of a collection. The collection limit must be checked by the
+	/// caller.
+	fn insert_collection_nft(
+		collection_id: CollectionId,
+		collection: &mut Collection<T::AccountId, T::CollectionOffchainDataLimit>,
+		nft_id: NFTId,
+	) -> DispatchResult {
+		collection.nfts_count = collection
+			.nfts_count
 			.checked_add(1)
-			.expect("If u32 is not enough we should crash for safety; qed.");
-		NextCollectionId::<T>::put(next_id);
+			.ok_or(Error::<T>::CannotAddMoreNFTsToCollection)?;
+		CollectionNfts::<T>::insert(collection_id, nft_id, ());
 
-		collection_id
+		Ok(())
 	}
 
 	pub fn balance_check(account: &T::AccountId, amount: BalanceOf<T>) -> bool {
@@ -1540,3 +4886,32 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 }
+
+impl<T: Config> traits::OnEnclaveRemoved<T::AccountId> for Pallet<T> {
+	fn on_enclave_removed(cluster_id: ClusterId, operator_address: &T::AccountId) -> Weight {
+		let mut count: u64 = 0;
+		for (nft_id, _) in ShardHolderNfts::<T>::drain_prefix(operator_address) {
+			count += 1;
+
+			let is_secret_holder = SecretNftShardHolders::<T>::mutate_exists(nft_id, |x| {
+				Self::remove_shard_holder(x, operator_address)
+			});
+			if is_secret_holder {
+				SecretNftsResharding::<T>::insert(nft_id, ());
+				let event = Event::SecretNFTReshardingRequested { nft_id, cluster_id };
+				Self::deposit_event(event);
+			}
+
+			let is_capsule_holder = CapsuleShardHolders::<T>::mutate_exists(nft_id, |x| {
+				Self::remove_shard_holder(x, operator_address)
+			});
+			if is_capsule_holder {
+				CapsulesResharding::<T>::insert(nft_id, ());
+				let event = Event::CapsuleReshardingRequested { nft_id, cluster_id };
+				Self::deposit_event(event);
+			}
+		}
+
+		T::DbWeight::get().reads_writes(1 + 4 * count, 1 + 5 * count)
+	}
+}
@@ -172,19 +172,153 @@ pub mod v3 {
 			Ok(Vec::new())
 		}
 
+		// fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		// 	Nfts::<T>::translate(|_id, old: OldNFTData<T::AccountId, T::NFTOffchainDataLimit>| {
+		// 		let new_state = NFTState::new(
+		// 			old.state.is_capsule,
+		// 			old.state.is_listed,
+		// 			old.state.is_secret,
+		// 			old.state.is_delegated,
+		// 			old.state.is_soulbound,
+		// 			old.state.is_syncing,
+		// 			old.state.is_rented,
+		// 			false,
+		// 			false,
+		// 		);
+
+		// 		let new_nft_data = NFTData::new(
+		// 			old.owner,
+		// 			old.creator,
+		// 			old.offchain_data,
+		// 			old.royalty,
+		// 			new_state,
+		// 			old.collection_id,
+		// 		);
+
+		// 		Some(new_nft_data)
+		// 	});
+
+		// 	frame_support::weights::Weight::MAX
+		// }
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV3");
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use scale_info::TypeInfo;
+	use sp_std::fmt::Debug;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	#[derive(
+		Encode, Decode, Eq, Default, TypeInfo, Clone, PartialEq, RuntimeDebug, MaxEncodedLen,
+	)]
+	pub struct OldNFTState {
+		/// Is NFT converted to capsule.
+		pub is_capsule: bool,
+		/// Is NFT listed for sale.
+		pub is_listed: bool,
+		/// Is NFT contains secret.
+		pub is_secret: bool,
+		/// Is NFT delegated.
+		pub is_delegated: bool,
+		/// Is NFT soulbound.
+		pub is_soulbound: bool,
+		/// Is NFT Secret syncing
+		pub is_syncing_secret: bool,
+		/// Is NFT Rented or available for rent.
+		pub is_rented: bool,
+		/// Is Capsule syncing.
+		pub is_syncing_capsule: bool,
+		/// Is Transmission.
+		pub is_transmission: bool,
+	}
+
+	#[derive(
+		Encode,
+		Decode,
+		Eq,
+		Default,
+		TypeInfo,
+		CloneNoBound,
+		PartialEqNoBound,
+		RuntimeDebugNoBound,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(NFTOffchainDataLimit))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen))]
+	pub struct OldNFTData<AccountId, NFTOffchainDataLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		NFTOffchainDataLimit: Get<u32>,
+	{
+		/// NFT owner
+		pub owner: AccountId,
+		/// NFT creator
+		pub creator: AccountId,
+		/// NFT offchain_data
+		pub offchain_data: U8BoundedVec<NFTOffchainDataLimit>,
+		/// Collection ID
+		pub collection_id: Option<CollectionId>,
+		/// Royalty
+		pub royalty: Permill,
+		/// NFT state
+		pub state: OldNFTState,
+	}
+
+	/// Locks replacing the `is_listed`, `is_rented` and `is_transmission` flags. The NFT pallet
+	/// does not know which pallet set those flags, the runtime does.
+	pub trait LegacyLocks {
+		/// Lock of the pallet that listed the NFT (marketplace or auction).
+		fn listed(nft_id: NFTId) -> NFTLock;
+		/// Lock of the rent pallet.
+		fn rented(nft_id: NFTId) -> NFTLock;
+		/// Lock of the transmission protocols pallet.
+		fn transmission(nft_id: NFTId) -> NFTLock;
+	}
+
+	pub struct MigrationV4<T, L>(sp_std::marker::PhantomData<(T, L)>);
+	impl<T: Config, L: LegacyLocks> OnRuntimeUpgrade for MigrationV4<T, L> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV4");
+			Ok(Vec::new())
+		}
+
 		fn on_runtime_upgrade() -> frame_support::weights::Weight {
-			Nfts::<T>::translate(|_id, old: OldNFTData<T::AccountId, T::NFTOffchainDataLimit>| {
-				let new_state = NFTState::new(
+			// Locks that could not be taken, either because they were duplicated or because the
+			// NFT already holds the maximum number of locks.
+			let mut dropped_locks: u32 = 0;
+			Nfts::<T>::translate(|id, old: OldNFTData<T::AccountId, T::NFTOffchainDataLimit>| {
+				let mut new_state = NFTState::new(
 					old.state.is_capsule,
-					old.state.is_listed,
 					old.state.is_secret,
 					old.state.is_delegated,
 					old.state.is_soulbound,
-					old.state.is_syncing,
-					old.state.is_rented,
-					false,
-					false,
+					old.state.is_syncing_secret,
+					old.state.is_syncing_capsule,
 				);
+				let locks = [
+					old.state.is_listed.then(|| L::listed(id)),
+					old.state.is_rented.then(|| L::rented(id)),
+					old.state.is_transmission.then(|| L::transmission(id)),
+				];
+				for lock in locks.into_iter().flatten() {
+					if new_state.lock(lock).is_none() {
+						dropped_locks = dropped_locks.saturating_add(1);
+						log::warn!("MigrationV4: lock {:?} of NFT {} was dropped", lock, id);
+					}
+				}
 
 				let new_nft_data = NFTData::new(
 					old.owner,
@@ -197,14 +331,403 @@ pub mod v3 {
 
 				Some(new_nft_data)
 			});
+			if dropped_locks > 0 {
+				log::warn!("MigrationV4: {} NFT locks were dropped", dropped_locks);
+			}
 
 			frame_support::weights::Weight::MAX
 		}
 
 		#[cfg(feature = "try-runtime")]
 		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
-			log::info!("Post-upgrade inside MigrationV3");
+			log::info!("Post-upgrade inside MigrationV4");
+			Ok(())
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+	use frame_support::traits::OnRuntimeUpgrade;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	/// Build the `AccountNfts` index from the existing NFTs.
+	pub struct MigrationV5<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV5<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV5");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			for (nft_id, nft) in Nfts::<T>::iter() {
+				AccountNfts::<T>::insert(nft.owner, nft_id, ());
+				count += 1;
+			}
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV5");
+			let nfts = Nfts::<T>::iter().count();
+			let indexed = AccountNfts::<T>::iter().count();
+			ensure!(nfts == indexed, "Every NFT should be indexed by its owner");
+			Ok(())
+		}
+	}
+}
+
+pub mod v6 {
+	use super::*;
+	use frame_support::traits::OnRuntimeUpgrade;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	/// Give every existing NFT a single beneficiary royalty split paying its creator.
+	pub struct MigrationV6<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV6<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV6");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			for (nft_id, nft) in Nfts::<T>::iter() {
+				let splits = BoundedVec::try_from(vec![(nft.creator, Permill::one())]);
+				if let Ok(splits) = splits {
+					NFTRoyaltySplits::<T>::insert(nft_id, splits);
+				}
+				count += 1;
+			}
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV6");
+			let nfts = Nfts::<T>::iter().count();
+			let splits = NFTRoyaltySplits::<T>::iter().count();
+			ensure!(nfts == splits, "Every NFT should have royalty splits");
 			Ok(())
 		}
 	}
 }
+
+pub mod v7 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use scale_info::TypeInfo;
+	use sp_std::fmt::Debug;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	#[derive(
+		Encode,
+		Decode,
+		Eq,
+		Default,
+		TypeInfo,
+		CloneNoBound,
+		PartialEqNoBound,
+		RuntimeDebugNoBound,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(CollectionOffChainDataLimit, CollectionSizeLimit))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen))]
+	pub struct OldCollection<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		CollectionOffChainDataLimit: Get<u32>,
+		CollectionSizeLimit: Get<u32>,
+	{
+		/// Collection owner
+		pub owner: AccountId,
+		/// Collection offchain_data
+		pub offchain_data: U8BoundedVec<CollectionOffChainDataLimit>,
+		/// NFTs in that collection
+		pub nfts: BoundedVec<NFTId, CollectionSizeLimit>,
+		/// Maximum length of the collection
+		pub limit: Option<u32>,
+		/// Is collection closed for adding new NFTs
+		pub is_closed: bool,
+	}
+
+	/// Add the default and maximum royalty to collections.
+	pub struct MigrationV7<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV7<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV7");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			Collections::<T>::translate(
+				|_id,
+				 old: OldCollection<
+					T::AccountId,
+					T::CollectionOffchainDataLimit,
+					T::CollectionSizeLimit,
+				>| {
+					count += 1;
+					Some(v8::OldCollection {
+						owner: old.owner,
+						offchain_data: old.offchain_data,
+						nfts: old.nfts,
+						limit: old.limit,
+						is_closed: old.is_closed,
+						royalty: None,
+						max_royalty: None,
+					})
+				},
+			);
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV7");
+			Ok(())
+		}
+	}
+}
+
+pub mod v8 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use scale_info::TypeInfo;
+	use sp_std::fmt::Debug;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	#[derive(
+		Encode,
+		Decode,
+		Eq,
+		Default,
+		TypeInfo,
+		CloneNoBound,
+		PartialEqNoBound,
+		RuntimeDebugNoBound,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(CollectionOffChainDataLimit, CollectionSizeLimit))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen))]
+	pub struct OldCollection<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		CollectionOffChainDataLimit: Get<u32>,
+		CollectionSizeLimit: Get<u32>,
+	{
+		/// Collection owner
+		pub owner: AccountId,
+		/// Collection offchain_data
+		pub offchain_data: U8BoundedVec<CollectionOffChainDataLimit>,
+		/// NFTs in that collection
+		pub nfts: BoundedVec<NFTId, CollectionSizeLimit>,
+		/// Maximum length of the collection
+		pub limit: Option<u32>,
+		/// Is collection closed for adding new NFTs
+		pub is_closed: bool,
+		/// Royalty inherited by the NFTs of the collection that do not override it
+		pub royalty: Option<Permill>,
+		/// Maximum royalty of the NFTs of the collection
+		pub max_royalty: Option<Permill>,
+	}
+
+	/// Add the mint allowlist Merkle root to collections.
+	pub struct MigrationV8<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV8<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV8");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			Collections::<T>::translate(
+				|_id,
+				 old: OldCollection<
+					T::AccountId,
+					T::CollectionOffchainDataLimit,
+					T::CollectionSizeLimit,
+				>| {
+					count += 1;
+					Some(v9::OldCollection {
+						owner: old.owner,
+						offchain_data: old.offchain_data,
+						nfts: old.nfts,
+						limit: old.limit,
+						is_closed: old.is_closed,
+						royalty: old.royalty,
+						max_royalty: old.max_royalty,
+						allowlist_root: None,
+					})
+				},
+			);
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV8");
+			Ok(())
+		}
+	}
+}
+
+pub mod v9 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use scale_info::TypeInfo;
+	use sp_std::fmt::Debug;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	#[derive(
+		Encode,
+		Decode,
+		Eq,
+		Default,
+		TypeInfo,
+		CloneNoBound,
+		PartialEqNoBound,
+		RuntimeDebugNoBound,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(CollectionOffChainDataLimit, CollectionSizeLimit))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen))]
+	pub struct OldCollection<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		CollectionOffChainDataLimit: Get<u32>,
+		CollectionSizeLimit: Get<u32>,
+	{
+		/// Collection owner
+		pub owner: AccountId,
+		/// Collection offchain_data
+		pub offchain_data: U8BoundedVec<CollectionOffChainDataLimit>,
+		/// NFTs in that collection
+		pub nfts: BoundedVec<NFTId, CollectionSizeLimit>,
+		/// Maximum length of the collection
+		pub limit: Option<u32>,
+		/// Is collection closed for adding new NFTs
+		pub is_closed: bool,
+		/// Royalty inherited by the NFTs of the collection that do not override it
+		pub royalty: Option<Permill>,
+		/// Maximum royalty of the NFTs of the collection
+		pub max_royalty: Option<Permill>,
+		/// Merkle root of the collection mint allowlist
+		pub allowlist_root: Option<MerkleHash>,
+	}
+
+	/// Replace the NFT list of collections by a counter. The lists are kept in
+	/// `CollectionNftsToMigrate` and moved to `CollectionNfts` by `migrate_collection_nfts`
+	/// over the following blocks.
+	pub struct MigrationV9<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV9<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV9");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			Collections::<T>::translate(
+				|id,
+				 old: OldCollection<
+					T::AccountId,
+					T::CollectionOffchainDataLimit,
+					T::CollectionSizeLimit,
+				>| {
+					count += 1;
+					let mut collection = Collection::new(old.owner, old.offchain_data, old.limit);
+					collection.nfts_count = old.nfts.len() as u32;
+					collection.is_closed = old.is_closed;
+					collection.royalty = old.royalty;
+					collection.max_royalty = old.max_royalty;
+					collection.allowlist_root = old.allowlist_root;
+					if !old.nfts.is_empty() {
+						CollectionNftsToMigrate::<T>::insert(id, old.nfts);
+					}
+
+					Some(collection)
+				},
+			);
+
+			T::DbWeight::get().reads_writes(count, count * 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV9");
+			Ok(())
+		}
+	}
+
+	/// Move NFTs from `CollectionNftsToMigrate` to `CollectionNfts` without exceeding the given
+	/// weight. NFTs burned since the upgrade are skipped. Returns the consumed weight.
+	pub fn migrate_collection_nfts<T: Config>(
+		limit: frame_support::weights::Weight,
+	) -> frame_support::weights::Weight {
+		let db_weight = T::DbWeight::get();
+		let list_weight = db_weight.reads_writes(1, 1);
+		let nft_weight = db_weight.reads_writes(1, 1);
+		let mut weight = db_weight.reads(1);
+
+		while weight.saturating_add(list_weight).saturating_add(nft_weight).ref_time() <=
+			limit.ref_time()
+		{
+			let (collection_id, nfts) = match CollectionNftsToMigrate::<T>::iter().next() {
+				Some(x) => x,
+				None => break,
+			};
+			weight = weight.saturating_add(list_weight);
+
+			let mut nfts = nfts.into_inner();
+			while weight.saturating_add(nft_weight).ref_time() <= limit.ref_time() {
+				let nft_id = match nfts.pop() {
+					Some(x) => x,
+					None => break,
+				};
+				let nft = Nfts::<T>::get(nft_id);
+				if nft.map_or(false, |x| x.collection_id == Some(collection_id)) {
+					CollectionNfts::<T>::insert(collection_id, nft_id, ());
+				}
+				weight = weight.saturating_add(nft_weight);
+			}
+
+			if nfts.is_empty() {
+				CollectionNftsToMigrate::<T>::remove(collection_id);
+			} else {
+				let nfts = BoundedVec::try_from(nfts).unwrap_or_default();
+				CollectionNftsToMigrate::<T>::insert(collection_id, nfts);
+				break
+			}
+		}
+
+		weight
+	}
+}
@@ -15,11 +15,19 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
-use crate::{tests::mock, Collection, CollectionId, Error, Event as NFTsEvent, NFTData, NFTId};
+use crate::{
+	tests::mock, Collection, CollectionId, Error, Event as NFTsEvent, NFTData, NFTId, NESTING_LOCK,
+};
 use frame_support::{assert_noop, assert_ok, error::BadOrigin, BoundedVec};
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
-use primitives::{nfts::NFTState, tee::ClusterId};
+use primitives::{
+	nfts::{
+		AttributeNamespace, BatchMode, CollectionMintConfig, MerkleHash, MetadataMutability,
+		MinterAttribution, NFTLock, NFTState, NFTStoragePayment, NFTVoucher,
+	},
+	tee::ClusterId,
+};
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
@@ -31,6 +39,7 @@ const INVALID_ID: NFTId = 1001;
 const PERCENT_100: Permill = Permill::from_parts(1000000);
 const PERCENT_80: Permill = Permill::from_parts(800000);
 const PERCENT_0: Permill = Permill::from_parts(0);
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
 
 fn origin(account: u64) -> mock::RuntimeOrigin {
 	RawOrigin::Signed(account).into()
@@ -45,13 +54,13 @@ fn prepare_tests() {
 	let bob: mock::RuntimeOrigin = origin(BOB);
 
 	//Create alice NFT.
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_100, None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_100), None, false).unwrap();
 
 	// Create alice collection.
 	NFT::create_collection(alice, BoundedVec::default(), None).unwrap();
 
 	//Create bob NFT.
-	NFT::create_nft(bob.clone(), BoundedVec::default(), PERCENT_100, None, false).unwrap();
+	NFT::create_nft(bob.clone(), BoundedVec::default(), Some(PERCENT_100), None, false).unwrap();
 
 	// Create bob collection.
 	NFT::create_collection(bob, BoundedVec::default(), None).unwrap();
@@ -96,7 +105,7 @@ mod create_nft {
 			NFT::create_nft(
 				alice,
 				data.offchain_data.clone(),
-				data.royalty,
+				Some(data.royalty),
 				data.collection_id,
 				data.state.is_soulbound,
 			)
@@ -106,6 +115,7 @@ mod create_nft {
 			// Final state checks.
 			let nft = NFT::nfts(nft_id);
 			assert_eq!(nft, Some(data.clone()));
+			assert_eq!(NFT::account_nfts(ALICE, nft_id), Some(()));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance - NFT::nft_mint_fee());
 
 			// Events checks.
@@ -123,6 +133,29 @@ mod create_nft {
 		})
 	}
 
+	#[test]
+	fn create_nft_without_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+
+			// Outside of a collection, the royalty defaults to zero.
+			assert_ok!(NFT::create_nft(alice.clone(), BoundedVec::default(), None, None, false));
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, PERCENT_0);
+
+			// In a collection, the royalty is the collection default.
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			assert_ok!(NFT::create_nft(alice, BoundedVec::default(), None, collection_id, false));
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+		})
+	}
+
 	#[test]
 	fn create_nft_with_collection() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -141,7 +174,7 @@ mod create_nft {
 			NFT::create_nft(
 				alice,
 				data.offchain_data.clone(),
-				data.royalty,
+				Some(data.royalty),
 				data.collection_id,
 				data.state.is_soulbound,
 			)
@@ -152,7 +185,7 @@ mod create_nft {
 			let nft = NFT::nfts(nft_id);
 			assert_eq!(nft, Some(data.clone()));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance - NFT::nft_mint_fee());
-			assert_eq!(NFT::collections(ALICE_COLLECTION_ID).unwrap().nfts.contains(&nft_id), true);
+			assert_eq!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some(), true);
 
 			// Events checks.
 			let event = NFTsEvent::NFTCreated {
@@ -174,7 +207,7 @@ mod create_nft {
 		ExtBuilder::new_build(vec![(ALICE, 1)]).execute_with(|| {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Should fail and storage should remain empty.
-			let err = NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false);
+			let err = NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false);
 			assert_noop!(err, BalanceError::<Test>::InsufficientBalance);
 		})
 	}
@@ -189,7 +222,7 @@ mod create_nft {
 			let err = NFT::create_nft(
 				alice,
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				Some(BOB_COLLECTION_ID),
 				false,
 			);
@@ -211,7 +244,7 @@ mod create_nft {
 			let err = NFT::create_nft(
 				alice,
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				Some(ALICE_COLLECTION_ID),
 				false,
 			);
@@ -232,7 +265,7 @@ mod create_nft {
 				NFT::create_nft(
 					alice.clone(),
 					BoundedVec::default(),
-					PERCENT_0,
+					Some(PERCENT_0),
 					Some(ALICE_COLLECTION_ID),
 					false,
 				)
@@ -243,7 +276,7 @@ mod create_nft {
 			let err = NFT::create_nft(
 				alice,
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				Some(ALICE_COLLECTION_ID),
 				false,
 			);
@@ -265,7 +298,7 @@ mod create_nft {
 			NFT::create_nft(
 				alice.clone(),
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				Some(collection_id),
 				false,
 			)
@@ -275,7 +308,7 @@ mod create_nft {
 			let err = NFT::create_nft(
 				alice,
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				Some(collection_id),
 				false,
 			);
@@ -292,7 +325,7 @@ mod create_nft {
 			let alice_balance = Balances::free_balance(ALICE);
 
 			// Try to create an NFT.
-			let err = NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false);
+			let err = NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false);
 
 			// Should fail because Alice's account must stay alive.
 			assert_noop!(err, BalanceError::<Test>::KeepAlive);
@@ -317,6 +350,8 @@ mod burn_nft {
 
 			// Final state checks.
 			assert_eq!(NFT::nfts(ALICE_NFT_ID).is_some(), false);
+			assert_eq!(NFT::account_nfts(ALICE, ALICE_NFT_ID), None);
+			assert_eq!(BurnedNFTs::get(), vec![(ALICE_NFT_ID, ALICE)]);
 
 			// Events checks.
 			let event = NFTsEvent::NFTBurned { nft_id: ALICE_NFT_ID };
@@ -340,9 +375,10 @@ mod burn_nft {
 			// Final state checks.
 			assert_eq!(NFT::nfts(ALICE_NFT_ID).is_some(), false);
 			assert_eq!(
-				NFT::collections(ALICE_COLLECTION_ID).unwrap().nfts,
-				expected_collection.nfts
+				NFT::collections(ALICE_COLLECTION_ID).unwrap().nfts_count,
+				expected_collection.nfts_count
 			);
+			assert_eq!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, ALICE_NFT_ID), None);
 
 			// Events checks.
 			let event = NFTsEvent::NFTBurned { nft_id: ALICE_NFT_ID };
@@ -361,8 +397,7 @@ mod burn_nft {
 			NFT::add_secret(alice.clone(), ALICE_NFT_ID, offchain_data.clone()).unwrap();
 
 			// Set listed to true for Alice's NFT.
-			let nft_state =
-				NFTState::new(false, false, true, false, false, false, false, false, false);
+			let nft_state = NFTState::new(false, true, false, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			assert_eq!(NFT::secret_nfts_offchain_data(ALICE_NFT_ID).unwrap(), offchain_data);
@@ -426,8 +461,7 @@ mod burn_nft {
 			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, offchain_data.clone()).unwrap();
 
 			// Set listed to true for Alice's NFT.
-			let nft_state =
-				NFTState::new(true, false, false, false, false, true, false, false, false);
+			let nft_state = NFTState::new(true, false, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID).unwrap(), offchain_data);
@@ -504,17 +538,15 @@ mod burn_nft {
 	}
 
 	#[test]
-	fn cannot_burn_listed_nfts() {
+	fn cannot_burn_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
-			// Set listed to true for Alice's NFT.
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 			// Burning an nft.
 			let err = NFT::burn_nft(origin(ALICE), ALICE_NFT_ID);
-			// Should fail because NFT is listed for sale.
-			assert_noop!(err, Error::<Test>::CannotBurnListedNFTs);
+			// Should fail because NFT is locked.
+			assert_noop!(err, Error::<Test>::CannotBurnLockedNFTs);
 		})
 	}
 
@@ -523,43 +555,13 @@ mod burn_nft {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			// Set delegated to true for Alice's NFT.
-			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB)).unwrap();
+			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB), None).unwrap();
 			// Burning an nft.
 			let err = NFT::burn_nft(origin(ALICE), ALICE_NFT_ID);
 			// Should fail because NFT is delegated.
 			assert_noop!(err, Error::<Test>::CannotBurnDelegatedNFTs);
 		})
 	}
-
-	#[test]
-	fn cannot_burn_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			// Set capsule to true for Alice's NFT.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Burning an nft.
-			let err = NFT::burn_nft(origin(ALICE), ALICE_NFT_ID);
-			// Should fail because NFT is capsule.
-			assert_noop!(err, Error::<Test>::CannotBurnRentedNFTs);
-		})
-	}
-
-	#[test]
-	fn cannot_burn_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			// Set transmission to true for Alice's NFT.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Burning an nft.
-			let err = NFT::burn_nft(origin(ALICE), ALICE_NFT_ID);
-			// Should fail because NFT is in transmission.
-			assert_noop!(err, Error::<Test>::CannotBurnNFTsInTransmission);
-		})
-	}
 }
 
 mod transfer_nft {
@@ -578,6 +580,9 @@ mod transfer_nft {
 			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
 			assert_eq!(nft.owner, BOB);
 			assert_eq!(nft.creator, ALICE);
+			assert_eq!(NFT::account_nfts(ALICE, ALICE_NFT_ID), None);
+			assert_eq!(NFT::account_nfts(BOB, ALICE_NFT_ID), Some(()));
+			assert_eq!(TransferredNFTs::get(), vec![(ALICE_NFT_ID, ALICE, BOB)]);
 
 			// Events checks.
 			let event =
@@ -624,18 +629,16 @@ mod transfer_nft {
 	}
 
 	#[test]
-	fn cannot_transfer_listed_nfts() {
+	fn cannot_transfer_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set NFT to listed.
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 			// Try to transfer.
 			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
-			// Should fail because NFT is listed.
-			assert_noop!(err, Error::<Test>::CannotTransferListedNFTs);
+			// Should fail because NFT is locked.
+			assert_noop!(err, Error::<Test>::CannotTransferLockedNFTs);
 		})
 	}
 
@@ -645,7 +648,7 @@ mod transfer_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set NFT to delegated.
-			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB)).unwrap();
+			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB), None).unwrap();
 			// Try to transfer.
 			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
 			// Should fail because NFT is delegated.
@@ -659,7 +662,8 @@ mod transfer_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Create soulbound NFTs.
-			let ok = NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, true);
+			let ok =
+				NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, true);
 			assert_ok!(ok);
 			let nft_id = NFT::get_next_nft_id() - 1;
 			let mut nft = NFT::get_nft(nft_id).unwrap();
@@ -679,8 +683,7 @@ mod transfer_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set NFT to unsynced secret.
-			let nft_state =
-				NFTState::new(false, false, true, false, false, true, false, false, false);
+			let nft_state = NFTState::new(false, true, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Try to transfer.
 			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
@@ -689,30 +692,13 @@ mod transfer_nft {
 		})
 	}
 
-	#[test]
-	fn cannot_transfer_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set NFT to listed.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Try to transfer.
-			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
-			// Should fail because NFT is listed.
-			assert_noop!(err, Error::<Test>::CannotTransferRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_transfer_not_synced_capsules() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set NFT to capsule / capsule syncing.
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(true, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Try to transfer.
 			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
@@ -720,22 +706,6 @@ mod transfer_nft {
 			assert_noop!(err, Error::<Test>::CannotTransferNotSyncedCapsules);
 		})
 	}
-
-	#[test]
-	fn cannot_transfer_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set NFT to in transmission.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Try to transfer.
-			let err = NFT::transfer_nft(alice, ALICE_NFT_ID, BOB);
-			// Should fail because NFT is in transmission.
-			assert_noop!(err, Error::<Test>::CannotTransferNFTsInTransmission);
-		})
-	}
 }
 
 mod delegate_nft {
@@ -750,7 +720,7 @@ mod delegate_nft {
 			let mut expected_data = NFT::nfts(ALICE_NFT_ID).unwrap();
 			expected_data.state.is_delegated = true;
 			// Delegating NFT to another account.
-			let ok = NFT::delegate_nft(alice, ALICE_NFT_ID, Some(BOB));
+			let ok = NFT::delegate_nft(alice, ALICE_NFT_ID, Some(BOB), None);
 			assert_ok!(ok);
 
 			// Final state checks.
@@ -758,7 +728,11 @@ mod delegate_nft {
 			assert_eq!(NFT::delegated_nfts(ALICE_NFT_ID), Some(BOB));
 
 			// Events checks.
-			let event = NFTsEvent::NFTDelegated { nft_id: ALICE_NFT_ID, recipient: Some(BOB) };
+			let event = NFTsEvent::NFTDelegated {
+				nft_id: ALICE_NFT_ID,
+				recipient: Some(BOB),
+				expiry: None,
+			};
 			let event = RuntimeEvent::NFT(event);
 			System::assert_last_event(event);
 		})
@@ -773,9 +747,9 @@ mod delegate_nft {
 			let mut expected_data = NFT::nfts(ALICE_NFT_ID).unwrap();
 			expected_data.state.is_delegated = false;
 			// Delegating NFT to another account.
-			NFT::delegate_nft(alice.clone(), ALICE_NFT_ID, Some(BOB)).unwrap();
+			NFT::delegate_nft(alice.clone(), ALICE_NFT_ID, Some(BOB), None).unwrap();
 			// Delegate NFT to none.
-			let ok = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
+			let ok = NFT::delegate_nft(alice, ALICE_NFT_ID, None, None);
 			assert_ok!(ok);
 
 			// Final state checks.
@@ -783,19 +757,103 @@ mod delegate_nft {
 			assert_eq!(NFT::delegated_nfts(ALICE_NFT_ID), None);
 
 			// Events checks.
-			let event = NFTsEvent::NFTDelegated { nft_id: ALICE_NFT_ID, recipient: None };
+			let event =
+				NFTsEvent::NFTDelegated { nft_id: ALICE_NFT_ID, recipient: None, expiry: None };
 			let event = RuntimeEvent::NFT(event);
 			System::assert_last_event(event);
 		})
 	}
 
+	#[test]
+	fn delegate_nft_with_expiry() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let ok = NFT::delegate_nft(alice, ALICE_NFT_ID, Some(BOB), Some(10));
+			assert_ok!(ok);
+
+			// State checks.
+			assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_delegated);
+			assert_eq!(NFT::delegated_nfts(ALICE_NFT_ID), Some(BOB));
+			assert_eq!(NFT::delegation_expiries(ALICE_NFT_ID), Some(10));
+			assert_eq!(NFT::delegation_queue().size(), 1);
+			let event = NFTsEvent::NFTDelegated {
+				nft_id: ALICE_NFT_ID,
+				recipient: Some(BOB),
+				expiry: Some(10),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// The delegation is still active before the expiry.
+			run_to_block(9);
+			assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_delegated);
+
+			// The delegation is cleared at the expiry.
+			run_to_block(10);
+			assert!(!NFT::nfts(ALICE_NFT_ID).unwrap().state.is_delegated);
+			assert_eq!(NFT::delegated_nfts(ALICE_NFT_ID), None);
+			assert_eq!(NFT::delegation_expiries(ALICE_NFT_ID), None);
+			assert_eq!(NFT::delegation_queue().size(), 0);
+			let event = NFTsEvent::DelegationExpired { nft_id: ALICE_NFT_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn undelegate_nft_with_expiry() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::delegate_nft(alice.clone(), ALICE_NFT_ID, Some(BOB), Some(10)).unwrap();
+			// Redelegate without expiry.
+			NFT::delegate_nft(alice.clone(), ALICE_NFT_ID, Some(BOB), None).unwrap();
+			assert_eq!(NFT::delegation_expiries(ALICE_NFT_ID), None);
+			assert_eq!(NFT::delegation_queue().size(), 0);
+
+			// Delegate again and undelegate before the expiry.
+			NFT::delegate_nft(alice.clone(), ALICE_NFT_ID, Some(BOB), Some(10)).unwrap();
+			assert_ok!(NFT::delegate_nft(alice, ALICE_NFT_ID, None, Some(10)));
+			assert_eq!(NFT::delegation_expiries(ALICE_NFT_ID), None);
+			assert_eq!(NFT::delegation_queue().size(), 0);
+			let event =
+				NFTsEvent::NFTDelegated { nft_id: ALICE_NFT_ID, recipient: None, expiry: None };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn delegation_expiry_in_the_past() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to delegate with an expiry at the current block.
+			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, Some(BOB), Some(1));
+			// Should fail because the expiry must be in the future.
+			assert_noop!(err, Error::<Test>::DelegationExpiryInThePast);
+		})
+	}
+
+	#[test]
+	fn simultaneous_delegation_limit_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let limit = SimultaneousDelegationLimit::get();
+			NFT::fill_delegation_queue(limit, INVALID_ID, 100).unwrap();
+			// Try to delegate with an expiry when the queue is full.
+			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, Some(BOB), Some(10));
+			// Should fail because the queue is full.
+			assert_noop!(err, Error::<Test>::SimultaneousDelegationLimitReached);
+		})
+	}
+
 	#[test]
 	fn nft_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Delegating unexisting NFT.
-			let err = NFT::delegate_nft(alice, INVALID_ID, None);
+			let err = NFT::delegate_nft(alice, INVALID_ID, None, None);
 			// Should fail because NFT does not exist.
 			assert_noop!(err, Error::<Test>::NFTNotFound);
 		})
@@ -807,41 +865,23 @@ mod delegate_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Delegating unowned NFT.
-			let err = NFT::delegate_nft(alice, BOB_NFT_ID, None);
+			let err = NFT::delegate_nft(alice, BOB_NFT_ID, None, None);
 			// Should fail because NFT is not owned by Alice.
 			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
 		})
 	}
 
 	#[test]
-	fn cannot_delegate_listed_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set alice's NFT to listed.
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Delegate listed NFT.
-			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
-			// Should fail because NFT is listed.
-			assert_noop!(err, Error::<Test>::CannotDelegateListedNFTs);
-		})
-	}
-
-	#[test]
-	fn cannot_delegate_rented_nfts() {
+	fn cannot_delegate_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set alice's NFT to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Delegate capsule NFT.
-			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
-			// Should fail because NFT is capsule.
-			assert_noop!(err, Error::<Test>::CannotDelegateRentedNFTs);
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Delegate locked NFT.
+			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None, None);
+			// Should fail because NFT is locked.
+			assert_noop!(err, Error::<Test>::CannotDelegateLockedNFTs);
 		})
 	}
 
@@ -851,11 +891,10 @@ mod delegate_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set alice's NFT to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, true, false, false, false);
+			let nft_state = NFTState::new(false, false, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Delegate capsule NFT.
-			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
+			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None, None);
 			// Should fail because NFT is secret and syncing.
 			assert_noop!(err, Error::<Test>::CannotDelegateSyncingNFTs);
 		})
@@ -867,31 +906,14 @@ mod delegate_nft {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set alice's NFT to capsule / syncing.
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(true, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Delegate capsule NFT.
-			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
+			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None, None);
 			// Should fail because NFT is capsule and syncing.
 			assert_noop!(err, Error::<Test>::CannotDelegateSyncingCapsules);
 		})
 	}
-
-	#[test]
-	fn cannot_delegate_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set alice's NFT to in transmission.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Delegate capsule NFT.
-			let err = NFT::delegate_nft(alice, ALICE_NFT_ID, None);
-			// Should fail because NFT is in transmission.
-			assert_noop!(err, Error::<Test>::CannotDelegateNFTsInTransmission);
-		})
-	}
 }
 
 mod set_royalty {
@@ -959,18 +981,16 @@ mod set_royalty {
 	}
 
 	#[test]
-	fn cannot_set_royalty_for_listed_nfts() {
+	fn cannot_set_royalty_for_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set Alice's NFT to listed.
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 			// Set royalty.
 			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
-			// Should fail because you cannot set royalty for listed NFTs.
-			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForListedNFTs);
+			// Should fail because you cannot set royalty for locked NFTs.
+			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForLockedNFTs);
 		})
 	}
 
@@ -980,7 +1000,7 @@ mod set_royalty {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set Alice's NFT to delegated.
-			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB)).unwrap();
+			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB), None).unwrap();
 			// Set royalty.
 			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
 			// Should fail because you cannot set royalty for delegated NFTs.
@@ -988,30 +1008,13 @@ mod set_royalty {
 		})
 	}
 
-	#[test]
-	fn cannot_set_royalty_for_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set Alice's NFT to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Set royalty.
-			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
-			// Should fail because you cannot set royalty for capsule NFTs.
-			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_set_royalty_for_syncing_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set Alice's NFT to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, true, false, false, false);
+			let nft_state = NFTState::new(false, false, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Set royalty.
 			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
@@ -1026,8 +1029,7 @@ mod set_royalty {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			// Set Alice's NFT to capsule and syncing.
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(true, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 			// Set royalty.
 			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
@@ -1035,22 +1037,6 @@ mod set_royalty {
 			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForSyncingCapsules);
 		})
 	}
-
-	#[test]
-	fn cannot_set_royalty_for_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			// Set Alice's NFT to in transmission.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-			// Set royalty.
-			let err = NFT::set_royalty(alice, ALICE_NFT_ID, PERCENT_80);
-			// Should fail because you cannot set royalty for nft in transmission.
-			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForNFTsInTransmission);
-		})
-	}
 }
 
 mod set_nft_mint_fee {
@@ -1129,6 +1115,7 @@ mod create_collection {
 
 mod burn_collection {
 	use super::*;
+	use frame_support::{traits::Hooks, weights::Weight};
 
 	#[test]
 	fn burn_collection() {
@@ -1149,6 +1136,38 @@ mod burn_collection {
 		})
 	}
 
+	#[test]
+	fn mint_data_is_cleared_on_idle() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_mint_config(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				Some(public_mint::mint_config(0)),
+			)
+			.unwrap();
+			let accounts = BoundedVec::try_from(vec![BOB]).unwrap();
+			NFT::set_mint_allowlist(origin(ALICE), ALICE_COLLECTION_ID, accounts, true).unwrap();
+			NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default()).unwrap();
+			NFT::burn_nft(origin(BOB), NFT::next_nft_id() - 1).unwrap();
+			assert_ok!(NFT::burn_collection(origin(ALICE), ALICE_COLLECTION_ID));
+
+			// The mint data is queued and cleared when the blocks have spare weight.
+			assert_eq!(NFT::mint_allowlists_to_clear(ALICE_COLLECTION_ID), Some(()));
+			assert_eq!(NFT::collection_mints_to_clear(ALICE_COLLECTION_ID), Some(()));
+			assert_eq!(NFT::public_mints(ALICE_COLLECTION_ID, BOB), 1);
+			NFT::on_idle(1, Weight::zero());
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, BOB), Some(()));
+			NFT::on_idle(1, Weight::from_ref_time(u64::MAX));
+
+			// Final state checks.
+			assert_eq!(NFT::mint_allowlists_to_clear(ALICE_COLLECTION_ID), None);
+			assert_eq!(NFT::collection_mints_to_clear(ALICE_COLLECTION_ID), None);
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, BOB), None);
+			assert_eq!(NFT::public_mints(ALICE_COLLECTION_ID, BOB), 0);
+		})
+	}
+
 	#[test]
 	fn collection_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -1324,7 +1343,7 @@ mod limit_collection {
 			let ok = NFT::create_nft(
 				alice.clone(),
 				BoundedVec::default(),
-				PERCENT_100,
+				Some(PERCENT_100),
 				Some(ALICE_COLLECTION_ID),
 				false,
 			);
@@ -1360,7 +1379,7 @@ mod add_nft_to_collection {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let mut expected_collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
-			expected_collection.nfts.try_push(ALICE_COLLECTION_ID).unwrap();
+			expected_collection.nfts_count += 1;
 			// Add Alice's NFT to her collection.
 			let ok = NFT::add_nft_to_collection(alice, ALICE_NFT_ID, ALICE_COLLECTION_ID);
 			assert_ok!(ok);
@@ -1427,7 +1446,7 @@ mod add_nft_to_collection {
 				NFT::create_nft(
 					alice.clone(),
 					BoundedVec::default(),
-					PERCENT_0,
+					Some(PERCENT_0),
 					Some(ALICE_COLLECTION_ID),
 					false,
 				)
@@ -1453,7 +1472,7 @@ mod add_nft_to_collection {
 				NFT::create_nft(
 					alice.clone(),
 					BoundedVec::default(),
-					PERCENT_0,
+					Some(PERCENT_0),
 					Some(ALICE_COLLECTION_ID),
 					false,
 				)
@@ -1562,19 +1581,17 @@ mod add_secret {
 	}
 
 	#[test]
-	fn cannot_add_secret_to_listed_nfts() {
+	fn cannot_add_secret_to_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
-			// Set Alice's NFT to listed
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 
 			// Add a secret to Alice's NFT.
 			let err = NFT::add_secret(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotAddSecretToListedNFTs);
+			assert_noop!(err, Error::<Test>::CannotAddSecretToLockedNFTs);
 		})
 	}
 
@@ -1585,8 +1602,7 @@ mod add_secret {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 			// Set Alice's NFT to secret
-			let nft_state =
-				NFTState::new(false, false, true, false, false, false, false, false, false);
+			let nft_state = NFTState::new(false, true, false, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Add a secret to Alice's NFT.
@@ -1595,23 +1611,6 @@ mod add_secret {
 		})
 	}
 
-	#[test]
-	fn cannot_add_secret_to_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
-			// Set Alice's NFT to rented
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-			// Add a secret to Alice's NFT.
-			let err = NFT::add_secret(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotAddSecretToRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_add_secret_to_delegated_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -1619,8 +1618,7 @@ mod add_secret {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 			// Set Alice's NFT to delegated
-			let nft_state =
-				NFTState::new(false, false, false, true, false, false, false, false, false);
+			let nft_state = NFTState::new(false, false, true, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Add a secret to Alice's NFT.
@@ -1636,8 +1634,7 @@ mod add_secret {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 			// Set Alice's NFT to syncing capsule
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(true, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Add a secret to Alice's NFT.
@@ -1646,23 +1643,6 @@ mod add_secret {
 		})
 	}
 
-	#[test]
-	fn cannot_add_secret_to_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
-			// Set Alice's NFT to in transmission
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-			// Add a secret to Alice's NFT.
-			let err = NFT::add_secret(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotAddSecretToNFTsInTransmission);
-		})
-	}
-
 	#[test]
 	fn not_enough_balance() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -1698,7 +1678,7 @@ mod create_secret_nft {
 				alice,
 				data.offchain_data.clone(),
 				offchain_data.clone(),
-				data.royalty,
+				Some(data.royalty),
 				data.collection_id,
 				data.state.is_soulbound,
 			)
@@ -1740,7 +1720,7 @@ mod create_secret_nft {
 				alice,
 				BoundedVec::default(),
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				None,
 				false,
 			);
@@ -1761,7 +1741,7 @@ mod create_secret_nft {
 					alice,
 					BoundedVec::default(),
 					BoundedVec::default(),
-					PERCENT_0,
+					Some(PERCENT_0),
 					None,
 					false,
 				);
@@ -1772,6 +1752,35 @@ mod create_secret_nft {
 				assert_eq!(Balances::free_balance(ALICE), alice_balance);
 			})
 	}
+
+	#[test]
+	fn nft_without_royalty_inherits_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			let data = BoundedVec::default();
+			assert_ok!(NFT::create_secret_nft(
+				origin(ALICE),
+				data.clone(),
+				data,
+				None,
+				collection_id,
+				false
+			));
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			// Updating the collection royalty changes the royalty of the NFT.
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(nft_id), PERCENT_80);
+		})
+	}
 }
 
 mod add_secret_shard {
@@ -1819,6 +1828,7 @@ mod add_secret_shard {
 
 				// Add a secret to Alice's NFT.
 				NFT::add_secret(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+				let collector_balance = Balances::free_balance(COLLECTOR);
 
 				NFT::add_secret_shard(alice_enclave, ALICE_NFT_ID).unwrap();
 				NFT::add_secret_shard(bob_enclave, ALICE_NFT_ID).unwrap();
@@ -1829,6 +1839,14 @@ mod add_secret_shard {
 				assert_eq!(nft.state.is_secret, true);
 				assert_eq!(nft.state.is_syncing_secret, false);
 				assert_eq!(shards, None);
+				assert_eq!(NFT::secret_syncs(ALICE_NFT_ID), None);
+				// The reserved secret NFT mint fee is collected once synced.
+				assert_eq!(NFT::pending_secret_fees(ALICE_NFT_ID), None);
+				assert_eq!(Balances::reserved_balance(ALICE), 0);
+				assert_eq!(
+					Balances::free_balance(COLLECTOR),
+					collector_balance + SECRET_NFT_MINT_FEE
+				);
 
 				// Events checks.
 				let event = RuntimeEvent::NFT(NFTsEvent::ShardAdded {
@@ -2040,20 +2058,18 @@ mod convert_to_capsule {
 	}
 
 	#[test]
-	fn cannot_convert_listed_nfts() {
+	fn cannot_convert_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 
-			// Change NFT State
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Lock the NFT.
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 
 			// Convert Alice's NFT to capsule.
 			let err = NFT::convert_to_capsule(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotConvertListedNFTs);
+			assert_noop!(err, Error::<Test>::CannotConvertLockedNFTs);
 		})
 	}
 
@@ -2065,8 +2081,7 @@ mod convert_to_capsule {
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, false, false);
+			let nft_state = NFTState::new(true, false, false, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Convert Alice's NFT to capsule.
@@ -2075,24 +2090,6 @@ mod convert_to_capsule {
 		})
 	}
 
-	#[test]
-	fn cannot_convert_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
-
-			// Change NFT State
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-			// Convert Alice's NFT to capsule.
-			let err = NFT::convert_to_capsule(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotConvertRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_convert_delegated_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -2101,8 +2098,7 @@ mod convert_to_capsule {
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(false, false, false, true, false, false, false, false, false);
+			let nft_state = NFTState::new(false, false, true, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Convert Alice's NFT to capsule.
@@ -2119,8 +2115,7 @@ mod convert_to_capsule {
 			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(false, false, false, false, false, true, false, false, false);
+			let nft_state = NFTState::new(false, false, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Convert Alice's NFT to capsule.
@@ -2129,24 +2124,6 @@ mod convert_to_capsule {
 		})
 	}
 
-	#[test]
-	fn cannot_convert_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> = BoundedVec::default();
-
-			// Change NFT State
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-			// Convert Alice's NFT to capsule.
-			let err = NFT::convert_to_capsule(alice, ALICE_NFT_ID, offchain_data.clone());
-			assert_noop!(err, Error::<Test>::CannotConvertNFTsInTransmission);
-		})
-	}
-
 	#[test]
 	fn not_enough_balance() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -2182,7 +2159,7 @@ mod create_capsule {
 				alice,
 				data.offchain_data.clone(),
 				offchain_data.clone(),
-				data.royalty,
+				Some(data.royalty),
 				data.collection_id,
 				data.state.is_soulbound,
 			)
@@ -2225,7 +2202,7 @@ mod create_capsule {
 				alice,
 				BoundedVec::default(),
 				BoundedVec::default(),
-				PERCENT_0,
+				Some(PERCENT_0),
 				None,
 				false,
 			);
@@ -2246,7 +2223,7 @@ mod create_capsule {
 					alice,
 					BoundedVec::default(),
 					BoundedVec::default(),
-					PERCENT_0,
+					Some(PERCENT_0),
 					None,
 					false,
 				);
@@ -2257,274 +2234,346 @@ mod create_capsule {
 				assert_eq!(Balances::free_balance(ALICE), alice_balance);
 			})
 	}
-}
 
-// TODO: add back when we can revert capsule
-// mod revert_capsule {
-// 	use super::*;
-
-// 	#[test]
-// 	fn revert_capsule() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			prepare_tee_for_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-// 			let alice_enclave: mock::RuntimeOrigin = origin(ALICE_ENCLAVE);
-// 			// Convert Alice's NFT to capsule.
-// 			let ok = NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default());
-// 			assert_ok!(ok);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, false, false, false, false, false, false, true, false);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-// 			NFT::add_capsule_shard(alice_enclave, ALICE_NFT_ID).unwrap();
-
-// 			// Revert capsule
-// 			let ok = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_ok!(ok);
-
-// 			// Final state checks.
-// 			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
-// 			assert_eq!(nft.state.is_capsule, false);
-// 			assert_eq!(nft.state.is_syncing_capsule, false);
-// 			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID), None);
-// 			assert_eq!(NFT::capsules_shards_count(ALICE_NFT_ID), None);
-
-// 			// Events checks.
-// 			let event = NFTsEvent::CapsuleReverted { nft_id: ALICE_NFT_ID };
-// 			let event = RuntimeEvent::NFT(event);
-// 			System::assert_last_event(event);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn nft_not_found() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, INVALID_ID);
-// 			assert_noop!(err, Error::<Test>::NFTNotFound);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn not_the_nft_owner() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, BOB_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn nft_is_not_capsule() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::NFTIsNotCapsule);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn cannot_revert_listed_nfts() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, true, false, false, false, false, false, false, false);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::CannotRevertListedNFTs);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn cannot_revert_rented_nfts() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, false, false, false, false, false, true, false, false);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::CannotRevertRentedNFTs);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn cannot_revert_delegated_nfts() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, false, false, true, false, false, false, false, false);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::CannotRevertDelegatedNFTs);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn cannot_revert_syncing_nfts() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, false, false, false, false, true, false, false, false);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::CannotRevertSyncingNFTs);
-// 		})
-// 	}
-
-// 	#[test]
-// 	fn cannot_revert_nfts_in_transmission() {
-// 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-// 			prepare_tests();
-// 			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-// 			// Change NFT State
-// 			let nft_state =
-// 				NFTState::new(true, false, false, false, false, false, false, false, true);
-// 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-// 			// Revert capsule
-// 			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
-// 			assert_noop!(err, Error::<Test>::CannotRevertNFTsInTransmission);
-// 		})
-// 	}
-// }
+	#[test]
+	fn capsule_without_royalty_inherits_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			let data = BoundedVec::default();
+			assert_ok!(NFT::create_capsule(
+				origin(ALICE),
+				data.clone(),
+				data,
+				None,
+				collection_id,
+				false
+			));
+			let nft_id = NFT::next_nft_id() - 1;
 
-mod set_capsule_offchaindata {
+			// Final state checks.
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			// Updating the collection royalty changes the royalty of the NFT.
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(nft_id), PERCENT_80);
+		})
+	}
+}
+
+mod revert_capsule {
 	use super::*;
 
+	/// Convert Alice's NFT to a synced capsule.
+	fn prepare_capsule() {
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		NFT::convert_to_capsule(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+		let nft_state = NFTState::new(true, false, false, false, false, false);
+		NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+	}
+
 	#[test]
-	fn set_capsule_offchaindata() {
+	fn revert_capsule() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-			// Convert Alice's NFT to capsule.
-			let ok = NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default());
-			assert_ok!(ok);
-
-			// Change NFT State.
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			prepare_capsule();
+			let alice_balance = Balances::free_balance(ALICE);
 
-			// Set capsule offchain data.
-			let ok = NFT::set_capsule_offchaindata(alice, ALICE_NFT_ID, BoundedVec::default());
+			// Revert capsule
+			let ok = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
 			assert_ok!(ok);
 
 			// Final state checks.
 			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
-			assert_eq!(nft.state.is_capsule, true);
+			assert_eq!(nft.state.is_capsule, false);
 			assert_eq!(nft.state.is_syncing_capsule, false);
-			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID), Some(BoundedVec::default()));
+			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID), None);
+			assert_eq!(NFT::capsules_shards_count(ALICE_NFT_ID), None);
+			// Nothing is refunded by default.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
 
 			// Events checks.
-			let event = NFTsEvent::CapsuleOffchainDataSet {
-				nft_id: ALICE_NFT_ID,
-				offchain_data: BoundedVec::default(),
-			};
+			let event = NFTsEvent::CapsuleReverted { nft_id: ALICE_NFT_ID, refund: 0 };
 			let event = RuntimeEvent::NFT(event);
 			System::assert_last_event(event);
 		})
 	}
 
 	#[test]
-	fn nft_not_found() {
+	fn revert_capsule_with_refund() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
+			CapsuleRefundRatio::set(Permill::from_percent(50));
+			let alice_balance = Balances::free_balance(ALICE);
+			prepare_capsule();
 
-			// Set capsule offchain data.
-			let err = NFT::set_capsule_offchaindata(alice, INVALID_ID, BoundedVec::default());
-			assert_noop!(err, Error::<Test>::NFTNotFound);
-		})
-	}
+			// Half of the fee is held in a deposit.
+			let refund = CAPSULE_MINT_FEE / 2;
+			assert_eq!(NFT::capsule_deposits(ALICE_NFT_ID), Some((ALICE, refund)));
+			assert_eq!(Balances::reserved_balance(ALICE), refund);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - CAPSULE_MINT_FEE);
+
+			// Revert capsule
+			let ok = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::capsule_deposits(ALICE_NFT_ID), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - CAPSULE_MINT_FEE + refund);
+
+			// Events checks.
+			let event = NFTsEvent::CapsuleReverted { nft_id: ALICE_NFT_ID, refund };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn refund_goes_to_the_payer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			CapsuleRefundRatio::set(Permill::from_percent(50));
+			prepare_capsule();
+			NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID, BOB).unwrap();
+			let bob_balance = Balances::free_balance(BOB);
+
+			// Revert capsule
+			assert_ok!(NFT::revert_capsule(origin(BOB), ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+		})
+	}
+
+	#[test]
+	fn burn_capsule_releases_deposit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			CapsuleRefundRatio::set(Permill::from_percent(50));
+			prepare_capsule();
+
+			assert_ok!(NFT::burn_nft(origin(ALICE), ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::capsule_deposits(ALICE_NFT_ID), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn convert_reverted_capsule() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_capsule();
+			NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID).unwrap();
+
+			// The NFT can become a capsule again.
+			let ok = NFT::convert_to_capsule(origin(ALICE), ALICE_NFT_ID, BoundedVec::default());
+			assert_ok!(ok);
+			assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_capsule);
+		})
+	}
+
+	#[test]
+	fn revert_secret_capsule() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_capsule();
+			// Add a synced secret to the capsule.
+			let nft_state = NFTState::new(true, true, false, false, false, false);
+			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+
+			// Revert capsule
+			assert_ok!(NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID));
+
+			// The secret is kept.
+			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.state.is_capsule, false);
+			assert_eq!(nft.state.is_secret, true);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), INVALID_ID);
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
 
 	#[test]
 	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), BOB_NFT_ID);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn nft_is_not_capsule() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::NFTIsNotCapsule);
+		})
+	}
+
+	#[test]
+	fn cannot_revert_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_capsule();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotRevertLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_revert_delegated_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_capsule();
+			// Change NFT State
+			let nft_state = NFTState::new(true, false, true, false, false, false);
+			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotRevertDelegatedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_revert_syncing_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_capsule();
+			// Change NFT State
+			let nft_state = NFTState::new(true, true, false, false, true, false);
+			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+
+			// Revert capsule
+			let err = NFT::revert_capsule(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotRevertSyncingNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_revert_syncing_capsules() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+
+			// Revert capsule
+			let err = NFT::revert_capsule(alice, ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotRevertSyncingCapsules);
+		})
+	}
+}
+
+mod set_capsule_offchaindata {
+	use super::*;
+
+	#[test]
+	fn set_capsule_offchaindata() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
+			// Convert Alice's NFT to capsule.
+			let ok = NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default());
+			assert_ok!(ok);
+
+			// Change NFT State.
+			let nft_state = NFTState::new(true, false, false, false, false, false);
+			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+
 			// Set capsule offchain data.
-			let err = NFT::set_capsule_offchaindata(alice, BOB_NFT_ID, BoundedVec::default());
-			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+			let ok = NFT::set_capsule_offchaindata(alice, ALICE_NFT_ID, BoundedVec::default());
+			assert_ok!(ok);
+
+			// Final state checks.
+			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.state.is_capsule, true);
+			assert_eq!(nft.state.is_syncing_capsule, false);
+			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID), Some(BoundedVec::default()));
+
+			// Events checks.
+			let event = NFTsEvent::CapsuleOffchainDataSet {
+				nft_id: ALICE_NFT_ID,
+				offchain_data: BoundedVec::default(),
+			};
+			let event = RuntimeEvent::NFT(event);
+			System::assert_last_event(event);
 		})
 	}
 
 	#[test]
-	fn nft_is_not_capsule() {
+	fn nft_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Set capsule offchain data.
-			let err = NFT::set_capsule_offchaindata(alice, ALICE_NFT_ID, BoundedVec::default());
-			assert_noop!(err, Error::<Test>::NFTIsNotCapsule);
+			let err = NFT::set_capsule_offchaindata(alice, INVALID_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NFTNotFound);
 		})
 	}
 
 	#[test]
-	fn cannot_set_offchain_data_for_listed_nfts() {
+	fn not_the_nft_owner() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
-			// Change NFT State
-			let nft_state =
-				NFTState::new(true, true, false, false, false, false, false, false, false);
-			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			// Set capsule offchain data.
+			let err = NFT::set_capsule_offchaindata(alice, BOB_NFT_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn nft_is_not_capsule() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Set capsule offchain data.
 			let err = NFT::set_capsule_offchaindata(alice, ALICE_NFT_ID, BoundedVec::default());
-			assert_noop!(err, Error::<Test>::CannotSetOffchainDataForListedNFTs);
+			assert_noop!(err, Error::<Test>::NFTIsNotCapsule);
 		})
 	}
 
 	#[test]
-	fn cannot_set_offchain_data_for_rented_nfts() {
+	fn cannot_set_offchain_data_for_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, true, false, false);
+			let nft_state = NFTState::new(true, false, false, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 
 			// Set capsule offchain data.
 			let err = NFT::set_capsule_offchaindata(alice, ALICE_NFT_ID, BoundedVec::default());
-			assert_noop!(err, Error::<Test>::CannotSetOffchainDataForRentedNFTs);
+			assert_noop!(err, Error::<Test>::CannotSetOffchainDataForLockedNFTs);
 		})
 	}
 
@@ -2535,8 +2584,7 @@ mod set_capsule_offchaindata {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(true, false, false, true, false, false, false, false, false);
+			let nft_state = NFTState::new(true, false, true, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Set capsule offchain data.
@@ -2552,8 +2600,7 @@ mod set_capsule_offchaindata {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(true, false, false, false, false, true, false, false, false);
+			let nft_state = NFTState::new(true, false, false, false, true, false);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Set capsule offchain data.
@@ -2569,8 +2616,7 @@ mod set_capsule_offchaindata {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Change NFT State
-			let nft_state =
-				NFTState::new(true, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(true, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 			// Set capsule offchain data.
@@ -2812,8 +2858,7 @@ mod notify_enclave_key_update {
 					.unwrap();
 
 				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, false, false, false, false, false, false);
+				let nft_state = NFTState::new(true, false, false, false, false, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let ok = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
@@ -2872,29 +2917,7 @@ mod notify_enclave_key_update {
 	}
 
 	#[test]
-	fn cannot_change_key_for_listed_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
-			|| {
-				prepare_tests();
-				let alice: mock::RuntimeOrigin = origin(ALICE);
-
-				// Add a secret to Alice's NFT.
-				NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default())
-					.unwrap();
-
-				// Change NFT State
-				let nft_state =
-					NFTState::new(true, true, false, false, false, false, false, false, false);
-				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
-				assert_noop!(err, Error::<Test>::CannotChangeKeyForListedNFTs);
-			},
-		)
-	}
-
-	#[test]
-	fn cannot_change_key_for_rented_nfts() {
+	fn cannot_change_key_for_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
 			|| {
 				prepare_tests();
@@ -2905,12 +2928,12 @@ mod notify_enclave_key_update {
 					.unwrap();
 
 				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, false, false, false, true, false, false);
+				let nft_state = NFTState::new(true, false, false, false, false, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
+				NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
 
 				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
-				assert_noop!(err, Error::<Test>::CannotChangeKeyForRentedNFTs);
+				assert_noop!(err, Error::<Test>::CannotChangeKeyForLockedNFTs);
 			},
 		)
 	}
@@ -2927,8 +2950,7 @@ mod notify_enclave_key_update {
 					.unwrap();
 
 				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, true, false, false, false, false, false);
+				let nft_state = NFTState::new(true, false, true, false, false, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
@@ -2949,8 +2971,7 @@ mod notify_enclave_key_update {
 					.unwrap();
 
 				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, false, false, true, false, false, false);
+				let nft_state = NFTState::new(true, false, false, false, true, false);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
@@ -2971,8 +2992,7 @@ mod notify_enclave_key_update {
 					.unwrap();
 
 				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, false, false, false, false, true, false);
+				let nft_state = NFTState::new(true, false, false, false, false, true);
 				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
 
 				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
@@ -2980,28 +3000,6 @@ mod notify_enclave_key_update {
 			},
 		)
 	}
-
-	#[test]
-	fn cannot_change_key_for_nfts_in_transmission() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
-			|| {
-				prepare_tests();
-				let alice: mock::RuntimeOrigin = origin(ALICE);
-
-				// Add a secret to Alice's NFT.
-				NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default())
-					.unwrap();
-
-				// Change NFT State
-				let nft_state =
-					NFTState::new(true, false, false, false, false, false, false, false, true);
-				NFT::set_nft_state(ALICE_NFT_ID, nft_state).unwrap();
-
-				let err = NFT::notify_enclave_key_update(alice, ALICE_NFT_ID);
-				assert_noop!(err, Error::<Test>::CannotChangeKeyForNFTsInTransmission);
-			},
-		)
-	}
 }
 
 mod set_collection_offchaindata {
@@ -3060,3 +3058,4679 @@ mod set_collection_offchaindata {
 		})
 	}
 }
+
+mod create_nfts_batch {
+	use super::*;
+
+	fn batch(
+		amount: usize,
+	) -> BoundedVec<(BoundedVec<u8, NFTOffchainDataLimit>, Option<Permill>, bool), BatchSizeLimit> {
+		BoundedVec::try_from(vec![(BoundedVec::default(), Some(PERCENT_0), false); amount]).unwrap()
+	}
+
+	#[test]
+	fn create_nfts_batch() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			let first_nft_id = NFT::next_nft_id();
+
+			let ok = NFT::create_nfts_batch(
+				alice,
+				ALICE_COLLECTION_ID,
+				batch(3),
+				BatchMode::AllOrNothing,
+			);
+			assert_ok!(ok);
+
+			// Final state checks.
+			for nft_id in first_nft_id..first_nft_id + 3 {
+				let nft = NFT::nfts(nft_id).unwrap();
+				assert_eq!(nft.owner, ALICE);
+				assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+				assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			}
+			// The fee is charged once for all the NFTs.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - 3 * NFT_MINT_FEE);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let err =
+				NFT::create_nfts_batch(alice, BOB_COLLECTION_ID, batch(1), BatchMode::AllOrNothing);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_is_closed() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::close_collection(alice.clone(), ALICE_COLLECTION_ID).unwrap();
+			let err =
+				NFT::create_nfts_batch(alice, ALICE_COLLECTION_ID, batch(1), BatchMode::BestEffort);
+			assert_noop!(err, Error::<Test>::CollectionIsClosed);
+		})
+	}
+
+	#[test]
+	fn all_or_nothing_collection_has_reached_limit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::limit_collection(alice.clone(), ALICE_COLLECTION_ID, 2).unwrap();
+			let err = NFT::create_nfts_batch(
+				alice,
+				ALICE_COLLECTION_ID,
+				batch(3),
+				BatchMode::AllOrNothing,
+			);
+			// Should fail because the batch does not fit in the collection.
+			assert_noop!(err, Error::<Test>::CollectionHasReachedLimit);
+		})
+	}
+
+	#[test]
+	fn all_or_nothing_insufficient_balance() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			// Alice can only pay the mint fee of two NFTs.
+			Balances::reserve(&ALICE, alice_balance - 2 * NFT_MINT_FEE - 1).unwrap();
+			let err = NFT::create_nfts_batch(
+				alice,
+				ALICE_COLLECTION_ID,
+				batch(3),
+				BatchMode::AllOrNothing,
+			);
+			// Should fail because Alice cannot pay the fee of the whole batch.
+			assert_noop!(err, Error::<Test>::InsufficientBalance);
+		})
+	}
+
+	#[test]
+	fn best_effort_collection_has_reached_limit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			NFT::limit_collection(alice.clone(), ALICE_COLLECTION_ID, 2).unwrap();
+			let ok =
+				NFT::create_nfts_batch(alice, ALICE_COLLECTION_ID, batch(3), BatchMode::BestEffort);
+			assert_ok!(ok);
+
+			// Only the NFTs that fit in the collection are created and paid for.
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.nfts_count, 2);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - 2 * NFT_MINT_FEE);
+
+			// Events checks.
+			let error = Error::<Test>::CollectionHasReachedLimit.into();
+			let event = NFTsEvent::NFTBatchItemFailed { index: 2, error };
+			let event = RuntimeEvent::NFT(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn best_effort_skips_royalties_above_maximum() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, None, Some(PERCENT_80))
+				.unwrap();
+			let first_nft_id = NFT::next_nft_id();
+			let nfts = vec![
+				(BoundedVec::default(), Some(PERCENT_0), false),
+				(BoundedVec::default(), Some(PERCENT_100), false),
+				(BoundedVec::default(), Some(PERCENT_80), false),
+				(BoundedVec::default(), Some(PERCENT_100), false),
+			];
+			let nfts = BoundedVec::try_from(nfts).unwrap();
+			let ok =
+				NFT::create_nfts_batch(alice, ALICE_COLLECTION_ID, nfts, BatchMode::BestEffort);
+			assert_ok!(ok);
+
+			// Final state checks.
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.nfts_count, 2);
+			assert_eq!(NFT::nfts(first_nft_id).unwrap().royalty, PERCENT_0);
+			assert_eq!(NFT::nfts(first_nft_id + 1).unwrap().royalty, PERCENT_80);
+			assert_eq!(NFT::next_nft_id(), first_nft_id + 2);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - 2 * NFT_MINT_FEE);
+
+			// Events checks.
+			let error = Error::<Test>::RoyaltyExceedsCollectionMaximum.into();
+			let events = System::events().into_iter().map(|r| r.event).collect::<Vec<_>>();
+			let event = NFTsEvent::NFTBatchItemFailed { index: 1, error };
+			assert!(events.contains(&RuntimeEvent::NFT(event)));
+			let event = NFTsEvent::NFTBatchItemFailed { index: 3, error };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn all_or_nothing_royalty_above_maximum() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, None, Some(PERCENT_80))
+				.unwrap();
+			let nfts = vec![
+				(BoundedVec::default(), Some(PERCENT_0), false),
+				(BoundedVec::default(), Some(PERCENT_100), false),
+			];
+			let nfts = BoundedVec::try_from(nfts).unwrap();
+			let err =
+				NFT::create_nfts_batch(alice, ALICE_COLLECTION_ID, nfts, BatchMode::AllOrNothing);
+			// Should fail because the second royalty is above the maximum of the collection.
+			assert_noop!(err, Error::<Test>::RoyaltyExceedsCollectionMaximum);
+		})
+	}
+
+	#[test]
+	fn nfts_without_royalty_inherit_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+			let nfts = BoundedVec::try_from(vec![(BoundedVec::default(), None, false)]).unwrap();
+			assert_ok!(NFT::create_nfts_batch(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				nfts,
+				BatchMode::AllOrNothing
+			));
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			// Updating the collection royalty changes the royalty of the NFT.
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(nft_id), PERCENT_80);
+		})
+	}
+}
+
+mod transfer_nfts_batch {
+	use super::*;
+
+	#[test]
+	fn transfer_nfts_batch() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
+			let second_nft_id = NFT::next_nft_id() - 1;
+
+			let transfers =
+				BoundedVec::try_from(vec![(ALICE_NFT_ID, BOB), (second_nft_id, CHARLIE)]).unwrap();
+			let ok = NFT::transfer_nfts_batch(alice, transfers, BatchMode::AllOrNothing);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, BOB);
+			assert_eq!(NFT::nfts(second_nft_id).unwrap().owner, CHARLIE);
+
+			// Events checks.
+			let event = NFTsEvent::NFTTransferred {
+				nft_id: second_nft_id,
+				sender: ALICE,
+				recipient: CHARLIE,
+			};
+			let event = RuntimeEvent::NFT(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn all_or_nothing_reverts_on_failure() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let transfers =
+				BoundedVec::try_from(vec![(ALICE_NFT_ID, BOB), (BOB_NFT_ID, CHARLIE)]).unwrap();
+			let err = NFT::transfer_nfts_batch(alice, transfers, BatchMode::AllOrNothing);
+			// Should fail because Alice does not own the second NFT.
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn best_effort_skips_failures() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			let transfers =
+				BoundedVec::try_from(vec![(ALICE_NFT_ID, BOB), (BOB_NFT_ID, CHARLIE)]).unwrap();
+			let ok = NFT::transfer_nfts_batch(alice.clone(), transfers, BatchMode::BestEffort);
+			assert_ok!(ok);
+
+			// Locked and unowned NFTs are not transferred.
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, ALICE);
+			assert_eq!(NFT::nfts(BOB_NFT_ID).unwrap().owner, BOB);
+
+			// Events checks.
+			let error = Error::<Test>::CannotTransferLockedNFTs.into();
+			let event = NFTsEvent::NFTBatchItemFailed { index: 0, error };
+			System::assert_has_event(RuntimeEvent::NFT(event));
+			let error = Error::<Test>::NotTheNFTOwner.into();
+			let event = NFTsEvent::NFTBatchItemFailed { index: 1, error };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Valid transfers still go through.
+			NFT::unlock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			let transfers =
+				BoundedVec::try_from(vec![(ALICE_NFT_ID, BOB), (BOB_NFT_ID, CHARLIE)]).unwrap();
+			assert_ok!(NFT::transfer_nfts_batch(alice, transfers, BatchMode::BestEffort));
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, BOB);
+		})
+	}
+}
+
+mod approve_transfer {
+	use super::*;
+
+	#[test]
+	fn approve_transfer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let ok = NFT::approve_transfer(alice, ALICE_NFT_ID, BOB, Some(10));
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), Some((BOB, Some(10))));
+
+			// Events checks.
+			let event = NFTsEvent::TransferApproved {
+				nft_id: ALICE_NFT_ID,
+				owner: ALICE,
+				delegate: BOB,
+				expiry: Some(10),
+			};
+			let event = RuntimeEvent::NFT(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn approve_transfer_replaces_previous_approval() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			assert_ok!(NFT::approve_transfer(alice.clone(), ALICE_NFT_ID, BOB, None));
+			assert_ok!(NFT::approve_transfer(alice, ALICE_NFT_ID, CHARLIE, None));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), Some((CHARLIE, None)));
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to approve with an unknown NFT id.
+			let err = NFT::approve_transfer(alice, INVALID_ID, BOB, None);
+			// Should fail because NFT does not exist.
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to approve an unowned NFT.
+			let err = NFT::approve_transfer(alice, BOB_NFT_ID, CHARLIE, None);
+			// Should fail because Alice is not the NFT owner.
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_approve_yourself() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to approve the owner.
+			let err = NFT::approve_transfer(alice, ALICE_NFT_ID, ALICE, None);
+			// Should fail because Alice is the owner.
+			assert_noop!(err, Error::<Test>::CannotApproveYourself);
+		})
+	}
+
+	#[test]
+	fn approval_expiry_in_the_past() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			System::set_block_number(5);
+			// Try to approve with an expiry that is not in the future.
+			let err = NFT::approve_transfer(alice, ALICE_NFT_ID, BOB, Some(5));
+			// Should fail because the expiry block is the current block.
+			assert_noop!(err, Error::<Test>::ApprovalExpiryInThePast);
+		})
+	}
+}
+
+mod cancel_approval {
+	use super::*;
+
+	#[test]
+	fn cancel_approval() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::approve_transfer(alice.clone(), ALICE_NFT_ID, BOB, None).unwrap();
+			let ok = NFT::cancel_approval(alice, ALICE_NFT_ID);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), None);
+
+			// Events checks.
+			let event = NFTsEvent::ApprovalCancelled { nft_id: ALICE_NFT_ID };
+			let event = RuntimeEvent::NFT(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(BOB), BOB_NFT_ID, CHARLIE, None).unwrap();
+			// Try to cancel the approval of an unowned NFT.
+			let err = NFT::cancel_approval(origin(ALICE), BOB_NFT_ID);
+			// Should fail because Alice is not the NFT owner.
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn approval_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to cancel a missing approval.
+			let err = NFT::cancel_approval(alice, ALICE_NFT_ID);
+			// Should fail because no approval was given.
+			assert_noop!(err, Error::<Test>::ApprovalNotFound);
+		})
+	}
+}
+
+mod set_approval_for_all {
+	use super::*;
+
+	#[test]
+	fn set_approval_for_all() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let ok = NFT::set_approval_for_all(alice.clone(), BOB, true);
+			assert_ok!(ok);
+			assert_eq!(NFT::operator_approvals(ALICE, BOB), Some(()));
+
+			// Events checks.
+			let event =
+				NFTsEvent::ApprovalForAllSet { owner: ALICE, operator: BOB, approved: true };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Revoke the operator.
+			let ok = NFT::set_approval_for_all(alice, BOB, false);
+			assert_ok!(ok);
+			assert_eq!(NFT::operator_approvals(ALICE, BOB), None);
+
+			// Events checks.
+			let event =
+				NFTsEvent::ApprovalForAllSet { owner: ALICE, operator: BOB, approved: false };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn cannot_approve_yourself() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set the caller as its own operator.
+			let err = NFT::set_approval_for_all(alice, ALICE, true);
+			// Should fail because the operator is the caller.
+			assert_noop!(err, Error::<Test>::CannotApproveYourself);
+		})
+	}
+}
+
+mod transfer_from {
+	use super::*;
+
+	#[test]
+	fn transfer_from_approved_account() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, None).unwrap();
+			let ok = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, CHARLIE);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, CHARLIE);
+			assert_eq!(NFT::account_nfts(ALICE, ALICE_NFT_ID), None);
+			assert_eq!(NFT::account_nfts(CHARLIE, ALICE_NFT_ID), Some(()));
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), None);
+
+			// Events checks.
+			let event = NFTsEvent::NFTTransferred {
+				nft_id: ALICE_NFT_ID,
+				sender: ALICE,
+				recipient: CHARLIE,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// The approval cannot be reused.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			assert_noop!(err, Error::<Test>::NotApprovedToTransfer);
+		})
+	}
+
+	#[test]
+	fn transfer_from_operator() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_approval_for_all(origin(ALICE), BOB, true).unwrap();
+			let ok = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, BOB);
+			// The operator stays approved for the remaining NFTs of the owner.
+			assert_eq!(NFT::operator_approvals(ALICE, BOB), Some(()));
+		})
+	}
+
+	#[test]
+	fn transfer_nft_clears_approval() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, CHARLIE, None).unwrap();
+			NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID, BOB).unwrap();
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), None);
+
+			// Try to transfer with the approval of the previous owner.
+			let err = NFT::transfer_from(origin(CHARLIE), ALICE_NFT_ID, CHARLIE);
+			// Should fail because the approval was cleared.
+			assert_noop!(err, Error::<Test>::NotApprovedToTransfer);
+		})
+	}
+
+	#[test]
+	fn burn_nft_clears_approval() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, None).unwrap();
+			NFT::burn_nft(origin(ALICE), ALICE_NFT_ID).unwrap();
+			assert_eq!(NFT::nft_approvals(ALICE_NFT_ID), None);
+		})
+	}
+
+	#[test]
+	fn not_approved_to_transfer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, CHARLIE, None).unwrap();
+			// Try to transfer without approval.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			// Should fail because only Charlie is approved.
+			assert_noop!(err, Error::<Test>::NotApprovedToTransfer);
+		})
+	}
+
+	#[test]
+	fn approval_expired() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, Some(10)).unwrap();
+			System::set_block_number(10);
+			// Try to transfer once the approval expired.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			// Should fail because the approval expired.
+			assert_noop!(err, Error::<Test>::NotApprovedToTransfer);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to transfer with an unknown NFT id.
+			let err = NFT::transfer_from(origin(BOB), INVALID_ID, BOB);
+			// Should fail because NFT does not exist.
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn cannot_transfer_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, None).unwrap();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Try to transfer a locked NFT.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			// Should fail because NFT is locked.
+			assert_noop!(err, Error::<Test>::CannotTransferLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_transfer_delegated_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, None).unwrap();
+			NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(CHARLIE), None).unwrap();
+			// Try to transfer a delegated NFT.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, BOB);
+			// Should fail because NFT is delegated.
+			assert_noop!(err, Error::<Test>::CannotTransferDelegatedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_transfer_nfts_to_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::approve_transfer(origin(ALICE), ALICE_NFT_ID, BOB, None).unwrap();
+			// Try to transfer back to the owner.
+			let err = NFT::transfer_from(origin(BOB), ALICE_NFT_ID, ALICE);
+			// Should fail because the owner is the recipient.
+			assert_noop!(err, Error::<Test>::CannotTransferNFTsToYourself);
+		})
+	}
+}
+
+mod set_royalty_splits {
+	use super::*;
+
+	fn splits(
+		splits: Vec<(u64, Permill)>,
+	) -> BoundedVec<(u64, Permill), <Test as crate::Config>::RoyaltySplitsLimit> {
+		BoundedVec::try_from(splits).unwrap()
+	}
+
+	#[test]
+	fn set_royalty_splits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let percent_20 = Permill::from_percent(20);
+			let splits = splits(vec![(ALICE, PERCENT_80), (BOB, percent_20)]);
+			let ok = NFT::set_royalty_splits(alice.clone(), ALICE_NFT_ID, Some(splits.clone()));
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::nft_royalty_splits(ALICE_NFT_ID), Some(splits.clone()));
+
+			// Events checks.
+			let event =
+				NFTsEvent::NFTRoyaltySplitsSet { nft_id: ALICE_NFT_ID, splits: Some(splits) };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Remove the splits.
+			assert_ok!(NFT::set_royalty_splits(alice, ALICE_NFT_ID, None));
+			assert_eq!(NFT::nft_royalty_splits(ALICE_NFT_ID), None);
+		})
+	}
+
+	#[test]
+	fn burn_nft_removes_royalty_splits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let splits = splits(vec![(BOB, PERCENT_100)]);
+			NFT::set_royalty_splits(alice.clone(), ALICE_NFT_ID, Some(splits)).unwrap();
+			NFT::burn_nft(alice, ALICE_NFT_ID).unwrap();
+			assert_eq!(NFT::nft_royalty_splits(ALICE_NFT_ID), None);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits with an unknown NFT id.
+			let err = NFT::set_royalty_splits(alice, INVALID_ID, None);
+			// Should fail because NFT does not exist.
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_creator() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::transfer_nft(origin(BOB), BOB_NFT_ID, ALICE).unwrap();
+			// Try to set splits of an NFT created by Bob.
+			let err = NFT::set_royalty_splits(alice, BOB_NFT_ID, None);
+			// Should fail because Alice is not the NFT creator.
+			assert_noop!(err, Error::<Test>::NotTheNFTCreator);
+		})
+	}
+
+	#[test]
+	fn cannot_set_royalty_for_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Try to set splits of a locked NFT.
+			let err = NFT::set_royalty_splits(alice, ALICE_NFT_ID, None);
+			// Should fail because NFT is locked.
+			assert_noop!(err, Error::<Test>::CannotSetRoyaltyForLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn royalty_splits_must_sum_to_100_percent() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits that do not add up to 100%.
+			let splits = splits(vec![(ALICE, PERCENT_80)]);
+			let err = NFT::set_royalty_splits(alice.clone(), ALICE_NFT_ID, Some(splits));
+			// Should fail because shares add up to 80%.
+			assert_noop!(err, Error::<Test>::RoyaltySplitsMustSumTo100Percent);
+
+			// Empty splits are rejected as well.
+			let err = NFT::set_royalty_splits(alice, ALICE_NFT_ID, Some(BoundedVec::default()));
+			assert_noop!(err, Error::<Test>::RoyaltySplitsMustSumTo100Percent);
+		})
+	}
+
+	#[test]
+	fn duplicate_royalty_beneficiary() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits with the same beneficiary twice.
+			let half = Permill::from_percent(50);
+			let splits = splits(vec![(BOB, half), (BOB, half)]);
+			let err = NFT::set_royalty_splits(alice, ALICE_NFT_ID, Some(splits));
+			// Should fail because Bob appears twice.
+			assert_noop!(err, Error::<Test>::DuplicateRoyaltyBeneficiary);
+		})
+	}
+}
+
+mod set_collection_royalty_splits {
+	use super::*;
+
+	#[test]
+	fn set_collection_royalty_splits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let splits = BoundedVec::try_from(vec![(BOB, PERCENT_100)]).unwrap();
+			let ok = NFT::set_collection_royalty_splits(
+				alice.clone(),
+				ALICE_COLLECTION_ID,
+				Some(splits.clone()),
+			);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::collection_royalty_splits(ALICE_COLLECTION_ID), Some(splits.clone()));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionRoyaltySplitsSet {
+				collection_id: ALICE_COLLECTION_ID,
+				splits: Some(splits),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Burning the collection removes the splits.
+			NFT::burn_collection(alice, ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(NFT::collection_royalty_splits(ALICE_COLLECTION_ID), None);
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits with an unknown collection id.
+			let err = NFT::set_collection_royalty_splits(alice, INVALID_ID, None);
+			// Should fail because collection does not exist.
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits of Bob's collection.
+			let err = NFT::set_collection_royalty_splits(alice, BOB_COLLECTION_ID, None);
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn royalty_splits_must_sum_to_100_percent() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set splits that do not add up to 100%.
+			let splits = BoundedVec::try_from(vec![(BOB, PERCENT_80)]).unwrap();
+			let err = NFT::set_collection_royalty_splits(alice, ALICE_COLLECTION_ID, Some(splits));
+			// Should fail because shares add up to 80%.
+			assert_noop!(err, Error::<Test>::RoyaltySplitsMustSumTo100Percent);
+		})
+	}
+}
+
+mod set_collection_royalty {
+	use super::*;
+
+	#[test]
+	fn set_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let percent_50 = Permill::from_percent(50);
+			let ok = NFT::set_collection_royalty(
+				alice,
+				ALICE_COLLECTION_ID,
+				Some(percent_50),
+				Some(PERCENT_80),
+			);
+			assert_ok!(ok);
+
+			// Final state checks.
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.royalty, Some(percent_50));
+			assert_eq!(collection.max_royalty, Some(PERCENT_80));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionRoyaltySet {
+				collection_id: ALICE_COLLECTION_ID,
+				royalty: Some(percent_50),
+				max_royalty: Some(PERCENT_80),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn nfts_inherit_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let percent_50 = Permill::from_percent(50);
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+
+			// An NFT minted without a royalty follows it.
+			NFT::create_nft(alice.clone(), BoundedVec::default(), None, collection_id, false)
+				.unwrap();
+			let inheriting_id = NFT::next_nft_id() - 1;
+			// An NFT minted with a royalty overrides it, even if it matches the default.
+			NFT::create_nft(
+				alice.clone(),
+				BoundedVec::default(),
+				Some(percent_50),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let matching_id = NFT::next_nft_id() - 1;
+			// An NFT minted with another royalty overrides it.
+			NFT::create_nft(
+				alice.clone(),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let overriding_id = NFT::next_nft_id() - 1;
+			assert_eq!(NFT::royalty_overrides(inheriting_id), None);
+			assert_eq!(NFT::royalty_overrides(matching_id), Some(()));
+			assert_eq!(NFT::royalty_overrides(overriding_id), Some(()));
+			assert_eq!(NFT::nfts(inheriting_id).unwrap().royalty, percent_50);
+			assert_eq!(NFT::get_royalty(inheriting_id), percent_50);
+			assert_eq!(NFT::get_royalty(matching_id), percent_50);
+			assert_eq!(NFT::get_royalty(overriding_id), PERCENT_0);
+
+			// Updating the collection royalty only changes the inheriting NFT.
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(inheriting_id), PERCENT_80);
+			assert_eq!(NFT::get_royalty(matching_id), percent_50);
+			assert_eq!(NFT::get_royalty(overriding_id), PERCENT_0);
+
+			// Setting the royalty of an NFT overrides the collection royalty.
+			NFT::set_royalty(alice.clone(), inheriting_id, PERCENT_100).unwrap();
+			assert_eq!(NFT::get_royalty(inheriting_id), PERCENT_100);
+
+			// The maximum royalty caps every NFT of the collection.
+			NFT::set_collection_royalty(alice, ALICE_COLLECTION_ID, None, Some(percent_50))
+				.unwrap();
+			assert_eq!(NFT::get_royalty(inheriting_id), percent_50);
+			assert_eq!(NFT::get_royalty(overriding_id), PERCENT_0);
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set the royalty of an unknown collection.
+			let err = NFT::set_collection_royalty(alice, INVALID_ID, None, None);
+			// Should fail because collection does not exist.
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to set the royalty of Bob's collection.
+			let err = NFT::set_collection_royalty(alice, BOB_COLLECTION_ID, None, None);
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn royalty_exceeds_collection_maximum() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			// Try to set a default royalty above the maximum.
+			let err = NFT::set_collection_royalty(
+				alice.clone(),
+				ALICE_COLLECTION_ID,
+				Some(PERCENT_100),
+				Some(PERCENT_80),
+			);
+			// Should fail because the default is above the maximum.
+			assert_noop!(err, Error::<Test>::RoyaltyExceedsCollectionMaximum);
+
+			NFT::set_collection_royalty(alice.clone(), ALICE_COLLECTION_ID, None, Some(PERCENT_80))
+				.unwrap();
+			// NFTs cannot be minted with a royalty above the maximum.
+			let err = NFT::create_nft(
+				alice.clone(),
+				BoundedVec::default(),
+				Some(PERCENT_100),
+				collection_id,
+				false,
+			);
+			assert_noop!(err, Error::<Test>::RoyaltyExceedsCollectionMaximum);
+			// NFTs with a royalty above the maximum cannot be added.
+			let err = NFT::add_nft_to_collection(alice.clone(), ALICE_NFT_ID, ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::RoyaltyExceedsCollectionMaximum);
+			// The royalty of NFTs cannot be raised above the maximum.
+			NFT::create_nft(
+				alice.clone(),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			let err = NFT::set_royalty(alice, nft_id, PERCENT_100);
+			assert_noop!(err, Error::<Test>::RoyaltyExceedsCollectionMaximum);
+		})
+	}
+}
+
+mod add_minter {
+	use super::*;
+
+	#[test]
+	fn add_minter() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let ok = NFT::add_minter(alice, ALICE_COLLECTION_ID, BOB);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::collection_minters(ALICE_COLLECTION_ID).into_inner(), vec![BOB]);
+
+			// Events checks.
+			let event = NFTsEvent::CollectionMinterAdded {
+				collection_id: ALICE_COLLECTION_ID,
+				minter: BOB,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to add a minter to an unknown collection.
+			let err = NFT::add_minter(alice, INVALID_ID, BOB);
+			// Should fail because collection does not exist.
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to add a minter to Bob's collection.
+			let err = NFT::add_minter(alice, BOB_COLLECTION_ID, CHARLIE);
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn already_a_collection_minter() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_minter(alice.clone(), ALICE_COLLECTION_ID, BOB).unwrap();
+			// Try to add the same minter twice.
+			let err = NFT::add_minter(alice, ALICE_COLLECTION_ID, BOB);
+			// Should fail because Bob is already a minter.
+			assert_noop!(err, Error::<Test>::AlreadyACollectionMinter);
+		})
+	}
+
+	#[test]
+	fn too_many_collection_minters() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let limit = CollectionMintersLimit::get() as u64;
+			for minter in 0..limit {
+				NFT::add_minter(alice.clone(), ALICE_COLLECTION_ID, 100 + minter).unwrap();
+			}
+			// Try to add one more minter.
+			let err = NFT::add_minter(alice, ALICE_COLLECTION_ID, BOB);
+			// Should fail because the collection reached its minter limit.
+			assert_noop!(err, Error::<Test>::TooManyCollectionMinters);
+		})
+	}
+}
+
+mod remove_minter {
+	use super::*;
+
+	#[test]
+	fn remove_minter() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_minter(alice.clone(), ALICE_COLLECTION_ID, BOB).unwrap();
+			let ok = NFT::remove_minter(alice, ALICE_COLLECTION_ID, BOB);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert!(NFT::collection_minters(ALICE_COLLECTION_ID).is_empty());
+
+			// Events checks.
+			let event = NFTsEvent::CollectionMinterRemoved {
+				collection_id: ALICE_COLLECTION_ID,
+				minter: BOB,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Removed minters cannot mint anymore.
+			let err = NFT::create_nft(
+				origin(BOB),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				Some(ALICE_COLLECTION_ID),
+				false,
+			);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::add_minter(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			// Try to remove a minter as a minter.
+			let err = NFT::remove_minter(origin(BOB), ALICE_COLLECTION_ID, BOB);
+			// Should fail because Bob is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn not_a_collection_minter() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to remove an account that is not a minter.
+			let err = NFT::remove_minter(alice, ALICE_COLLECTION_ID, BOB);
+			// Should fail because Bob is not a minter.
+			assert_noop!(err, Error::<Test>::NotACollectionMinter);
+		})
+	}
+}
+
+mod set_minter_attribution {
+	use super::*;
+
+	#[test]
+	fn set_minter_attribution() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let attribution = MinterAttribution::CollectionOwner;
+			let ok = NFT::set_minter_attribution(alice, ALICE_COLLECTION_ID, attribution);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::minter_attributions(ALICE_COLLECTION_ID), attribution);
+
+			// Events checks.
+			let event =
+				NFTsEvent::MinterAttributionSet { collection_id: ALICE_COLLECTION_ID, attribution };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let attribution = MinterAttribution::CollectionOwner;
+			// Try to set the attribution of Bob's collection.
+			let err = NFT::set_minter_attribution(alice, BOB_COLLECTION_ID, attribution);
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod create_nft_for {
+	use super::*;
+
+	#[test]
+	fn create_nft_for() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::add_minter(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			let bob_balance = Balances::free_balance(BOB);
+			let nft_id = NFT::next_nft_id();
+			let ok = NFT::create_nft_for(
+				origin(BOB),
+				CHARLIE,
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				ALICE_COLLECTION_ID,
+				false,
+			);
+			assert_ok!(ok);
+
+			// Final state checks.
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, CHARLIE);
+			assert_eq!(nft.creator, BOB);
+			assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+			assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			assert_eq!(NFT::account_nfts(CHARLIE, nft_id), Some(()));
+			// The minter pays the mint fee.
+			assert_eq!(Balances::free_balance(BOB), bob_balance - NFT::nft_mint_fee());
+
+			// Events checks.
+			let event = NFTsEvent::NFTCreated {
+				nft_id,
+				owner: CHARLIE,
+				offchain_data: BoundedVec::default(),
+				royalty: PERCENT_0,
+				collection_id: Some(ALICE_COLLECTION_ID),
+				is_soulbound: false,
+				mint_fee: NFT::nft_mint_fee(),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_owner_attribution() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let attribution = MinterAttribution::CollectionOwner;
+			NFT::add_minter(alice.clone(), ALICE_COLLECTION_ID, BOB).unwrap();
+			NFT::set_minter_attribution(alice, ALICE_COLLECTION_ID, attribution).unwrap();
+
+			// Minting to another account.
+			let collection_id = ALICE_COLLECTION_ID;
+			let data = BoundedVec::default();
+			NFT::create_nft_for(origin(BOB), CHARLIE, data, Some(PERCENT_0), collection_id, false)
+				.unwrap();
+			let nft = NFT::nfts(NFT::next_nft_id() - 1).unwrap();
+			assert_eq!(nft.owner, CHARLIE);
+			assert_eq!(nft.creator, ALICE);
+
+			// Minting to the minter with create_nft.
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::create_nft(
+				origin(BOB),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft = NFT::nfts(NFT::next_nft_id() - 1).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+		})
+	}
+
+	#[test]
+	fn collection_owner_can_mint_for_others() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let data = BoundedVec::default();
+			let ok = NFT::create_nft_for(
+				origin(ALICE),
+				BOB,
+				data,
+				Some(PERCENT_0),
+				ALICE_COLLECTION_ID,
+				false,
+			);
+			assert_ok!(ok);
+			let nft = NFT::nfts(NFT::next_nft_id() - 1).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let data = BoundedVec::default();
+			// Try to mint into Alice's collection without being a minter.
+			let err = NFT::create_nft_for(
+				origin(BOB),
+				BOB,
+				data,
+				Some(PERCENT_0),
+				ALICE_COLLECTION_ID,
+				false,
+			);
+			// Should fail because Bob is neither the owner nor a minter.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_is_closed() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::add_minter(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			NFT::close_collection(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			let data = BoundedVec::default();
+			// Try to mint into a closed collection.
+			let err = NFT::create_nft_for(
+				origin(BOB),
+				BOB,
+				data,
+				Some(PERCENT_0),
+				ALICE_COLLECTION_ID,
+				false,
+			);
+			// Should fail because the collection is closed.
+			assert_noop!(err, Error::<Test>::CollectionIsClosed);
+		})
+	}
+
+	#[test]
+	fn nft_without_royalty_inherits_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+			let data = BoundedVec::default();
+			assert_ok!(NFT::create_nft_for(
+				origin(ALICE),
+				BOB,
+				data,
+				None,
+				ALICE_COLLECTION_ID,
+				false
+			));
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			// Updating the collection royalty changes the royalty of the NFT.
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(nft_id), PERCENT_80);
+		})
+	}
+}
+
+mod set_mint_config {
+	use super::*;
+
+	#[test]
+	fn set_mint_config() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let config = public_mint::mint_config(50);
+			let ok = NFT::set_mint_config(alice.clone(), ALICE_COLLECTION_ID, Some(config.clone()));
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::collection_mint_configs(ALICE_COLLECTION_ID), Some(config.clone()));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionMintConfigSet {
+				collection_id: ALICE_COLLECTION_ID,
+				config: Some(config),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Remove the configuration.
+			assert_ok!(NFT::set_mint_config(alice, ALICE_COLLECTION_ID, None));
+			assert_eq!(NFT::collection_mint_configs(ALICE_COLLECTION_ID), None);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			// Try to configure Bob's collection.
+			let err = NFT::set_mint_config(alice, BOB_COLLECTION_ID, None);
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn invalid_mint_period() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let mut config = public_mint::mint_config(50);
+			config.start_block = Some(10);
+			config.end_block = Some(5);
+			// Try to set a mint ending before it starts.
+			let err = NFT::set_mint_config(alice, ALICE_COLLECTION_ID, Some(config));
+			// Should fail because the end block is before the start block.
+			assert_noop!(err, Error::<Test>::InvalidMintPeriod);
+		})
+	}
+}
+
+mod set_mint_allowlist {
+	use super::*;
+
+	#[test]
+	fn set_mint_allowlist() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let accounts = BoundedVec::try_from(vec![BOB, CHARLIE]).unwrap();
+			let ok = NFT::set_mint_allowlist(alice.clone(), ALICE_COLLECTION_ID, accounts, true);
+			assert_ok!(ok);
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, BOB), Some(()));
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, CHARLIE), Some(()));
+
+			// Remove Bob.
+			let accounts = BoundedVec::try_from(vec![BOB]).unwrap();
+			let ok = NFT::set_mint_allowlist(alice, ALICE_COLLECTION_ID, accounts.clone(), false);
+			assert_ok!(ok);
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, BOB), None);
+			assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, CHARLIE), Some(()));
+
+			// Events checks.
+			let event = NFTsEvent::MintAllowlistUpdated {
+				collection_id: ALICE_COLLECTION_ID,
+				accounts,
+				allowed: false,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let accounts = BoundedVec::try_from(vec![BOB]).unwrap();
+			// Try to allowlist itself in Alice's collection.
+			let err = NFT::set_mint_allowlist(origin(BOB), ALICE_COLLECTION_ID, accounts, true);
+			// Should fail because Bob is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod public_mint {
+	use super::*;
+
+	pub fn mint_config(price: u64) -> CollectionMintConfig<u64, u64, u64> {
+		CollectionMintConfig {
+			price,
+			start_block: None,
+			end_block: None,
+			max_per_account: None,
+			payout: None,
+			allowlist_until: None,
+		}
+	}
+
+	fn prepare_public_mint(config: CollectionMintConfig<u64, u64, u64>) {
+		prepare_tests();
+		NFT::set_mint_config(origin(ALICE), ALICE_COLLECTION_ID, Some(config)).unwrap();
+	}
+
+	#[test]
+	fn public_mint() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_public_mint(mint_config(50));
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let nft_id = NFT::next_nft_id();
+			let ok = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			assert_ok!(ok);
+
+			// Final state checks.
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+			assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+			assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			assert_eq!(NFT::public_mints(ALICE_COLLECTION_ID, BOB), 1);
+			// Bob pays the price to Alice and the mint fee.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + 50);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - 50 - NFT::nft_mint_fee());
+
+			// Events checks.
+			let event = NFTsEvent::NFTPublicMinted {
+				nft_id,
+				collection_id: ALICE_COLLECTION_ID,
+				owner: BOB,
+				price: 50,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn public_mint_pays_payout_account() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				let mut config = mint_config(50);
+				config.payout = Some(CHARLIE);
+				prepare_public_mint(config);
+				let alice_balance = Balances::free_balance(ALICE);
+				let charlie_balance = Balances::free_balance(CHARLIE);
+				NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default()).unwrap();
+				assert_eq!(Balances::free_balance(ALICE), alice_balance);
+				assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + 50);
+			},
+		)
+	}
+
+	#[test]
+	fn public_mint_closes_sold_out_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_public_mint(mint_config(0));
+			NFT::limit_collection(origin(ALICE), ALICE_COLLECTION_ID, 1).unwrap();
+			NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default()).unwrap();
+
+			// Final state checks.
+			assert!(NFT::collections(ALICE_COLLECTION_ID).unwrap().is_closed);
+			let event = NFTsEvent::CollectionClosed { collection_id: ALICE_COLLECTION_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Try to mint in the sold out collection.
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			// Should fail because the collection is closed.
+			assert_noop!(err, Error::<Test>::CollectionIsClosed);
+		})
+	}
+
+	#[test]
+	fn public_mint_not_configured() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to mint without public mint.
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			// Should fail because the collection has no public mint.
+			assert_noop!(err, Error::<Test>::PublicMintNotConfigured);
+		})
+	}
+
+	#[test]
+	fn public_mint_period() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			let mut config = mint_config(0);
+			config.start_block = Some(5);
+			config.end_block = Some(10);
+			prepare_public_mint(config);
+
+			// Try to mint before the start block.
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::PublicMintNotStarted);
+
+			// Mint during the period.
+			System::set_block_number(10);
+			assert_ok!(NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default()));
+
+			// Try to mint after the end block.
+			System::set_block_number(11);
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::PublicMintEnded);
+		})
+	}
+
+	#[test]
+	fn not_in_mint_allowlist() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				let mut config = mint_config(0);
+				config.allowlist_until = Some(5);
+				prepare_public_mint(config);
+				let accounts = BoundedVec::try_from(vec![BOB]).unwrap();
+				NFT::set_mint_allowlist(origin(ALICE), ALICE_COLLECTION_ID, accounts, true)
+					.unwrap();
+
+				// Allowlisted accounts can mint during the allowlist phase.
+				let data = BoundedVec::default();
+				assert_ok!(NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, data));
+				// Try to mint without being allowlisted.
+				let err =
+					NFT::public_mint(origin(CHARLIE), ALICE_COLLECTION_ID, BoundedVec::default());
+				assert_noop!(err, Error::<Test>::NotInMintAllowlist);
+
+				// Everyone can mint after the allowlist phase.
+				System::set_block_number(5);
+				let data = BoundedVec::default();
+				assert_ok!(NFT::public_mint(origin(CHARLIE), ALICE_COLLECTION_ID, data));
+			},
+		)
+	}
+
+	#[test]
+	fn account_mint_limit_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			let mut config = mint_config(0);
+			config.max_per_account = Some(1);
+			prepare_public_mint(config);
+			NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default()).unwrap();
+			// Try to mint a second NFT.
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			// Should fail because Bob already minted his NFT.
+			assert_noop!(err, Error::<Test>::AccountMintLimitReached);
+		})
+	}
+
+	#[test]
+	fn insufficient_balance() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 100)]).execute_with(|| {
+			prepare_public_mint(mint_config(500));
+			// Try to mint without enough funds.
+			let err = NFT::public_mint(origin(BOB), ALICE_COLLECTION_ID, BoundedVec::default());
+			// Should fail because Bob cannot pay the price.
+			assert_noop!(err, BalanceError::<Test>::InsufficientBalance);
+		})
+	}
+}
+
+mod set_allowlist_root {
+	use super::*;
+
+	#[test]
+	fn set_allowlist_root() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let root = Some([1; 32]);
+			assert_ok!(NFT::set_allowlist_root(alice.clone(), ALICE_COLLECTION_ID, root));
+
+			// Final state checks.
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.allowlist_root, root);
+
+			// Events checks.
+			let event =
+				NFTsEvent::CollectionAllowlistRootSet { collection_id: ALICE_COLLECTION_ID, root };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Remove the root.
+			assert_ok!(NFT::set_allowlist_root(alice, ALICE_COLLECTION_ID, None));
+			assert_eq!(NFT::collections(ALICE_COLLECTION_ID).unwrap().allowlist_root, None);
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to set the root of a non existent collection.
+			let err = NFT::set_allowlist_root(origin(ALICE), INVALID_ID, Some([1; 32]));
+			// Should fail because the collection does not exist.
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to set the root of Bob's collection.
+			let err = NFT::set_allowlist_root(origin(ALICE), BOB_COLLECTION_ID, Some([1; 32]));
+			// Should fail because Alice is not the collection owner.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod allowlist_mint {
+	use super::*;
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+
+	fn hash_pair(a: MerkleHash, b: MerkleHash) -> MerkleHash {
+		if a <= b {
+			BlakeTwo256::hash(&[a, b].concat()).into()
+		} else {
+			BlakeTwo256::hash(&[b, a].concat()).into()
+		}
+	}
+
+	/// Build a four leaves allowlist and return its root with the proofs of Bob (2 NFTs) and
+	/// Charlie (1 NFT).
+	fn allowlist() -> (MerkleHash, Vec<MerkleHash>, Vec<MerkleHash>) {
+		let bob = NFT::allowlist_leaf(&BOB, 2);
+		let charlie = NFT::allowlist_leaf(&CHARLIE, 1);
+		let left = hash_pair(bob, charlie);
+		let right = hash_pair(NFT::allowlist_leaf(&ALICE, 1), NFT::allowlist_leaf(&4, 3));
+
+		(hash_pair(left, right), vec![charlie, right], vec![bob, right])
+	}
+
+	fn prepare_allowlist_mint(
+	) -> (BoundedVec<MerkleHash, AllowlistProofLimit>, BoundedVec<MerkleHash, AllowlistProofLimit>)
+	{
+		prepare_tests();
+		let (root, bob_proof, charlie_proof) = allowlist();
+		NFT::set_allowlist_root(origin(ALICE), ALICE_COLLECTION_ID, Some(root)).unwrap();
+
+		(BoundedVec::try_from(bob_proof).unwrap(), BoundedVec::try_from(charlie_proof).unwrap())
+	}
+
+	#[test]
+	fn allowlist_mint() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			let (proof, _) = prepare_allowlist_mint();
+			let bob_balance = Balances::free_balance(BOB);
+			let nft_id = NFT::next_nft_id();
+			let data = BoundedVec::default();
+			let ok = NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 2, proof);
+			assert_ok!(ok);
+
+			// Final state checks.
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+			assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+			assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			assert_eq!(NFT::allowlist_claims(ALICE_COLLECTION_ID, BOB), 1);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - NFT::nft_mint_fee());
+
+			// Events checks.
+			let event = NFTsEvent::NFTAllowlistMinted {
+				nft_id,
+				collection_id: ALICE_COLLECTION_ID,
+				owner: BOB,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn allowlist_mint_closes_sold_out_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				let (bob_proof, charlie_proof) = prepare_allowlist_mint();
+				NFT::limit_collection(origin(ALICE), ALICE_COLLECTION_ID, 1).unwrap();
+				let data = BoundedVec::default();
+				NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 2, bob_proof).unwrap();
+
+				// Final state checks.
+				assert!(NFT::collections(ALICE_COLLECTION_ID).unwrap().is_closed);
+				let event = NFTsEvent::CollectionClosed { collection_id: ALICE_COLLECTION_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+
+				// Try to mint in the sold out collection.
+				let data = BoundedVec::default();
+				let err = NFT::allowlist_mint(
+					origin(CHARLIE),
+					ALICE_COLLECTION_ID,
+					data,
+					1,
+					charlie_proof,
+				);
+				// Should fail because the collection is closed.
+				assert_noop!(err, Error::<Test>::CollectionIsClosed);
+			},
+		)
+	}
+
+	#[test]
+	fn allowlist_not_configured() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let data = BoundedVec::default();
+			// Try to mint in a collection without allowlist.
+			let err = NFT::allowlist_mint(
+				origin(BOB),
+				ALICE_COLLECTION_ID,
+				data,
+				1,
+				BoundedVec::default(),
+			);
+			// Should fail because the collection has no allowlist root.
+			assert_noop!(err, Error::<Test>::AllowlistNotConfigured);
+		})
+	}
+
+	#[test]
+	fn invalid_allowlist_proof() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				let (bob_proof, charlie_proof) = prepare_allowlist_mint();
+				// Try to claim more NFTs than allowed by the leaf.
+				let data = BoundedVec::default();
+				let err = NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 3, bob_proof);
+				assert_noop!(err, Error::<Test>::InvalidAllowlistProof);
+
+				// Try to use Charlie's proof.
+				let data = BoundedVec::default();
+				let err =
+					NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 1, charlie_proof);
+				assert_noop!(err, Error::<Test>::InvalidAllowlistProof);
+			},
+		)
+	}
+
+	#[test]
+	fn allowlist_leaf_already_claimed() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			let (proof, _) = prepare_allowlist_mint();
+			for _ in 0..2 {
+				let data = BoundedVec::default();
+				NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 2, proof.clone())
+					.unwrap();
+			}
+			assert_eq!(NFT::allowlist_claims(ALICE_COLLECTION_ID, BOB), 2);
+
+			// Try to reuse the leaf once fully claimed.
+			let data = BoundedVec::default();
+			let err = NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 2, proof);
+			// Should fail because the leaf has already been claimed.
+			assert_noop!(err, Error::<Test>::AllowlistLeafAlreadyClaimed);
+		})
+	}
+
+	#[test]
+	fn allowlist_quantity_exceeded() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			let (proof, _) = prepare_allowlist_mint();
+			for _ in 0..2 {
+				let data = BoundedVec::default();
+				NFT::allowlist_mint(origin(BOB), ALICE_COLLECTION_ID, data, 2, proof.clone())
+					.unwrap();
+			}
+
+			// A new allowlist gives Bob a single NFT.
+			let root = NFT::allowlist_leaf(&BOB, 1);
+			NFT::set_allowlist_root(origin(ALICE), ALICE_COLLECTION_ID, Some(root)).unwrap();
+			let data = BoundedVec::default();
+			let err = NFT::allowlist_mint(
+				origin(BOB),
+				ALICE_COLLECTION_ID,
+				data,
+				1,
+				BoundedVec::default(),
+			);
+			// Should fail because Bob already minted more NFTs than his new allowance.
+			assert_noop!(err, Error::<Test>::AllowlistQuantityExceeded);
+		})
+	}
+}
+
+mod redeem_voucher {
+	use super::*;
+	use crate::VOUCHER_SIGNING_PREFIX;
+	use parity_scale_codec::Encode;
+	use sp_core::H256;
+	use sp_runtime::testing::TestSignature;
+
+	type Voucher = NFTVoucher<u64, u64, u64, NFTOffchainDataLimit>;
+
+	fn new_voucher(nonce: u64) -> Voucher {
+		NFTVoucher {
+			creator: ALICE,
+			offchain_data: BoundedVec::try_from(vec![1]).unwrap(),
+			royalty: Some(PERCENT_80),
+			collection_id: Some(ALICE_COLLECTION_ID),
+			price: 50,
+			expiry: 10,
+			nonce,
+		}
+	}
+
+	fn sign(signer: u64, voucher: &Voucher) -> TestSignature {
+		TestSignature(signer, NFT::voucher_signing_payload(voucher))
+	}
+
+	#[test]
+	fn redeem_voucher() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let nft_id = NFT::next_nft_id();
+			let voucher = new_voucher(0);
+			let signature = sign(ALICE, &voucher);
+			assert_ok!(NFT::redeem_voucher(origin(BOB), voucher.clone(), signature));
+
+			// Final state checks.
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+			assert_eq!(nft.offchain_data, voucher.offchain_data);
+			assert_eq!(nft.royalty, PERCENT_80);
+			assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+			assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			assert_eq!(NFT::redeemed_vouchers(ALICE, 0), Some(()));
+			// Bob pays the price to Alice and the mint fee.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + 50);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - 50 - NFT::nft_mint_fee());
+
+			// Events checks.
+			let event = NFTsEvent::VoucherRedeemed {
+				nft_id,
+				creator: ALICE,
+				owner: BOB,
+				price: 50,
+				nonce: 0,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn voucher_signed_for_another_domain() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let voucher = new_voucher(0);
+			let genesis_hash = System::block_hash(0);
+
+			// Try to redeem a voucher signed without the voucher prefix.
+			let signature = TestSignature(ALICE, voucher.encode());
+			let err = NFT::redeem_voucher(origin(BOB), voucher.clone(), signature);
+			assert_noop!(err, Error::<Test>::InvalidVoucherSignature);
+
+			// Try to redeem a voucher signed with another prefix.
+			let payload = (b"ternoa/nft-listing", genesis_hash, &voucher).encode();
+			let signature = TestSignature(ALICE, payload);
+			let err = NFT::redeem_voucher(origin(BOB), voucher.clone(), signature);
+			assert_noop!(err, Error::<Test>::InvalidVoucherSignature);
+
+			// Try to redeem a voucher signed for another chain.
+			let payload = (VOUCHER_SIGNING_PREFIX, H256::repeat_byte(1), &voucher).encode();
+			let signature = TestSignature(ALICE, payload);
+			let err = NFT::redeem_voucher(origin(BOB), voucher, signature);
+			assert_noop!(err, Error::<Test>::InvalidVoucherSignature);
+		})
+	}
+
+	#[test]
+	fn invalid_voucher_signature() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let voucher = new_voucher(0);
+			// Try to redeem a voucher signed by Bob on behalf of Alice.
+			let err = NFT::redeem_voucher(origin(BOB), voucher.clone(), sign(BOB, &voucher));
+			assert_noop!(err, Error::<Test>::InvalidVoucherSignature);
+
+			// Try to redeem a voucher with a lower price than the signed one.
+			let signature = sign(ALICE, &voucher);
+			let mut tampered = voucher;
+			tampered.price = 1;
+			let err = NFT::redeem_voucher(origin(BOB), tampered, signature);
+			assert_noop!(err, Error::<Test>::InvalidVoucherSignature);
+		})
+	}
+
+	#[test]
+	fn voucher_expired() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let voucher = new_voucher(0);
+			let signature = sign(ALICE, &voucher);
+			System::set_block_number(voucher.expiry + 1);
+			// Try to redeem the voucher after its expiry.
+			let err = NFT::redeem_voucher(origin(BOB), voucher, signature);
+			// Should fail because the voucher has expired.
+			assert_noop!(err, Error::<Test>::VoucherExpired);
+		})
+	}
+
+	#[test]
+	fn voucher_already_redeemed() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let voucher = new_voucher(0);
+			let signature = sign(ALICE, &voucher);
+			NFT::redeem_voucher(origin(BOB), voucher.clone(), signature.clone()).unwrap();
+			// Try to replay the voucher.
+			let err = NFT::redeem_voucher(origin(BOB), voucher, signature);
+			// Should fail because the nonce has already been redeemed.
+			assert_noop!(err, Error::<Test>::VoucherAlreadyRedeemed);
+
+			// Another nonce can still be redeemed.
+			let voucher = new_voucher(1);
+			let signature = sign(ALICE, &voucher);
+			assert_ok!(NFT::redeem_voucher(origin(BOB), voucher, signature));
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mut voucher = new_voucher(0);
+			voucher.collection_id = Some(BOB_COLLECTION_ID);
+			let signature = sign(ALICE, &voucher);
+			// Try to redeem a voucher minting in Bob's collection.
+			let err = NFT::redeem_voucher(origin(BOB), voucher, signature);
+			// Should fail because Alice cannot mint in Bob's collection.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn insufficient_balance() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mut voucher = new_voucher(0);
+			voucher.price = 5000;
+			let signature = sign(ALICE, &voucher);
+			// Try to redeem a voucher without enough funds.
+			let err = NFT::redeem_voucher(origin(BOB), voucher, signature);
+			// Should fail because Bob cannot pay the price.
+			assert_noop!(err, BalanceError::<Test>::InsufficientBalance);
+		})
+	}
+
+	#[test]
+	fn voucher_without_royalty_inherits_collection_royalty() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let percent_50 = Permill::from_percent(50);
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(percent_50), None)
+				.unwrap();
+			let mut voucher = new_voucher(0);
+			voucher.royalty = None;
+			let signature = sign(ALICE, &voucher);
+			assert_ok!(NFT::redeem_voucher(origin(BOB), voucher, signature));
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			assert_eq!(NFT::royalty_overrides(nft_id), None);
+			assert_eq!(NFT::nfts(nft_id).unwrap().royalty, percent_50);
+			// Updating the collection royalty changes the royalty of the NFT.
+			NFT::set_collection_royalty(origin(ALICE), ALICE_COLLECTION_ID, Some(PERCENT_80), None)
+				.unwrap();
+			assert_eq!(NFT::get_royalty(nft_id), PERCENT_80);
+		})
+	}
+}
+
+mod issue_soulbound {
+	use super::*;
+
+	#[test]
+	fn issue_soulbound() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let nft_id = NFT::next_nft_id();
+			let data = BoundedVec::try_from(vec![1]).unwrap();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			let ok = NFT::issue_soulbound(origin(ALICE), BOB, data, collection_id);
+			assert_ok!(ok);
+
+			// Final state checks.
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+			assert_eq!(nft.royalty, PERCENT_0);
+			assert!(nft.state.is_soulbound);
+			assert!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			// Alice pays the mint fee.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - NFT::nft_mint_fee());
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+
+			// Events checks.
+			let event = NFTsEvent::SoulboundIssued { nft_id, issuer: ALICE, recipient: BOB };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// The recipient cannot transfer the NFT.
+			let err = NFT::transfer_nft(origin(BOB), nft_id, ALICE);
+			assert_noop!(err, Error::<Test>::CannotTransferNotCreatedSoulboundNFTs);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let data = BoundedVec::default();
+			// Try to issue a soulbound NFT in Bob's collection.
+			let err = NFT::issue_soulbound(origin(ALICE), BOB, data, Some(BOB_COLLECTION_ID));
+			// Should fail because Alice cannot mint in Bob's collection.
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod revoke_soulbound {
+	use super::*;
+
+	fn issue_to_bob() -> NFTId {
+		let nft_id = NFT::next_nft_id();
+		let collection_id = Some(ALICE_COLLECTION_ID);
+		NFT::issue_soulbound(origin(ALICE), BOB, BoundedVec::default(), collection_id).unwrap();
+		nft_id
+	}
+
+	#[test]
+	fn revoke_soulbound() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = issue_to_bob();
+			assert_ok!(NFT::revoke_soulbound(origin(ALICE), nft_id));
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(nft_id), None);
+			assert!(!NFT::collection_nfts_of(ALICE_COLLECTION_ID, nft_id).is_some());
+			assert_eq!(NFT::account_nfts(BOB, nft_id), None);
+
+			// Events checks.
+			System::assert_has_event(RuntimeEvent::NFT(NFTsEvent::NFTBurned { nft_id }));
+			let event = NFTsEvent::SoulboundRevoked { nft_id };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn not_the_nft_creator() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = issue_to_bob();
+			// Try to revoke the NFT as its owner.
+			let err = NFT::revoke_soulbound(origin(BOB), nft_id);
+			// Should fail because Bob is not the issuer.
+			assert_noop!(err, Error::<Test>::NotTheNFTCreator);
+		})
+	}
+
+	#[test]
+	fn not_an_issued_soulbound_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to revoke a regular NFT.
+			let err = NFT::revoke_soulbound(origin(ALICE), ALICE_NFT_ID);
+			// Should fail because the NFT is not soulbound.
+			assert_noop!(err, Error::<Test>::NotAnIssuedSoulboundNFT);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to revoke a non existent NFT.
+			let err = NFT::revoke_soulbound(origin(ALICE), INVALID_ID);
+			// Should fail because the NFT does not exist.
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+}
+
+mod reject_soulbound {
+	use super::*;
+
+	#[test]
+	fn reject_soulbound() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = NFT::next_nft_id();
+			NFT::issue_soulbound(origin(ALICE), BOB, BoundedVec::default(), None).unwrap();
+			assert_ok!(NFT::reject_soulbound(origin(BOB), nft_id));
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(nft_id), None);
+			assert_eq!(NFT::account_nfts(BOB, nft_id), None);
+
+			// Events checks.
+			let event = NFTsEvent::SoulboundRejected { nft_id };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = NFT::next_nft_id();
+			NFT::issue_soulbound(origin(ALICE), BOB, BoundedVec::default(), None).unwrap();
+			// Try to reject the NFT as its issuer.
+			let err = NFT::reject_soulbound(origin(ALICE), nft_id);
+			// Should fail because Alice is not the owner.
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn not_an_issued_soulbound_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = NFT::next_nft_id();
+			let data = BoundedVec::default();
+			NFT::create_nft(origin(ALICE), data, Some(PERCENT_0), None, true).unwrap();
+			// Try to reject a soulbound NFT minted by its owner.
+			let err = NFT::reject_soulbound(origin(ALICE), nft_id);
+			// Should fail because the NFT was not issued by another account.
+			assert_noop!(err, Error::<Test>::NotAnIssuedSoulboundNFT);
+		})
+	}
+}
+
+fn create_alice_nft() -> NFTId {
+	NFT::create_nft(origin(ALICE), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::next_nft_id() - 1
+}
+
+mod nest_nft {
+	use super::*;
+
+	#[test]
+	fn nest_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			assert_ok!(NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_parents(nft_id), Some(ALICE_NFT_ID));
+			assert_eq!(NFT::nft_children(ALICE_NFT_ID).into_inner(), vec![nft_id]);
+			assert!(NFT::nfts(nft_id).unwrap().state.is_locked_by(&NESTING_LOCK));
+			assert_eq!(NFT::ancestors_of(nft_id), vec![ALICE_NFT_ID]);
+
+			// Events checks.
+			let event = NFTsEvent::NFTNested { nft_id, parent_id: ALICE_NFT_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// The nested NFT cannot be transferred on its own.
+			let err = NFT::transfer_nft(origin(ALICE), nft_id, BOB);
+			assert_noop!(err, Error::<Test>::CannotTransferLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn transfer_parent_moves_nested_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let child_id = create_alice_nft();
+			let grandchild_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), child_id, ALICE_NFT_ID).unwrap();
+			NFT::nest_nft(origin(ALICE), grandchild_id, child_id).unwrap();
+			assert_ok!(NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID, BOB));
+
+			// Final state checks.
+			for nft_id in [ALICE_NFT_ID, child_id, grandchild_id] {
+				assert_eq!(NFT::nfts(nft_id).unwrap().owner, BOB);
+				assert_eq!(NFT::account_nfts(ALICE, nft_id), None);
+				assert_eq!(NFT::account_nfts(BOB, nft_id), Some(()));
+				assert_eq!(NFT::get_nft(nft_id).unwrap().owner, BOB);
+			}
+			assert_eq!(NFT::ancestors_of(grandchild_id), vec![child_id, ALICE_NFT_ID]);
+			let transfers = [ALICE_NFT_ID, child_id, grandchild_id].map(|x| (x, ALICE, BOB));
+			let mut transferred = TransferredNFTs::get();
+			transferred.sort();
+			assert_eq!(transferred, transfers.to_vec());
+
+			// Events checks.
+			for nft_id in [ALICE_NFT_ID, child_id, grandchild_id] {
+				let event = NFTsEvent::NFTTransferred { nft_id, sender: ALICE, recipient: BOB };
+				System::assert_has_event(RuntimeEvent::NFT(event));
+			}
+
+			// Bob can unnest them.
+			assert_ok!(NFT::unnest_nft(origin(BOB), grandchild_id));
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to nest an NFT in Bob's NFT.
+			let err = NFT::nest_nft(origin(ALICE), ALICE_NFT_ID, BOB_NFT_ID);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+			// Try to nest Bob's NFT in Alice's NFT.
+			let nft_id = create_alice_nft();
+			let err = NFT::nest_nft(origin(ALICE), BOB_NFT_ID, nft_id);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn nft_already_nested() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			let other_parent_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			// Try to nest the NFT in another parent.
+			let err = NFT::nest_nft(origin(ALICE), nft_id, other_parent_id);
+			// Should fail because the NFT is already nested.
+			assert_noop!(err, Error::<Test>::NFTAlreadyNested);
+		})
+	}
+
+	#[test]
+	fn cannot_nest_into_descendant() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			// Try to nest the parent in its child.
+			let err = NFT::nest_nft(origin(ALICE), ALICE_NFT_ID, nft_id);
+			assert_noop!(err, Error::<Test>::CannotNestIntoDescendant);
+			// Try to nest an NFT in itself.
+			let err = NFT::nest_nft(origin(ALICE), ALICE_NFT_ID, ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotNestIntoDescendant);
+		})
+	}
+
+	#[test]
+	fn nesting_depth_limit_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mut parent_id = ALICE_NFT_ID;
+			for _ in 0..NestingDepthLimit::get() {
+				let nft_id = create_alice_nft();
+				NFT::nest_nft(origin(ALICE), nft_id, parent_id).unwrap();
+				parent_id = nft_id;
+			}
+			// Try to nest an NFT under the deepest one.
+			let nft_id = create_alice_nft();
+			let err = NFT::nest_nft(origin(ALICE), nft_id, parent_id);
+			assert_noop!(err, Error::<Test>::NestingDepthLimitReached);
+
+			// Try to nest a tree whose height exceeds the limit.
+			let err = NFT::nest_nft(origin(ALICE), ALICE_NFT_ID, nft_id);
+			assert_noop!(err, Error::<Test>::NestingDepthLimitReached);
+		})
+	}
+
+	#[test]
+	fn too_many_nft_children() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			for _ in 0..NFTChildrenLimit::get() {
+				let nft_id = create_alice_nft();
+				NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			}
+			// Try to nest one more NFT.
+			let nft_id = create_alice_nft();
+			let err = NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID);
+			// Should fail because the parent has too many children.
+			assert_noop!(err, Error::<Test>::TooManyNFTChildren);
+		})
+	}
+
+	#[test]
+	fn cannot_nest_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Try to nest an NFT in a locked NFT.
+			let err = NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotNestLockedNFTs);
+			// Try to nest a locked NFT.
+			let err = NFT::nest_nft(origin(ALICE), ALICE_NFT_ID, nft_id);
+			assert_noop!(err, Error::<Test>::CannotTransferLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_burn_nfts_with_children() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			// Try to burn the parent.
+			let err = NFT::burn_nft(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::CannotBurnNFTsWithChildren);
+			// Try to burn the child.
+			let err = NFT::burn_nft(origin(ALICE), nft_id);
+			assert_noop!(err, Error::<Test>::CannotBurnLockedNFTs);
+		})
+	}
+}
+
+mod unnest_nft {
+	use super::*;
+
+	#[test]
+	fn unnest_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			assert_ok!(NFT::unnest_nft(origin(ALICE), nft_id));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_parents(nft_id), None);
+			assert!(NFT::nft_children(ALICE_NFT_ID).is_empty());
+			assert!(!NFT::nfts(nft_id).unwrap().state.is_locked());
+
+			// Events checks.
+			let event = NFTsEvent::NFTUnnested { nft_id, parent_id: ALICE_NFT_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+
+			// Both NFTs can be transferred separately again.
+			assert_ok!(NFT::transfer_nft(origin(ALICE), nft_id, BOB));
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().owner, ALICE);
+		})
+	}
+
+	#[test]
+	fn nft_not_nested() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to unnest an NFT without parent.
+			let err = NFT::unnest_nft(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::NFTNotNested);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			// Try to unnest Alice's NFT.
+			let err = NFT::unnest_nft(origin(BOB), nft_id);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_unnest_from_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = create_alice_nft();
+			NFT::nest_nft(origin(ALICE), nft_id, ALICE_NFT_ID).unwrap();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Try to unnest an NFT from a listed bundle.
+			let err = NFT::unnest_nft(origin(ALICE), nft_id);
+			assert_noop!(err, Error::<Test>::CannotNestLockedNFTs);
+		})
+	}
+}
+
+fn attribute(
+	key: &[u8],
+	value: &[u8],
+) -> (BoundedVec<u8, AttributeKeyLimit>, BoundedVec<u8, AttributeValueLimit>) {
+	(BoundedVec::try_from(key.to_vec()).unwrap(), BoundedVec::try_from(value.to_vec()).unwrap())
+}
+
+mod set_nft_attribute {
+	use super::*;
+
+	#[test]
+	fn set_nft_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			assert_ok!(NFT::set_nft_attribute(
+				origin(ALICE),
+				ALICE_NFT_ID,
+				namespace,
+				key.clone(),
+				value.clone()
+			));
+
+			// Final state checks.
+			let deposit = AttributeDepositBase::get() + 9 * AttributeDepositPerByte::get();
+			let attribute = NFT::nft_attributes(ALICE_NFT_ID, (namespace, key.clone()));
+			assert_eq!(attribute, Some((value.clone(), Some((ALICE, deposit)))));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert_eq!(
+				NFT::get_nft_attribute(ALICE_NFT_ID, namespace, b"name"),
+				Some(value.to_vec())
+			);
+
+			// Events checks.
+			let event = NFTsEvent::NFTAttributeSet { nft_id: ALICE_NFT_ID, namespace, key, value };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn update_nft_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key.clone(), value)
+				.unwrap();
+			let (_, value) = attribute(b"name", b"bob");
+			assert_ok!(NFT::set_nft_attribute(
+				origin(ALICE),
+				ALICE_NFT_ID,
+				namespace,
+				key.clone(),
+				value.clone()
+			));
+
+			// Final state checks.
+			let deposit = AttributeDepositBase::get() + 7 * AttributeDepositPerByte::get();
+			let attribute = NFT::nft_attributes(ALICE_NFT_ID, (namespace, key));
+			assert_eq!(attribute, Some((value, Some((ALICE, deposit)))));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+		})
+	}
+
+	#[test]
+	fn set_collection_owner_nft_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::create_nft(
+				origin(ALICE),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			NFT::transfer_nft(origin(ALICE), nft_id, BOB).unwrap();
+			let (key, value) = attribute(b"rarity", b"rare");
+			let namespace = AttributeNamespace::CollectionOwner;
+			assert_ok!(NFT::set_nft_attribute(
+				origin(ALICE),
+				nft_id,
+				namespace,
+				key.clone(),
+				value.clone()
+			));
+
+			// Final state checks.
+			let deposit = AttributeDepositBase::get() + 10 * AttributeDepositPerByte::get();
+			let attribute = NFT::nft_attributes(nft_id, (namespace, key.clone()));
+			assert_eq!(attribute, Some((value, Some((ALICE, deposit)))));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+
+			// The NFT owner cannot change it.
+			let (_, value) = attribute(b"rarity", b"common");
+			let err = NFT::set_nft_attribute(origin(BOB), nft_id, namespace, key, value);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			// Try to set an attribute of a non existing NFT.
+			let err = NFT::set_nft_attribute(origin(ALICE), INVALID_ID, namespace, key, value);
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			// Try to set an attribute of Bob's NFT.
+			let err = NFT::set_nft_attribute(origin(ALICE), BOB_NFT_ID, namespace, key, value);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_set_attributes_of_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			// Try to set an attribute of a locked NFT.
+			let err = NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key, value);
+			assert_noop!(err, Error::<Test>::CannotSetAttributesOfLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_set_pallet_attributes() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Pallet;
+			// Try to set an attribute of the pallet namespace.
+			let err = NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key, value);
+			assert_noop!(err, Error::<Test>::CannotSetPalletAttributes);
+		})
+	}
+
+	#[test]
+	fn nft_not_in_a_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"rarity", b"rare");
+			let namespace = AttributeNamespace::CollectionOwner;
+			// Try to set a collection owner attribute of an NFT without collection.
+			let err = NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key, value);
+			assert_noop!(err, Error::<Test>::NFTNotInACollection);
+		})
+	}
+
+	#[test]
+	fn collection_attributes_locked() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::create_nft(
+				origin(ALICE),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			let (key, value) = attribute(b"rarity", b"rare");
+			let namespace = AttributeNamespace::CollectionOwner;
+			// Try to set a collection owner attribute once locked.
+			let err = NFT::set_nft_attribute(origin(ALICE), nft_id, namespace, key, value);
+			assert_noop!(err, Error::<Test>::CollectionAttributesLocked);
+		})
+	}
+
+	#[test]
+	fn insufficient_balance() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			Balances::set_balance(RuntimeOrigin::root(), ALICE, 15, 0).unwrap();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			// Try to set an attribute without enough funds for the deposit.
+			let err = NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key, value);
+			assert_noop!(err, BalanceError::<Test>::InsufficientBalance);
+		})
+	}
+}
+
+mod clear_nft_attribute {
+	use super::*;
+
+	#[test]
+	fn clear_nft_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key.clone(), value)
+				.unwrap();
+			assert_ok!(NFT::clear_nft_attribute(
+				origin(ALICE),
+				ALICE_NFT_ID,
+				namespace,
+				key.clone()
+			));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_attributes(ALICE_NFT_ID, (namespace, key.clone())), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+			// Events checks.
+			let event = NFTsEvent::NFTAttributeCleared { nft_id: ALICE_NFT_ID, namespace, key };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn burn_releases_deposits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key.clone(), value)
+				.unwrap();
+			assert_ok!(NFT::burn_nft(origin(ALICE), ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_attributes(ALICE_NFT_ID, (namespace, key)), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn attribute_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, _) = attribute(b"name", b"");
+			let namespace = AttributeNamespace::Owner;
+			// Try to clear an attribute that was never set.
+			let err = NFT::clear_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key);
+			assert_noop!(err, Error::<Test>::AttributeNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"name", b"alice");
+			let namespace = AttributeNamespace::Owner;
+			NFT::set_nft_attribute(origin(ALICE), ALICE_NFT_ID, namespace, key.clone(), value)
+				.unwrap();
+			// Try to clear an attribute of Alice's NFT.
+			let err = NFT::clear_nft_attribute(origin(BOB), ALICE_NFT_ID, namespace, key);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+}
+
+mod set_collection_attribute {
+	use super::*;
+
+	#[test]
+	fn set_collection_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			let namespace = AttributeNamespace::CollectionOwner;
+			assert_ok!(NFT::set_collection_attribute(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				key.clone(),
+				value.clone()
+			));
+
+			// Final state checks.
+			let deposit = AttributeDepositBase::get() + 10 * AttributeDepositPerByte::get();
+			let attribute =
+				NFT::collection_attributes(ALICE_COLLECTION_ID, (namespace, key.clone()));
+			assert_eq!(attribute, Some((value.clone(), Some((ALICE, deposit)))));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			let attribute = NFT::get_collection_attribute(ALICE_COLLECTION_ID, namespace, b"theme");
+			assert_eq!(attribute, Some(value.to_vec()));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionAttributeSet {
+				collection_id: ALICE_COLLECTION_ID,
+				namespace,
+				key,
+				value,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			// Try to set an attribute of a non existing collection.
+			let err = NFT::set_collection_attribute(origin(ALICE), INVALID_ID, key, value);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			// Try to set an attribute of Bob's collection.
+			let err = NFT::set_collection_attribute(origin(ALICE), BOB_COLLECTION_ID, key, value);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_attributes_locked() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			let (key, value) = attribute(b"theme", b"space");
+			// Try to set an attribute once locked.
+			let err = NFT::set_collection_attribute(origin(ALICE), ALICE_COLLECTION_ID, key, value);
+			assert_noop!(err, Error::<Test>::CollectionAttributesLocked);
+		})
+	}
+}
+
+mod clear_collection_attribute {
+	use super::*;
+
+	#[test]
+	fn clear_collection_attribute() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			let namespace = AttributeNamespace::CollectionOwner;
+			NFT::set_collection_attribute(origin(ALICE), ALICE_COLLECTION_ID, key.clone(), value)
+				.unwrap();
+			assert_ok!(NFT::clear_collection_attribute(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				key.clone()
+			));
+
+			// Final state checks.
+			let attribute =
+				NFT::collection_attributes(ALICE_COLLECTION_ID, (namespace, key.clone()));
+			assert_eq!(attribute, None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+			// Events checks.
+			let event = NFTsEvent::CollectionAttributeCleared {
+				collection_id: ALICE_COLLECTION_ID,
+				namespace,
+				key,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn burn_collection_releases_deposits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			let namespace = AttributeNamespace::CollectionOwner;
+			NFT::set_collection_attribute(origin(ALICE), ALICE_COLLECTION_ID, key.clone(), value)
+				.unwrap();
+			NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			assert_ok!(NFT::burn_collection(origin(ALICE), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			let attribute = NFT::collection_attributes(ALICE_COLLECTION_ID, (namespace, key));
+			assert_eq!(attribute, None);
+			assert_eq!(NFT::locked_collection_attributes(ALICE_COLLECTION_ID), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn attribute_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, _) = attribute(b"theme", b"");
+			// Try to clear an attribute that was never set.
+			let err = NFT::clear_collection_attribute(origin(ALICE), ALICE_COLLECTION_ID, key);
+			assert_noop!(err, Error::<Test>::AttributeNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let (key, value) = attribute(b"theme", b"space");
+			NFT::set_collection_attribute(origin(ALICE), ALICE_COLLECTION_ID, key.clone(), value)
+				.unwrap();
+			// Try to clear an attribute of Alice's collection.
+			let err = NFT::clear_collection_attribute(origin(BOB), ALICE_COLLECTION_ID, key);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod lock_collection_attributes {
+	use super::*;
+
+	#[test]
+	fn lock_collection_attributes() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			assert_ok!(NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::locked_collection_attributes(ALICE_COLLECTION_ID), Some(()));
+
+			// Events checks.
+			let event =
+				NFTsEvent::CollectionAttributesLocked { collection_id: ALICE_COLLECTION_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to lock the attributes of a non existing collection.
+			let err = NFT::lock_collection_attributes(origin(ALICE), INVALID_ID);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to lock the attributes of Bob's collection.
+			let err = NFT::lock_collection_attributes(origin(ALICE), BOB_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_attributes_locked() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			// Try to lock the attributes twice.
+			let err = NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::CollectionAttributesLocked);
+		})
+	}
+}
+
+mod set_nft_offchaindata {
+	use super::*;
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+
+	#[test]
+	fn set_nft_offchaindata() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let old_data = NFT::nfts(ALICE_NFT_ID).unwrap().offchain_data;
+			let offchain_data = BoundedVec::try_from(vec![1]).unwrap();
+			assert_ok!(NFT::set_nft_offchaindata(
+				origin(ALICE),
+				ALICE_NFT_ID,
+				offchain_data.clone()
+			));
+
+			// Final state checks.
+			assert_eq!(NFT::nfts(ALICE_NFT_ID).unwrap().offchain_data, offchain_data);
+			let history = NFT::nft_metadata_history(ALICE_NFT_ID);
+			assert_eq!(history.to_vec(), vec![BlakeTwo256::hash(&old_data)]);
+
+			// Events checks.
+			let event = NFTsEvent::NFTOffchainDataSet { nft_id: ALICE_NFT_ID, offchain_data };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn history_keeps_latest_hashes() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let limit = MetadataHistoryLimit::get() as u8;
+			for i in 1..=limit + 1 {
+				let offchain_data = BoundedVec::try_from(vec![i]).unwrap();
+				NFT::set_nft_offchaindata(origin(ALICE), ALICE_NFT_ID, offchain_data).unwrap();
+			}
+
+			// The hash of the original offchain data was dropped.
+			let expected: Vec<_> = (1..=limit).map(|i| BlakeTwo256::hash(&[i])).collect();
+			assert_eq!(NFT::nft_metadata_history(ALICE_NFT_ID).to_vec(), expected);
+		})
+	}
+
+	#[test]
+	fn collection_owner_mutability() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::create_nft(
+				origin(ALICE),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			NFT::transfer_nft(origin(ALICE), nft_id, BOB).unwrap();
+			let mutability = MetadataMutability::CollectionOwner;
+			NFT::set_collection_metadata_mutability(origin(ALICE), ALICE_COLLECTION_ID, mutability)
+				.unwrap();
+			let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> =
+				BoundedVec::try_from(vec![1]).unwrap();
+
+			// The NFT owner cannot update it.
+			let err = NFT::set_nft_offchaindata(origin(BOB), nft_id, offchain_data.clone());
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+
+			assert_ok!(NFT::set_nft_offchaindata(origin(ALICE), nft_id, offchain_data.clone()));
+			assert_eq!(NFT::nfts(nft_id).unwrap().offchain_data, offchain_data);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to update the offchain data of a non existing NFT.
+			let err = NFT::set_nft_offchaindata(origin(ALICE), INVALID_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to update the offchain data of Bob's NFT.
+			let err = NFT::set_nft_offchaindata(origin(ALICE), BOB_NFT_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_creator() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID, BOB).unwrap();
+			// Try to update the offchain data of a bought NFT.
+			let err = NFT::set_nft_offchaindata(origin(BOB), ALICE_NFT_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NotTheNFTCreator);
+		})
+	}
+
+	#[test]
+	fn cannot_set_offchain_data_for_locked_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::lock_nft(ALICE_NFT_ID, TEST_LOCK).unwrap();
+			// Try to update the offchain data of a locked NFT.
+			let err = NFT::set_nft_offchaindata(origin(ALICE), ALICE_NFT_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::CannotSetOffchainDataForLockedNFTs);
+		})
+	}
+
+	#[test]
+	fn nft_metadata_frozen() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::freeze_nft_metadata(origin(ALICE), ALICE_NFT_ID).unwrap();
+			// Try to update the offchain data of a frozen NFT.
+			let err = NFT::set_nft_offchaindata(origin(ALICE), ALICE_NFT_ID, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::NFTMetadataFrozen);
+		})
+	}
+
+	#[test]
+	fn collection_metadata_frozen() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let collection_id = Some(ALICE_COLLECTION_ID);
+			NFT::create_nft(
+				origin(ALICE),
+				BoundedVec::default(),
+				Some(PERCENT_0),
+				collection_id,
+				false,
+			)
+			.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			// Try to update the offchain data of an NFT of a frozen collection.
+			let err = NFT::set_nft_offchaindata(origin(ALICE), nft_id, BoundedVec::default());
+			assert_noop!(err, Error::<Test>::CollectionMetadataFrozen);
+		})
+	}
+}
+
+mod set_collection_metadata_mutability {
+	use super::*;
+
+	#[test]
+	fn set_collection_metadata_mutability() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mutability = MetadataMutability::CollectionOwner;
+			assert_ok!(NFT::set_collection_metadata_mutability(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				mutability
+			));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_metadata_mutability(ALICE_COLLECTION_ID), mutability);
+
+			// Events checks.
+			let event = NFTsEvent::CollectionMetadataMutabilitySet {
+				collection_id: ALICE_COLLECTION_ID,
+				mutability,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mutability = MetadataMutability::CollectionOwner;
+			// Try to set the policy of a non existing collection.
+			let err =
+				NFT::set_collection_metadata_mutability(origin(ALICE), INVALID_ID, mutability);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let mutability = MetadataMutability::CollectionOwner;
+			// Try to set the policy of Bob's collection.
+			let err = NFT::set_collection_metadata_mutability(
+				origin(ALICE),
+				BOB_COLLECTION_ID,
+				mutability,
+			);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_metadata_frozen() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			let mutability = MetadataMutability::CollectionOwner;
+			// Try to set the policy of a frozen collection.
+			let err = NFT::set_collection_metadata_mutability(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				mutability,
+			);
+			assert_noop!(err, Error::<Test>::CollectionMetadataFrozen);
+		})
+	}
+}
+
+mod freeze_nft_metadata {
+	use super::*;
+
+	#[test]
+	fn freeze_nft_metadata() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			assert_ok!(NFT::freeze_nft_metadata(origin(ALICE), ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::frozen_nft_metadata(ALICE_NFT_ID), Some(()));
+
+			// Events checks.
+			let event = NFTsEvent::NFTMetadataFrozen { nft_id: ALICE_NFT_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn burn_clears_metadata_state() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_nft_offchaindata(origin(ALICE), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			NFT::freeze_nft_metadata(origin(ALICE), ALICE_NFT_ID).unwrap();
+			assert_ok!(NFT::burn_nft(origin(ALICE), ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::frozen_nft_metadata(ALICE_NFT_ID), None);
+			assert!(NFT::nft_metadata_history(ALICE_NFT_ID).is_empty());
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to freeze a non existing NFT.
+			let err = NFT::freeze_nft_metadata(origin(ALICE), INVALID_ID);
+			assert_noop!(err, Error::<Test>::NFTNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to freeze Bob's NFT.
+			let err = NFT::freeze_nft_metadata(origin(ALICE), BOB_NFT_ID);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn nft_metadata_frozen() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::freeze_nft_metadata(origin(ALICE), ALICE_NFT_ID).unwrap();
+			// Try to freeze an NFT twice.
+			let err = NFT::freeze_nft_metadata(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::NFTMetadataFrozen);
+		})
+	}
+}
+
+mod freeze_collection_metadata {
+	use super::*;
+
+	#[test]
+	fn freeze_collection_metadata() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			assert_ok!(NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::frozen_collection_metadata(ALICE_COLLECTION_ID), Some(()));
+			let err = NFT::set_collection_offchaindata(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				BoundedVec::default(),
+			);
+			assert_noop!(err, Error::<Test>::CollectionMetadataFrozen);
+
+			// Events checks.
+			let event = NFTsEvent::CollectionMetadataFrozen { collection_id: ALICE_COLLECTION_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to freeze a non existing collection.
+			let err = NFT::freeze_collection_metadata(origin(ALICE), INVALID_ID);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to freeze Bob's collection.
+			let err = NFT::freeze_collection_metadata(origin(ALICE), BOB_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_metadata_frozen() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			// Try to freeze a collection twice.
+			let err = NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::CollectionMetadataFrozen);
+		})
+	}
+}
+
+mod storage_deposit {
+	use super::*;
+
+	fn offchain_data(len: usize) -> BoundedVec<u8, NFTOffchainDataLimit> {
+		BoundedVec::try_from(vec![1; len]).unwrap()
+	}
+
+	#[test]
+	fn create_nft_reserves_deposit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			let alice_balance = Balances::free_balance(ALICE);
+			let data = offchain_data(5);
+			assert_ok!(NFT::create_nft(origin(ALICE), data.clone(), Some(PERCENT_0), None, false));
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			// No mint fee is charged.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - deposit);
+
+			// Events checks.
+			let event = NFTsEvent::NFTCreated {
+				nft_id,
+				owner: ALICE,
+				offchain_data: data,
+				royalty: PERCENT_0,
+				collection_id: None,
+				is_soulbound: false,
+				mint_fee: 0,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn create_nfts_batch_reserves_deposits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			NFT::create_collection(origin(ALICE), BoundedVec::default(), None).unwrap();
+			let collection_id = NFT::next_collection_id() - 1;
+			let first_nft_id = NFT::next_nft_id();
+			let nfts =
+				BoundedVec::try_from(vec![(offchain_data(2), Some(PERCENT_0), false); 3]).unwrap();
+			assert_ok!(NFT::create_nfts_batch(
+				origin(ALICE),
+				collection_id,
+				nfts,
+				BatchMode::AllOrNothing
+			));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 2 * NFTDepositPerByte::get();
+			for nft_id in first_nft_id..first_nft_id + 3 {
+				assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+			}
+			assert_eq!(Balances::reserved_balance(ALICE), 3 * deposit);
+		})
+	}
+
+	#[test]
+	fn create_nfts_batch_skips_unpaid_deposits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			NFT::create_collection(origin(BOB), BoundedVec::default(), None).unwrap();
+			let collection_id = NFT::next_collection_id() - 1;
+			let deposit = NFTDepositBase::get() + 2 * NFTDepositPerByte::get();
+			// Bob can only pay the deposit of one NFT.
+			Balances::reserve(&BOB, Balances::free_balance(BOB) - deposit - 1).unwrap();
+			let first_nft_id = NFT::next_nft_id();
+			let nfts =
+				BoundedVec::try_from(vec![(offchain_data(2), Some(PERCENT_0), false); 2]).unwrap();
+			let ok =
+				NFT::create_nfts_batch(origin(BOB), collection_id, nfts, BatchMode::BestEffort);
+			assert_ok!(ok);
+
+			// Final state checks.
+			assert_eq!(NFT::collections(collection_id).unwrap().nfts_count, 1);
+			assert_eq!(NFT::nft_deposits(first_nft_id), Some((BOB, deposit)));
+			assert_eq!(NFT::next_nft_id(), first_nft_id + 1);
+
+			// Events checks.
+			let error = BalanceError::<Test>::InsufficientBalance.into();
+			let event = NFTsEvent::NFTBatchItemFailed { index: 1, error };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn burn_nft_releases_deposit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			let alice_balance = Balances::free_balance(ALICE);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_ok!(NFT::burn_nft(origin(ALICE), nft_id));
+
+			// Final state checks.
+			assert_eq!(NFT::nft_deposits(nft_id), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+		})
+	}
+
+	#[test]
+	fn deposit_stays_with_minter() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_ok!(NFT::transfer_nft(origin(ALICE), nft_id, BOB));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+
+			// The minter gets the deposit back when the owner burns the NFT.
+			assert_ok!(NFT::burn_nft(origin(BOB), nft_id));
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn deposit_follows_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::DepositFollowingOwner);
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_ok!(NFT::transfer_nft(origin(ALICE), nft_id, BOB));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((BOB, deposit)));
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::reserved_balance(BOB), deposit);
+
+			// The new owner pays the deposit and the previous owner gets it back.
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - deposit);
+
+			// The owner gets the deposit back when burning the NFT.
+			assert_ok!(NFT::burn_nft(origin(BOB), nft_id));
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+		})
+	}
+
+	#[test]
+	fn deposit_stays_if_new_owner_cannot_pay() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::DepositFollowingOwner);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			// Charlie has no funds to reserve the deposit.
+			assert_ok!(NFT::transfer_nft(origin(ALICE), nft_id, CHARLIE));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nfts(nft_id).unwrap().owner, CHARLIE);
+			assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+
+			// Events checks.
+			let event = NFTsEvent::NFTDepositNotMoved { nft_id, depositor: ALICE, owner: CHARLIE };
+			System::assert_has_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn kept_deposit_moves_to_next_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::DepositFollowingOwner);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			NFT::transfer_nft(origin(ALICE), nft_id, CHARLIE).unwrap();
+			assert_ok!(NFT::transfer_nft(origin(CHARLIE), nft_id, BOB));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((BOB, deposit)));
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::reserved_balance(BOB), deposit);
+		})
+	}
+
+	#[test]
+	fn add_secret_updates_deposit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			assert_ok!(NFT::add_secret(origin(ALICE), nft_id, offchain_data(3)));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get() + 8 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+			// The secret NFT mint fee stays reserved until the secret is synced.
+			assert_eq!(Balances::reserved_balance(ALICE), deposit + SECRET_NFT_MINT_FEE);
+		})
+	}
+
+	#[test]
+	fn set_nft_offchaindata_charges_the_depositor() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			NFT::create_collection(origin(ALICE), BoundedVec::default(), None).unwrap();
+			let collection_id = NFT::next_collection_id() - 1;
+			let mutability = MetadataMutability::CollectionOwner;
+			NFT::set_collection_metadata_mutability(origin(ALICE), collection_id, mutability)
+				.unwrap();
+			NFT::add_minter(origin(ALICE), collection_id, BOB).unwrap();
+			let data = offchain_data(5);
+			NFT::create_nft(origin(BOB), data, Some(PERCENT_0), Some(collection_id), false)
+				.unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// The collection owner grows the offchain data, Bob pays the difference.
+			assert_ok!(NFT::set_nft_offchaindata(origin(ALICE), nft_id, offchain_data(8)));
+			let deposit = NFTDepositBase::get() + 8 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((BOB, deposit)));
+			assert_eq!(Balances::reserved_balance(BOB), deposit);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+			// The collection owner shrinks the offchain data, Bob gets the difference back.
+			assert_ok!(NFT::set_nft_offchaindata(origin(ALICE), nft_id, offchain_data(2)));
+			let deposit = NFTDepositBase::get() + 2 * NFTDepositPerByte::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((BOB, deposit)));
+			assert_eq!(Balances::reserved_balance(BOB), deposit);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn fee_mode_does_not_reserve() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+
+			// Final state checks.
+			assert_eq!(NFT::nft_deposits(nft_id), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn insufficient_balance() {
+		ExtBuilder::new_build(vec![(ALICE, 10), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			// Try to mint without enough funds for the deposit.
+			let err =
+				NFT::create_nft(origin(ALICE), offchain_data(5), Some(PERCENT_0), None, false);
+			assert_noop!(err, BalanceError::<Test>::InsufficientBalance);
+		})
+	}
+}
+
+mod retry_secret_sync {
+	use super::*;
+
+	#[test]
+	fn retry_secret_sync() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+				NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+				run_to_block(1 + ShardSyncTimeout::get());
+
+				// Retry the sync on the second cluster.
+				assert_ok!(NFT::retry_secret_sync(alice, ALICE_NFT_ID, 1));
+
+				// Final state checks.
+				let now = System::block_number();
+				assert_eq!(NFT::secret_syncs(ALICE_NFT_ID), Some((now, Some(1))));
+				assert_eq!(NFT::secret_nfts_shards_count(ALICE_NFT_ID), None);
+				assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_secret);
+
+				// Events checks.
+				let event = NFTsEvent::SecretSyncRetried { nft_id: ALICE_NFT_ID, cluster_id: 1 };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+
+				// Only the enclaves of the new cluster can send shards.
+				let err = NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::ShareNotFromValidCluster);
+				assert_ok!(NFT::add_secret_shard(origin(CHARLIE_ENCLAVE), ALICE_NFT_ID));
+			},
+		)
+	}
+
+	#[test]
+	fn sync_timeout_not_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(ShardSyncTimeout::get());
+
+			let err = NFT::retry_secret_sync(alice, ALICE_NFT_ID, 1);
+			assert_noop!(err, Error::<Test>::SyncTimeoutNotReached);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			NFT::add_secret(origin(ALICE), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			let err = NFT::retry_secret_sync(origin(BOB), ALICE_NFT_ID, 1);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn nft_already_synced() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+			NFT::add_secret_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			let err = NFT::retry_secret_sync(alice, ALICE_NFT_ID, 1);
+			assert_noop!(err, Error::<Test>::NFTAlreadySynced);
+		})
+	}
+
+	#[test]
+	fn cluster_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			let err = NFT::retry_secret_sync(alice, ALICE_NFT_ID, INVALID_ID);
+			assert_noop!(err, Error::<Test>::ClusterNotFound);
+		})
+	}
+}
+
+mod cancel_secret_sync {
+	use super::*;
+	use crate::PendingSecretFees;
+
+	#[test]
+	fn cancel_secret_sync() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			assert_eq!(Balances::reserved_balance(ALICE), SECRET_NFT_MINT_FEE);
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			assert_ok!(NFT::cancel_secret_sync(alice, ALICE_NFT_ID));
+
+			// Final state checks.
+			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.state.is_secret, false);
+			assert_eq!(nft.state.is_syncing_secret, false);
+			assert_eq!(NFT::secret_nfts_offchain_data(ALICE_NFT_ID), None);
+			assert_eq!(NFT::secret_syncs(ALICE_NFT_ID), None);
+			assert_eq!(NFT::pending_secret_fees(ALICE_NFT_ID), None);
+			// The secret NFT mint fee is refunded.
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+
+			// Events checks.
+			let event = NFTsEvent::SecretSyncCancelled {
+				nft_id: ALICE_NFT_ID,
+				refund: SECRET_NFT_MINT_FEE,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn sync_timeout_not_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+			let err = NFT::cancel_secret_sync(alice, ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::SyncTimeoutNotReached);
+		})
+	}
+
+	#[test]
+	fn nft_is_not_secret() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let err = NFT::cancel_secret_sync(origin(ALICE), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::NFTIsNotSecret);
+		})
+	}
+
+	#[test]
+	fn secret_added_before_pending_fees() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			// Secrets added before the fee was reserved have already paid it.
+			PendingSecretFees::<Test>::remove(ALICE_NFT_ID);
+			Balances::unreserve(&ALICE, SECRET_NFT_MINT_FEE);
+			let alice_balance = Balances::free_balance(ALICE);
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			assert_ok!(NFT::cancel_secret_sync(alice, ALICE_NFT_ID));
+
+			// Final state checks.
+			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.state.is_secret, false);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+
+			// Events checks.
+			let event = NFTsEvent::SecretSyncCancelled { nft_id: ALICE_NFT_ID, refund: 0 };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn burn_collects_the_secret_fee() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			let collector_balance = Balances::free_balance(COLLECTOR);
+
+			assert_ok!(NFT::burn_nft(alice, ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::secret_syncs(ALICE_NFT_ID), None);
+			assert_eq!(NFT::pending_secret_fees(ALICE_NFT_ID), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(COLLECTOR), collector_balance + SECRET_NFT_MINT_FEE);
+		})
+	}
+}
+
+mod retry_capsule_sync {
+	use super::*;
+
+	#[test]
+	fn retry_capsule_sync() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default())
+					.unwrap();
+				NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+				run_to_block(1 + ShardSyncTimeout::get());
+
+				// Retry the sync on the second cluster.
+				assert_ok!(NFT::retry_capsule_sync(alice, ALICE_NFT_ID, 1));
+
+				// Final state checks.
+				let now = System::block_number();
+				assert_eq!(NFT::capsule_syncs(ALICE_NFT_ID), Some((now, Some(1))));
+				assert_eq!(NFT::capsules_shards_count(ALICE_NFT_ID), None);
+				assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_capsule);
+
+				// Events checks.
+				let event = NFTsEvent::CapsuleSyncRetried { nft_id: ALICE_NFT_ID, cluster_id: 1 };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+
+				// Only the enclaves of the new cluster can send shards.
+				let err = NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::ShareNotFromValidCluster);
+				assert_ok!(NFT::add_capsule_shard(origin(CHARLIE_ENCLAVE), ALICE_NFT_ID));
+			},
+		)
+	}
+
+	#[test]
+	fn sync_timeout_not_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+			let err = NFT::retry_capsule_sync(alice, ALICE_NFT_ID, 1);
+			assert_noop!(err, Error::<Test>::SyncTimeoutNotReached);
+		})
+	}
+
+	#[test]
+	fn key_update_restarts_the_timeout() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+			NFT::add_capsule_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+			NFT::notify_enclave_key_update(alice.clone(), ALICE_NFT_ID).unwrap();
+
+			let err = NFT::retry_capsule_sync(alice, ALICE_NFT_ID, 1);
+			assert_noop!(err, Error::<Test>::SyncTimeoutNotReached);
+		})
+	}
+
+	#[test]
+	fn cluster_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			prepare_tee_for_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			let err = NFT::retry_capsule_sync(alice, ALICE_NFT_ID, INVALID_ID);
+			assert_noop!(err, Error::<Test>::ClusterNotFound);
+		})
+	}
+}
+
+mod cancel_capsule_sync {
+	use super::*;
+
+	#[test]
+	fn cancel_capsule_sync() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			CapsuleRefundRatio::set(Permill::from_percent(50));
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let alice_balance = Balances::free_balance(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			assert_ok!(NFT::cancel_capsule_sync(alice, ALICE_NFT_ID));
+
+			// Final state checks.
+			let refund = CAPSULE_MINT_FEE / 2;
+			let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.state.is_capsule, false);
+			assert_eq!(nft.state.is_syncing_capsule, false);
+			assert_eq!(NFT::capsule_offchain_data(ALICE_NFT_ID), None);
+			assert_eq!(NFT::capsule_syncs(ALICE_NFT_ID), None);
+			assert_eq!(NFT::capsule_deposits(ALICE_NFT_ID), None);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance - CAPSULE_MINT_FEE + refund);
+
+			// Events checks.
+			let event = NFTsEvent::CapsuleSyncCancelled { nft_id: ALICE_NFT_ID, refund };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn sync_timeout_not_reached() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::convert_to_capsule(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+			let err = NFT::cancel_capsule_sync(alice, ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::SyncTimeoutNotReached);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::convert_to_capsule(origin(ALICE), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+			run_to_block(1 + ShardSyncTimeout::get());
+
+			let err = NFT::cancel_capsule_sync(origin(BOB), ALICE_NFT_ID);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+}
+
+mod resharding {
+	use super::*;
+
+	/// Sync the secret and the capsule of Alice's NFT on the first cluster.
+	fn prepare_synced_nft() {
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+		for enclave in [ALICE_ENCLAVE, BOB_ENCLAVE] {
+			NFT::add_secret_shard(origin(enclave), ALICE_NFT_ID).unwrap();
+		}
+		NFT::convert_to_capsule(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+		for enclave in [ALICE_ENCLAVE, BOB_ENCLAVE] {
+			NFT::add_capsule_shard(origin(enclave), ALICE_NFT_ID).unwrap();
+		}
+	}
+
+	/// Register Bob's enclave again in the first cluster.
+	fn reassign_bob_enclave() {
+		TEE::register_enclave(origin(BOB), BOB_ENCLAVE, BoundedVec::default()).unwrap();
+		TEE::assign_enclave(root(), BOB, 0).unwrap();
+	}
+
+	#[test]
+	fn synced_shard_holders_are_stored() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+
+				// Final state checks.
+				let holders = vec![(0, ALICE), (0, BOB)];
+				assert_eq!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::capsule_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::shard_holder_nfts(ALICE, ALICE_NFT_ID), Some(()));
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), Some(()));
+			},
+		)
+	}
+
+	#[test]
+	fn remove_enclave_requests_resharding() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+
+				assert_ok!(TEE::remove_enclave(root(), BOB));
+
+				// Final state checks.
+				let holders = vec![(0, ALICE)];
+				assert_eq!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::capsule_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), None);
+				assert_eq!(NFT::secret_nfts_resharding(ALICE_NFT_ID), Some(()));
+				assert_eq!(NFT::capsules_resharding(ALICE_NFT_ID), Some(()));
+
+				// Events checks.
+				let event = RuntimeEvent::NFT(NFTsEvent::SecretNFTReshardingRequested {
+					nft_id: ALICE_NFT_ID,
+					cluster_id: 0,
+				});
+				System::assert_has_event(event);
+				let event = RuntimeEvent::NFT(NFTsEvent::CapsuleReshardingRequested {
+					nft_id: ALICE_NFT_ID,
+					cluster_id: 0,
+				});
+				System::assert_has_event(event);
+			},
+		)
+	}
+
+	#[test]
+	fn resharding_does_not_block_the_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+
+				assert_ok!(NFT::transfer_nft(origin(ALICE), ALICE_NFT_ID, BOB));
+			},
+		)
+	}
+
+	#[test]
+	fn reshard_secret() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+				reassign_bob_enclave();
+
+				assert_ok!(NFT::add_secret_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID));
+
+				// Final state checks.
+				let holders = vec![(0, ALICE), (0, BOB)];
+				assert_eq!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::secret_nfts_resharding(ALICE_NFT_ID), None);
+				assert_eq!(NFT::capsules_resharding(ALICE_NFT_ID), Some(()));
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), Some(()));
+
+				// Events checks.
+				let event = NFTsEvent::SecretNFTResharded { nft_id: ALICE_NFT_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			},
+		)
+	}
+
+	#[test]
+	fn reshard_capsule() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+				reassign_bob_enclave();
+
+				assert_ok!(NFT::add_capsule_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID));
+
+				// Final state checks.
+				let holders = vec![(0, ALICE), (0, BOB)];
+				assert_eq!(NFT::capsule_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::capsules_resharding(ALICE_NFT_ID), None);
+
+				// Events checks.
+				let event = NFTsEvent::CapsuleResharded { nft_id: ALICE_NFT_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			},
+		)
+	}
+
+	#[test]
+	fn share_not_from_valid_cluster() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+
+				let err = NFT::add_secret_shard(origin(CHARLIE_ENCLAVE), ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::ShareNotFromValidCluster);
+			},
+		)
+	}
+
+	#[test]
+	fn enclave_already_added_shard() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+
+				let err = NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::EnclaveAlreadyAddedShard);
+			},
+		)
+	}
+
+	#[test]
+	fn burn_nft_removes_shard_holders() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				TEE::remove_enclave(root(), BOB).unwrap();
+
+				assert_ok!(NFT::burn_nft(origin(ALICE), ALICE_NFT_ID));
+
+				// Final state checks.
+				assert!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).is_empty());
+				assert!(NFT::capsule_shard_holders(ALICE_NFT_ID).is_empty());
+				assert_eq!(NFT::shard_holder_nfts(ALICE, ALICE_NFT_ID), None);
+				assert_eq!(NFT::secret_nfts_resharding(ALICE_NFT_ID), None);
+				assert_eq!(NFT::capsules_resharding(ALICE_NFT_ID), None);
+			},
+		)
+	}
+
+	#[test]
+	fn remove_enclave_during_capsule_key_update() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_nft();
+				NFT::notify_enclave_key_update(origin(ALICE), ALICE_NFT_ID).unwrap();
+				NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+
+				// The old capsule shards are forgotten, only the secret needs resharding.
+				assert_ok!(TEE::remove_enclave(root(), BOB));
+				assert!(NFT::capsule_shard_holders(ALICE_NFT_ID).is_empty());
+				assert_eq!(NFT::capsules_resharding(ALICE_NFT_ID), None);
+				assert_eq!(NFT::secret_nfts_resharding(ALICE_NFT_ID), Some(()));
+
+				reassign_bob_enclave();
+				assert_ok!(NFT::add_capsule_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID));
+
+				// Final state checks.
+				let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+				assert_eq!(nft.state.is_syncing_capsule, false);
+				assert_eq!(NFT::capsule_syncs(ALICE_NFT_ID), None);
+				let holders = vec![(0, ALICE), (0, BOB)];
+				assert_eq!(NFT::capsule_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+
+				// Events checks.
+				let event = NFTsEvent::CapsuleSynced { nft_id: ALICE_NFT_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			},
+		)
+	}
+}
+
+mod collection_nfts_migration {
+	use super::*;
+	use crate::{CollectionNfts, CollectionNftsToMigrate};
+	use frame_support::{traits::Hooks, weights::Weight};
+
+	#[test]
+	fn on_idle_moves_pending_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			NFT::add_nft_to_collection(alice.clone(), ALICE_NFT_ID, ALICE_COLLECTION_ID).unwrap();
+
+			// Simulate a collection whose list was not migrated yet, with a stale NFT id.
+			CollectionNfts::<Test>::remove(ALICE_COLLECTION_ID, ALICE_NFT_ID);
+			let pending = BoundedVec::try_from(vec![ALICE_NFT_ID, BOB_NFT_ID]).unwrap();
+			CollectionNftsToMigrate::<Test>::insert(ALICE_COLLECTION_ID, pending);
+			assert_eq!(NFT::collection_nfts(ALICE_COLLECTION_ID), Some(vec![ALICE_NFT_ID]));
+
+			NFT::on_idle(1, Weight::from_ref_time(u64::MAX));
+
+			assert_eq!(NFT::collection_nfts_to_migrate(ALICE_COLLECTION_ID), None);
+			assert_eq!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, ALICE_NFT_ID), Some(()));
+			assert_eq!(NFT::collection_nfts_of(ALICE_COLLECTION_ID, BOB_NFT_ID), None);
+			assert_eq!(NFT::collection_nfts(ALICE_COLLECTION_ID), Some(vec![ALICE_NFT_ID]));
+		})
+	}
+}
+
+mod transfer_collection {
+	use super::*;
+
+	#[test]
+	fn transfer_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			assert_ok!(NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_transfers(ALICE_COLLECTION_ID), Some(BOB));
+			assert_eq!(NFT::collections(ALICE_COLLECTION_ID).unwrap().owner, ALICE);
+
+			// Events checks.
+			let event = NFTsEvent::CollectionTransferRequested {
+				collection_id: ALICE_COLLECTION_ID,
+				owner: ALICE,
+				recipient: BOB,
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to transfer a non existing collection.
+			let err = NFT::transfer_collection(origin(ALICE), INVALID_ID, BOB);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, Some(BOB), Some(BOB))
+				.unwrap();
+			// Try to transfer Alice's collection as its admin.
+			let err = NFT::transfer_collection(origin(BOB), ALICE_COLLECTION_ID, BOB);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_transfer_collection_to_yourself() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to transfer a collection to its owner.
+			let err = NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, ALICE);
+			assert_noop!(err, Error::<Test>::CannotTransferCollectionToYourself);
+		})
+	}
+}
+
+mod accept_collection_transfer {
+	use super::*;
+	use frame_support::{traits::Hooks, weights::Weight};
+
+	#[test]
+	fn accept_collection_transfer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, Some(CHARLIE), None)
+				.unwrap();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::collections(ALICE_COLLECTION_ID).unwrap().owner, BOB);
+			assert_eq!(NFT::collection_transfers(ALICE_COLLECTION_ID), None);
+			assert_eq!(NFT::collection_admins(ALICE_COLLECTION_ID), None);
+			assert_ok!(NFT::close_collection(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionTransferred {
+				collection_id: ALICE_COLLECTION_ID,
+				from: ALICE,
+				to: BOB,
+			};
+			System::assert_has_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn minters_are_reset() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				NFT::add_minter(origin(ALICE), ALICE_COLLECTION_ID, ALICE).unwrap();
+				NFT::add_minter(origin(ALICE), ALICE_COLLECTION_ID, CHARLIE).unwrap();
+				NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+				assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+				// Final state checks.
+				assert!(NFT::collection_minters(ALICE_COLLECTION_ID).is_empty());
+				for account in [ALICE, CHARLIE] {
+					let err = NFT::create_nft(
+						origin(account),
+						BoundedVec::default(),
+						Some(PERCENT_0),
+						Some(ALICE_COLLECTION_ID),
+						false,
+					);
+					assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+				}
+			},
+		)
+	}
+
+	#[test]
+	fn mint_payout_is_reset() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let mut config = public_mint::mint_config(50);
+				config.payout = Some(ALICE);
+				NFT::set_mint_config(origin(ALICE), ALICE_COLLECTION_ID, Some(config)).unwrap();
+				NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+				assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+				// Final state checks.
+				let config = NFT::collection_mint_configs(ALICE_COLLECTION_ID).unwrap();
+				assert_eq!(config.payout, None);
+				assert_eq!(config.price, 50);
+				let alice_balance = Balances::free_balance(ALICE);
+				let bob_balance = Balances::free_balance(BOB);
+				NFT::public_mint(origin(CHARLIE), ALICE_COLLECTION_ID, BoundedVec::default())
+					.unwrap();
+				assert_eq!(Balances::free_balance(ALICE), alice_balance);
+				assert_eq!(Balances::free_balance(BOB), bob_balance + 50);
+			},
+		)
+	}
+
+	#[test]
+	fn royalty_splits_are_reset() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let splits = BoundedVec::try_from(vec![(ALICE, PERCENT_100)]).unwrap();
+			NFT::set_collection_royalty_splits(origin(ALICE), ALICE_COLLECTION_ID, Some(splits))
+				.unwrap();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_royalty_splits(ALICE_COLLECTION_ID), None);
+		})
+	}
+
+	#[test]
+	fn minter_settings_are_reset() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let attribution = MinterAttribution::CollectionOwner;
+			NFT::set_minter_attribution(origin(ALICE), ALICE_COLLECTION_ID, attribution).unwrap();
+			let mutability = MetadataMutability::CollectionOwner;
+			NFT::set_collection_metadata_mutability(origin(ALICE), ALICE_COLLECTION_ID, mutability)
+				.unwrap();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			let attribution = NFT::minter_attributions(ALICE_COLLECTION_ID);
+			assert_eq!(attribution, MinterAttribution::Minter);
+			let mutability = NFT::collection_metadata_mutability(ALICE_COLLECTION_ID);
+			assert_eq!(mutability, MetadataMutability::CreatorOwner);
+		})
+	}
+
+	#[test]
+	fn mint_allowlists_are_reset() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				let mut config = public_mint::mint_config(0);
+				config.allowlist_until = Some(5);
+				NFT::set_mint_config(origin(ALICE), ALICE_COLLECTION_ID, Some(config)).unwrap();
+				let accounts = BoundedVec::try_from(vec![CHARLIE]).unwrap();
+				NFT::set_mint_allowlist(origin(ALICE), ALICE_COLLECTION_ID, accounts, true)
+					.unwrap();
+				NFT::set_allowlist_root(origin(ALICE), ALICE_COLLECTION_ID, Some([1; 32])).unwrap();
+				NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+				assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+				// The allowlist of the previous owner is ignored until it is cleared.
+				assert_eq!(NFT::collections(ALICE_COLLECTION_ID).unwrap().allowlist_root, None);
+				assert_eq!(NFT::mint_allowlists_to_clear(ALICE_COLLECTION_ID), Some(()));
+				let err =
+					NFT::public_mint(origin(CHARLIE), ALICE_COLLECTION_ID, BoundedVec::default());
+				assert_noop!(err, Error::<Test>::NotInMintAllowlist);
+				let accounts = BoundedVec::try_from(vec![CHARLIE]).unwrap();
+				let err = NFT::set_mint_allowlist(origin(BOB), ALICE_COLLECTION_ID, accounts, true);
+				assert_noop!(err, Error::<Test>::MintAllowlistBeingCleared);
+
+				NFT::on_idle(1, Weight::from_ref_time(u64::MAX));
+
+				// Final state checks.
+				assert_eq!(NFT::mint_allowlists_to_clear(ALICE_COLLECTION_ID), None);
+				assert_eq!(NFT::mint_allowlist(ALICE_COLLECTION_ID, CHARLIE), None);
+				let accounts = BoundedVec::try_from(vec![CHARLIE]).unwrap();
+				assert_ok!(NFT::set_mint_allowlist(
+					origin(BOB),
+					ALICE_COLLECTION_ID,
+					accounts,
+					true
+				));
+			},
+		)
+	}
+
+	#[test]
+	fn irreversible_settings_are_kept() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::lock_collection_attributes(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			NFT::freeze_collection_metadata(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			assert_ok!(NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::locked_collection_attributes(ALICE_COLLECTION_ID), Some(()));
+			assert_eq!(NFT::frozen_collection_metadata(ALICE_COLLECTION_ID), Some(()));
+		})
+	}
+
+	#[test]
+	fn collection_transfer_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to accept a collection that was not offered.
+			let err = NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::CollectionTransferNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_transfer_recipient() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			// Try to accept a collection offered to another account.
+			let err = NFT::accept_collection_transfer(origin(CHARLIE), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionTransferRecipient);
+		})
+	}
+}
+
+mod cancel_collection_transfer {
+	use super::*;
+
+	#[test]
+	fn cancel_collection_transfer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			assert_ok!(NFT::cancel_collection_transfer(origin(ALICE), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_transfers(ALICE_COLLECTION_ID), None);
+			let err = NFT::accept_collection_transfer(origin(BOB), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::CollectionTransferNotFound);
+
+			// Events checks.
+			let event =
+				NFTsEvent::CollectionTransferCancelled { collection_id: ALICE_COLLECTION_ID };
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::transfer_collection(origin(ALICE), ALICE_COLLECTION_ID, BOB).unwrap();
+			// Try to cancel the transfer as its recipient.
+			let err = NFT::cancel_collection_transfer(origin(BOB), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_transfer_not_found() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to cancel a transfer that does not exist.
+			let err = NFT::cancel_collection_transfer(origin(ALICE), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::CollectionTransferNotFound);
+		})
+	}
+}
+
+mod set_collection_roles {
+	use super::*;
+
+	#[test]
+	fn set_collection_roles() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			assert_ok!(NFT::set_collection_roles(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				Some(BOB),
+				Some(CHARLIE)
+			));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_admins(ALICE_COLLECTION_ID), Some(BOB));
+			assert_eq!(NFT::collection_freezers(ALICE_COLLECTION_ID), Some(CHARLIE));
+
+			// Events checks.
+			let event = NFTsEvent::CollectionRolesSet {
+				collection_id: ALICE_COLLECTION_ID,
+				admin: Some(BOB),
+				freezer: Some(CHARLIE),
+			};
+			System::assert_last_event(RuntimeEvent::NFT(event));
+		})
+	}
+
+	#[test]
+	fn clear_collection_roles() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, Some(BOB), Some(BOB))
+				.unwrap();
+			assert_ok!(NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, None, None));
+
+			// Final state checks.
+			assert_eq!(NFT::collection_admins(ALICE_COLLECTION_ID), None);
+			assert_eq!(NFT::collection_freezers(ALICE_COLLECTION_ID), None);
+			let err = NFT::close_collection(origin(BOB), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn admin_can_manage_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, Some(BOB), None).unwrap();
+			let offchain_data = BoundedVec::try_from(vec![1]).unwrap();
+
+			assert_ok!(NFT::set_collection_offchaindata(
+				origin(BOB),
+				ALICE_COLLECTION_ID,
+				offchain_data.clone()
+			));
+			assert_ok!(NFT::limit_collection(origin(BOB), ALICE_COLLECTION_ID, 5));
+			let err = NFT::freeze_collection_metadata(origin(BOB), ALICE_COLLECTION_ID);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+			let err = NFT::set_collection_roles(origin(BOB), ALICE_COLLECTION_ID, None, None);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+			assert_ok!(NFT::close_collection(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.offchain_data, offchain_data);
+			assert_eq!(collection.limit, Some(5));
+			assert!(collection.is_closed);
+		})
+	}
+
+	#[test]
+	fn freezer_can_freeze_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::set_collection_roles(origin(ALICE), ALICE_COLLECTION_ID, None, Some(BOB)).unwrap();
+
+			let err = NFT::set_collection_offchaindata(
+				origin(BOB),
+				ALICE_COLLECTION_ID,
+				BoundedVec::default(),
+			);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+			let err = NFT::limit_collection(origin(BOB), ALICE_COLLECTION_ID, 5);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+			assert_ok!(NFT::freeze_collection_metadata(origin(BOB), ALICE_COLLECTION_ID));
+			assert_ok!(NFT::close_collection(origin(BOB), ALICE_COLLECTION_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::frozen_collection_metadata(ALICE_COLLECTION_ID), Some(()));
+			assert!(NFT::collections(ALICE_COLLECTION_ID).unwrap().is_closed);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			// Try to set the roles of Bob's collection.
+			let err = NFT::set_collection_roles(origin(ALICE), BOB_COLLECTION_ID, None, None);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+}
+
+mod rotate_secret {
+	use super::*;
+
+	/// Add a secret to Alice's NFT and sync it on the first cluster.
+	fn prepare_synced_secret() {
+		NFT::add_secret(origin(ALICE), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+		for enclave in [ALICE_ENCLAVE, BOB_ENCLAVE] {
+			NFT::add_secret_shard(origin(enclave), ALICE_NFT_ID).unwrap();
+		}
+	}
+
+	#[test]
+	fn rotate_secret() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> =
+					BoundedVec::try_from(vec![1]).unwrap();
+				let alice_balance = Balances::free_balance(ALICE);
+
+				assert_ok!(NFT::rotate_secret(
+					origin(ALICE),
+					ALICE_NFT_ID,
+					Some(offchain_data.clone())
+				));
+
+				// Final state checks.
+				let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+				assert_eq!(nft.state.is_secret, true);
+				assert_eq!(nft.state.is_syncing_secret, true);
+				assert!(NFT::secret_syncs(ALICE_NFT_ID).is_some());
+				assert_eq!(
+					NFT::secret_nfts_offchain_data(ALICE_NFT_ID),
+					Some(offchain_data.clone())
+				);
+				assert!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).is_empty());
+				assert_eq!(NFT::shard_holder_nfts(ALICE, ALICE_NFT_ID), None);
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), None);
+				// No secret NFT mint fee is charged for a rotation.
+				assert_eq!(NFT::pending_secret_fees(ALICE_NFT_ID), None);
+				assert_eq!(Balances::free_balance(ALICE), alice_balance);
+
+				// Events checks.
+				let event = NFTsEvent::SecretRotated {
+					nft_id: ALICE_NFT_ID,
+					offchain_data: Some(offchain_data),
+				};
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			},
+		)
+	}
+
+	#[test]
+	fn rotate_secret_keeps_offchain_data() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+
+				assert_ok!(NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None));
+
+				// Final state checks.
+				assert_eq!(
+					NFT::secret_nfts_offchain_data(ALICE_NFT_ID),
+					Some(BoundedVec::default())
+				);
+
+				// Events checks.
+				let event = NFTsEvent::SecretRotated { nft_id: ALICE_NFT_ID, offchain_data: None };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			},
+		)
+	}
+
+	#[test]
+	fn rotated_secret_is_synced_again() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None).unwrap();
+
+				// The new shards must all come from the same cluster.
+				assert_ok!(NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID));
+				let err = NFT::add_secret_shard(origin(CHARLIE_ENCLAVE), ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::ShareNotFromValidCluster);
+				assert_ok!(NFT::add_secret_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID));
+
+				// Final state checks.
+				let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+				assert_eq!(nft.state.is_syncing_secret, false);
+				assert_eq!(NFT::secret_syncs(ALICE_NFT_ID), None);
+				let holders = vec![(0, ALICE), (0, BOB)];
+				assert_eq!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).to_vec(), holders);
+				assert_eq!(NFT::shard_holder_nfts(ALICE, ALICE_NFT_ID), Some(()));
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), Some(()));
+			},
+		)
+	}
+
+	#[test]
+	fn rotate_secret_keeps_capsule_shard_holders() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				NFT::convert_to_capsule(origin(ALICE), ALICE_NFT_ID, BoundedVec::default())
+					.unwrap();
+				for enclave in [ALICE_ENCLAVE, BOB_ENCLAVE] {
+					NFT::add_capsule_shard(origin(enclave), ALICE_NFT_ID).unwrap();
+				}
+
+				assert_ok!(NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None));
+
+				// Final state checks.
+				assert!(NFT::secret_nft_shard_holders(ALICE_NFT_ID).is_empty());
+				assert_eq!(NFT::capsule_shard_holders(ALICE_NFT_ID).len(), 2);
+				assert_eq!(NFT::shard_holder_nfts(ALICE, ALICE_NFT_ID), Some(()));
+				assert_eq!(NFT::shard_holder_nfts(BOB, ALICE_NFT_ID), Some(()));
+			},
+		)
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+
+				let err = NFT::rotate_secret(origin(BOB), ALICE_NFT_ID, None);
+				assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+			},
+		)
+	}
+
+	#[test]
+	fn cannot_change_key_for_syncing_capsules() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				NFT::convert_to_capsule(origin(ALICE), ALICE_NFT_ID, BoundedVec::default())
+					.unwrap();
+
+				let err = NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None);
+				// Should fail because the capsule of the NFT is still syncing.
+				assert_noop!(err, Error::<Test>::CannotChangeKeyForSyncingCapsules);
+			},
+		)
+	}
+
+	#[test]
+	fn nft_is_not_secret() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+
+			let err = NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None);
+			assert_noop!(err, Error::<Test>::NFTIsNotSecret);
+		})
+	}
+
+	#[test]
+	fn cannot_change_key_for_syncing_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			NFT::add_secret(origin(ALICE), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+			let err = NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None);
+			assert_noop!(err, Error::<Test>::CannotChangeKeyForSyncingNFTs);
+		})
+	}
+
+	#[test]
+	fn cannot_change_key_for_delegated_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				NFT::delegate_nft(origin(ALICE), ALICE_NFT_ID, Some(BOB), None).unwrap();
+
+				let err = NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None);
+				assert_noop!(err, Error::<Test>::CannotChangeKeyForDelegatedNFTs);
+			},
+		)
+	}
+
+	#[test]
+	fn rotated_secret_sync_cannot_be_cancelled() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				let offchain_data: BoundedVec<u8, NFTOffchainDataLimit> =
+					BoundedVec::try_from(vec![1]).unwrap();
+				NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, Some(offchain_data.clone()))
+					.unwrap();
+				assert_eq!(NFT::secret_rotations(ALICE_NFT_ID), Some(()));
+				run_to_block(1 + ShardSyncTimeout::get());
+
+				let err = NFT::cancel_secret_sync(origin(ALICE), ALICE_NFT_ID);
+				// Should fail because the previous secret cannot be restored.
+				assert_noop!(err, Error::<Test>::CannotCancelSecretRotation);
+
+				// The sync can be retried on another cluster instead.
+				assert_ok!(NFT::retry_secret_sync(origin(ALICE), ALICE_NFT_ID, 1));
+				assert_ok!(NFT::add_secret_shard(origin(CHARLIE_ENCLAVE), ALICE_NFT_ID));
+
+				// Final state checks.
+				let nft = NFT::nfts(ALICE_NFT_ID).unwrap();
+				assert_eq!(nft.state.is_secret, true);
+				assert_eq!(
+					NFT::secret_nfts_offchain_data(ALICE_NFT_ID),
+					Some(offchain_data.clone())
+				);
+			},
+		)
+	}
+
+	#[test]
+	fn synced_rotation_is_forgotten() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				prepare_synced_secret();
+				NFT::rotate_secret(origin(ALICE), ALICE_NFT_ID, None).unwrap();
+				for enclave in [ALICE_ENCLAVE, BOB_ENCLAVE] {
+					NFT::add_secret_shard(origin(enclave), ALICE_NFT_ID).unwrap();
+				}
+
+				// Final state checks.
+				assert_eq!(NFT::secret_rotations(ALICE_NFT_ID), None);
+			},
+		)
+	}
+}
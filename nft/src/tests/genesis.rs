@@ -0,0 +1,145 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::mock::*;
+use frame_support::traits::GenesisBuild;
+use primitives::nfts::NFTState;
+use sp_arithmetic::per_things::Permill;
+
+use crate::GenesisConfig;
+
+const PERCENT_0: Permill = Permill::from_parts(0);
+
+/// Build the test externalities with the given NFT genesis.
+fn build(config: GenesisConfig<Test>) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	config.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+#[test]
+fn genesis() {
+	let config = GenesisConfig::<Test> {
+		nfts: vec![
+			(0, ALICE, vec![1], PERCENT_0, Some(0), false, None, None),
+			(1, ALICE, vec![2], PERCENT_0, Some(0), true, Some(vec![3]), None),
+			(5, BOB, vec![], PERCENT_0, None, false, None, Some(vec![4])),
+		],
+		collections: vec![(0, ALICE, vec![5], Some(2)), (3, BOB, vec![], None)],
+		nft_mint_fee: 1,
+		secret_nft_mint_fee: 2,
+		capsule_mint_fee: 3,
+	};
+
+	build(config).execute_with(|| {
+		// Fees checks.
+		assert_eq!(NFT::nft_mint_fee(), 1);
+		assert_eq!(NFT::secret_nft_mint_fee(), 2);
+		assert_eq!(NFT::capsule_mint_fee(), 3);
+
+		// NFTs checks.
+		let nft = NFT::nfts(1).unwrap();
+		assert_eq!(nft.owner, ALICE);
+		assert_eq!(nft.creator, ALICE);
+		assert_eq!(nft.collection_id, Some(0));
+		assert_eq!(nft.state, NFTState::new(false, true, false, true, false, false));
+		assert_eq!(NFT::secret_nfts_offchain_data(1).unwrap().to_vec(), vec![3]);
+		let nft = NFT::nfts(5).unwrap();
+		assert_eq!(nft.state, NFTState::new(true, false, false, false, false, false));
+		assert_eq!(NFT::capsule_offchain_data(5).unwrap().to_vec(), vec![4]);
+		assert_eq!(NFT::account_nfts(BOB, 5), Some(()));
+
+		// Collections checks.
+		let collection = NFT::collections(0).unwrap();
+		assert_eq!(collection.owner, ALICE);
+		assert_eq!(collection.limit, Some(2));
+		assert_eq!(collection.nfts_count, 2);
+		assert_eq!(NFT::collection_nfts_of(0, 1), Some(()));
+		assert_eq!(NFT::collections(3).unwrap().nfts_count, 0);
+
+		// Counters checks.
+		assert_eq!(NFT::next_nft_id(), 6);
+		assert_eq!(NFT::next_collection_id(), 4);
+	})
+}
+
+#[test]
+fn default_genesis() {
+	build(GenesisConfig::<Test>::default()).execute_with(|| {
+		assert_eq!(NFT::nft_mint_fee(), NFT_MINT_FEE);
+		assert_eq!(NFT::secret_nft_mint_fee(), SECRET_NFT_MINT_FEE);
+		assert_eq!(NFT::capsule_mint_fee(), CAPSULE_MINT_FEE);
+		assert_eq!(NFT::next_nft_id(), 0);
+		assert_eq!(NFT::next_collection_id(), 0);
+	})
+}
+
+#[test]
+#[should_panic(expected = "Duplicate NFT id in genesis")]
+fn duplicate_nft_id() {
+	let config = GenesisConfig::<Test> {
+		nfts: vec![
+			(0, ALICE, vec![], PERCENT_0, None, false, None, None),
+			(0, BOB, vec![], PERCENT_0, None, false, None, None),
+		],
+		..Default::default()
+	};
+	build(config);
+}
+
+#[test]
+#[should_panic(expected = "Duplicate collection id in genesis")]
+fn duplicate_collection_id() {
+	let config = GenesisConfig::<Test> {
+		collections: vec![(0, ALICE, vec![], None), (0, BOB, vec![], None)],
+		..Default::default()
+	};
+	build(config);
+}
+
+#[test]
+#[should_panic(expected = "Collection has too many NFTs in genesis")]
+fn collection_over_limit() {
+	let config = GenesisConfig::<Test> {
+		nfts: vec![
+			(0, ALICE, vec![], PERCENT_0, Some(0), false, None, None),
+			(1, ALICE, vec![], PERCENT_0, Some(0), false, None, None),
+		],
+		collections: vec![(0, ALICE, vec![], Some(1))],
+		..Default::default()
+	};
+	build(config);
+}
+
+#[test]
+#[should_panic(expected = "Collection limit exceeds the maximum allowed in genesis")]
+fn collection_limit_over_maximum() {
+	let config = GenesisConfig::<Test> {
+		collections: vec![(0, ALICE, vec![], Some(CollectionSizeLimit::get() + 1))],
+		..Default::default()
+	};
+	build(config);
+}
+
+#[test]
+#[should_panic(expected = "Unknown NFT collection in genesis")]
+fn unknown_collection() {
+	let config = GenesisConfig::<Test> {
+		nfts: vec![(0, ALICE, vec![], PERCENT_0, Some(0), false, None, None)],
+		..Default::default()
+	};
+	build(config);
+}
@@ -16,12 +16,15 @@
 
 use frame_support::{
 	parameter_types,
-	traits::{ConstU32, Contains, Currency},
+	traits::{ConstU32, Contains, Currency, Hooks},
+	weights::Weight,
 };
+use primitives::nfts::{NFTId, NFTLock, NFTStoragePayment};
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
 
 use crate::{self as ternoa_nft, Config, NegativeImbalanceOf};
@@ -134,6 +137,7 @@ impl ternoa_tee::Config for Test {
 	type ClusterSize = ClusterSize;
 	type MaxUriLen = MaxUriLen;
 	type ListSizeLimit = ListSizeLimit;
+	type OnEnclaveRemoved = NFT;
 }
 
 parameter_types! {
@@ -144,6 +148,26 @@ parameter_types! {
 	pub const InitialSecretMintFee: Balance = SECRET_NFT_MINT_FEE;
 	pub const ShardsNumber: u32 = 2;
 	pub const InitialCapsuleMintFee: Balance = CAPSULE_MINT_FEE;
+	pub const BatchSizeLimit: u32 = 10;
+	pub const RoyaltySplitsLimit: u32 = 5;
+	pub const CollectionMintersLimit: u32 = 5;
+	pub const SimultaneousDelegationLimit: u32 = 10;
+	pub const DelegationActionsInBlockLimit: u32 = 10;
+	pub const NFTChildrenLimit: u32 = 5;
+	pub const NestingDepthLimit: u32 = 3;
+	pub static CapsuleRefundRatio: Permill = Permill::from_parts(0);
+	pub const ShardSyncTimeout: u64 = 10;
+	pub static StoragePayment: NFTStoragePayment = NFTStoragePayment::Fee;
+	pub const NFTDepositBase: u64 = 20;
+	pub const NFTDepositPerByte: u64 = 1;
+	pub const MetadataHistoryLimit: u32 = 3;
+	pub const AttributeKeyLimit: u32 = 32;
+	pub const AttributeValueLimit: u32 = 64;
+	pub const AttributeDepositBase: u64 = 10;
+	pub const AttributeDepositPerByte: u64 = 1;
+	pub const AllowlistProofLimit: u32 = 16;
+	pub static TransferredNFTs: Vec<(NFTId, u64, u64)> = vec![];
+	pub static BurnedNFTs: Vec<(NFTId, u64)> = vec![];
 }
 
 impl Config for Test {
@@ -158,7 +182,31 @@ impl Config for Test {
 	type InitialSecretMintFee = InitialSecretMintFee;
 	type ShardsNumber = ShardsNumber;
 	type TEEExt = TEE;
+	type OnNFTTransfer = MockNFTHooks;
+	type OnNFTBurn = MockNFTHooks;
 	type InitialCapsuleMintFee = InitialCapsuleMintFee;
+	type BatchSizeLimit = BatchSizeLimit;
+	type RoyaltySplitsLimit = RoyaltySplitsLimit;
+	type CollectionMintersLimit = CollectionMintersLimit;
+	type SimultaneousDelegationLimit = SimultaneousDelegationLimit;
+	type ActionsInBlockLimit = DelegationActionsInBlockLimit;
+	type NFTChildrenLimit = NFTChildrenLimit;
+	type NestingDepthLimit = NestingDepthLimit;
+	type CapsuleRefundRatio = CapsuleRefundRatio;
+	type ShardSyncTimeout = ShardSyncTimeout;
+	type StoragePayment = StoragePayment;
+	type NFTDepositBase = NFTDepositBase;
+	type NFTDepositPerByte = NFTDepositPerByte;
+	type MetadataHistoryLimit = MetadataHistoryLimit;
+	type AttributeKeyLimit = AttributeKeyLimit;
+	type AttributeValueLimit = AttributeValueLimit;
+	type AttributeDepositBase = AttributeDepositBase;
+	type AttributeDepositPerByte = AttributeDepositPerByte;
+	type AllowlistProofLimit = AllowlistProofLimit;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
 }
 
 pub struct MockFeeCollector;
@@ -168,6 +216,32 @@ impl frame_support::traits::OnUnbalanced<NegativeImbalanceOf<Test>> for MockFeeC
 	}
 }
 
+pub struct MockNFTHooks;
+impl ternoa_common::traits::OnNFTTransfer<u64> for MockNFTHooks {
+	fn on_nft_transfer(nft_id: NFTId, from: &u64, to: &u64) -> Weight {
+		let mut transferred = TransferredNFTs::get();
+		transferred.push((nft_id, *from, *to));
+		TransferredNFTs::set(transferred);
+		Weight::zero()
+	}
+
+	fn releases_lock(_lock: &NFTLock) -> bool {
+		false
+	}
+}
+impl ternoa_common::traits::OnNFTBurn<u64> for MockNFTHooks {
+	fn on_nft_burn(nft_id: NFTId, owner: &u64) -> Weight {
+		let mut burned = BurnedNFTs::get();
+		burned.push((nft_id, *owner));
+		BurnedNFTs::set(burned);
+		Weight::zero()
+	}
+
+	fn releases_lock(_lock: &NFTLock) -> bool {
+		false
+	}
+}
+
 pub struct ExtBuilder {
 	balances: Vec<(u64, Balance)>,
 }
@@ -206,3 +280,15 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
 	t.into()
 }
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		NFT::on_finalize(System::block_number());
+		Balances::on_finalize(System::block_number());
+		System::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		System::on_initialize(System::block_number());
+		Balances::on_initialize(System::block_number());
+		NFT::on_initialize(System::block_number());
+	}
+}
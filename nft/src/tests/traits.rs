@@ -15,23 +15,26 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
-use frame_support::BoundedVec;
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult, BoundedVec};
 use frame_system::RawOrigin;
-use primitives::nfts::NFTState;
+use primitives::nfts::{
+	AttributeNamespace, CollectionId, NFTId, NFTLock, NFTState, NFTStoragePayment,
+};
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
-use crate::tests::mock;
+use crate::{tests::mock, Error};
 
 const PERCENT_0: Permill = Permill::from_parts(0);
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
 
 #[test]
 fn set_nft_state() {
 	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
 		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
-		NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 		let nft_id = mock::NFT::get_next_nft_id() - 1;
-		let nft_state = NFTState::new(true, true, true, true, true, true, false, false, false);
+		let nft_state = NFTState::new(true, true, true, true, true, false);
 		<NFT as NFTExt>::set_nft_state(nft_id, nft_state.clone()).unwrap();
 		let nft = NFT::nfts(nft_id).unwrap();
 		assert_eq!(nft.state, nft_state);
@@ -44,7 +47,8 @@ fn create_filled_collection() {
 		<NFT as NFTExt>::create_filled_collection(ALICE, 0, 0, CollectionSizeLimit::get()).unwrap();
 		let collection = NFT::collections(0).unwrap();
 		assert_eq!(collection.owner, ALICE);
-		assert_eq!(collection.nfts.len(), CollectionSizeLimit::get() as usize);
+		assert_eq!(collection.nfts_count, CollectionSizeLimit::get());
+		assert_eq!(NFT::collection_nfts(0).unwrap().len(), CollectionSizeLimit::get() as usize);
 	})
 }
 
@@ -52,7 +56,7 @@ fn create_filled_collection() {
 fn get_nft() {
 	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
 		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
-		NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 		let nft_id = mock::NFT::get_next_nft_id() - 1;
 		let nft = NFT::get_nft(nft_id).unwrap();
 		assert_eq!(nft.owner, ALICE);
@@ -66,7 +70,7 @@ fn get_nft() {
 fn set_nft() {
 	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
 		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
-		NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 		let nft_id = mock::NFT::get_next_nft_id() - 1;
 		let nft = NFT::get_nft(nft_id).unwrap();
 		let mut nft_data = nft.clone();
@@ -74,6 +78,8 @@ fn set_nft() {
 		NFT::set_nft(nft_id, nft_data).unwrap();
 		let nft = NFT::get_nft(nft_id).unwrap();
 		assert_eq!(nft.owner, BOB);
+		assert_eq!(NFT::account_nfts(ALICE, nft_id), None);
+		assert_eq!(NFT::account_nfts(BOB, nft_id), Some(()));
 	})
 }
 
@@ -87,3 +93,467 @@ fn create_nft() {
 		assert_eq!(nft.owner, ALICE);
 	})
 }
+
+#[test]
+fn create_nft_reserves_deposit() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		StoragePayment::set(NFTStoragePayment::Deposit);
+		let offchain_data = BoundedVec::try_from(vec![1; 5]).unwrap();
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, offchain_data, PERCENT_0, None, false).unwrap();
+
+		let deposit = NFTDepositBase::get() + 5 * NFTDepositPerByte::get();
+		assert_eq!(NFT::nft_deposits(nft_id), Some((ALICE, deposit)));
+		assert_eq!(Balances::reserved_balance(ALICE), deposit);
+	})
+}
+
+#[test]
+fn mutate_nft() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		assert_eq!(NFT::account_nfts(ALICE, nft_id), Some(()));
+
+		// The owner index follows the owner changes.
+		NFT::mutate_nft(nft_id, |x| -> DispatchResult {
+			x.as_mut().unwrap().owner = BOB;
+			Ok(())
+		})
+		.unwrap();
+		assert_eq!(NFT::get_nft(nft_id).unwrap().owner, BOB);
+		assert_eq!(NFT::account_nfts(ALICE, nft_id), None);
+		assert_eq!(NFT::account_nfts(BOB, nft_id), Some(()));
+
+		// Failed mutations leave the index untouched.
+		let err = NFT::mutate_nft(nft_id, |x| -> DispatchResult {
+			x.as_mut().unwrap().owner = CHARLIE;
+			Err(Error::<Test>::NFTNotFound.into())
+		});
+		assert_noop!(err, Error::<Test>::NFTNotFound);
+	})
+}
+
+#[test]
+fn nfts_of() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		for _ in 0..3 {
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		}
+		<NFT as NFTExt>::create_nft(BOB, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+
+		let mut nfts = NFT::nfts_of(ALICE, 0, 10);
+		nfts.sort();
+		assert_eq!(nfts, vec![0, 1, 2]);
+		assert_eq!(NFT::nfts_of(ALICE, 1, 10).len(), 2);
+		assert_eq!(NFT::nfts_of(ALICE, 0, 2).len(), 2);
+		assert_eq!(NFT::nfts_of(BOB, 0, 10), vec![3]);
+		assert_eq!(NFT::nft_state(3), Some(NFTState::new_default(false)));
+		assert_eq!(NFT::nft_state(4), None);
+	})
+}
+
+#[test]
+fn get_royalty_splits() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+		NFT::create_collection(alice.clone(), BoundedVec::default(), None).unwrap();
+		let collection_id = NFT::next_collection_id() - 1;
+		NFT::create_nft(
+			alice.clone(),
+			BoundedVec::default(),
+			Some(PERCENT_0),
+			Some(collection_id),
+			false,
+		)
+		.unwrap();
+		let nft_id = NFT::next_nft_id() - 1;
+
+		// Creator gets the whole royalty by default.
+		assert_eq!(NFT::get_royalty_splits(nft_id), vec![(ALICE, Permill::one())]);
+
+		// Collection default splits apply.
+		let splits = BoundedVec::try_from(vec![(BOB, Permill::one())]).unwrap();
+		NFT::set_collection_royalty_splits(alice.clone(), collection_id, Some(splits)).unwrap();
+		assert_eq!(NFT::get_royalty_splits(nft_id), vec![(BOB, Permill::one())]);
+
+		// NFT splits take precedence.
+		let half = Permill::from_percent(50);
+		let splits = BoundedVec::try_from(vec![(ALICE, half), (CHARLIE, half)]).unwrap();
+		NFT::set_royalty_splits(alice, nft_id, Some(splits)).unwrap();
+		assert_eq!(NFT::get_royalty_splits(nft_id), vec![(ALICE, half), (CHARLIE, half)]);
+
+		// Unknown NFTs have no beneficiaries.
+		assert_eq!(NFT::get_royalty_splits(nft_id + 1), vec![]);
+	})
+}
+
+#[test]
+fn lock_nft() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		NFT::lock_nft(nft_id, TEST_LOCK).unwrap();
+		let nft = NFT::get_nft(nft_id).unwrap();
+		assert!(nft.state.is_locked_by(&TEST_LOCK));
+		assert!(nft.can_transfer().is_err());
+		assert!(nft.can_list().is_err());
+
+		// The same lock cannot be taken twice.
+		let err = NFT::lock_nft(nft_id, TEST_LOCK);
+		assert_noop!(err, Error::<Test>::NFTAlreadyLocked);
+	})
+}
+
+#[test]
+fn unlock_nft() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		NFT::lock_nft(nft_id, TEST_LOCK).unwrap();
+		NFT::unlock_nft(nft_id, TEST_LOCK).unwrap();
+		let nft = NFT::get_nft(nft_id).unwrap();
+		assert!(!nft.state.is_locked());
+		assert!(nft.can_transfer().is_ok());
+
+		// A lock that is not held cannot be released.
+		let err = NFT::unlock_nft(nft_id, TEST_LOCK);
+		assert_noop!(err, Error::<Test>::NFTLockNotFound);
+	})
+}
+
+#[test]
+fn set_nft_pallet_attribute() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		NFT::set_nft_pallet_attribute(nft_id, b"rented", b"true").unwrap();
+		let value = NFT::get_nft_attribute(nft_id, AttributeNamespace::Pallet, b"rented");
+		assert_eq!(value, Some(b"true".to_vec()));
+		// No deposit is reserved for pallet attributes.
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		// Other namespaces are not affected.
+		assert_eq!(NFT::get_nft_attribute(nft_id, AttributeNamespace::Owner, b"rented"), None);
+
+		// Unknown NFTs cannot have attributes.
+		let err = NFT::set_nft_pallet_attribute(nft_id + 1, b"rented", b"true");
+		assert_noop!(err, Error::<Test>::NFTNotFound);
+	})
+}
+
+#[test]
+fn clear_nft_pallet_attribute() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let nft_id =
+			<NFT as NFTExt>::create_nft(ALICE, BoundedVec::default(), PERCENT_0, None, false)
+				.unwrap();
+		NFT::set_nft_pallet_attribute(nft_id, b"rented", b"true").unwrap();
+		NFT::clear_nft_pallet_attribute(nft_id, b"rented").unwrap();
+		assert_eq!(NFT::get_nft_attribute(nft_id, AttributeNamespace::Pallet, b"rented"), None);
+
+		// An attribute that is not set cannot be cleared.
+		let err = NFT::clear_nft_pallet_attribute(nft_id, b"rented");
+		assert_noop!(err, Error::<Test>::AttributeNotFound);
+	})
+}
+
+#[test]
+fn get_collection_attribute() {
+	ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+		NFT::create_collection(alice.clone(), BoundedVec::default(), None).unwrap();
+		let collection_id = NFT::next_collection_id() - 1;
+		let key = BoundedVec::try_from(b"theme".to_vec()).unwrap();
+		let value = BoundedVec::try_from(b"space".to_vec()).unwrap();
+		NFT::set_collection_attribute(alice, collection_id, key, value).unwrap();
+		let namespace = AttributeNamespace::CollectionOwner;
+		let value = NFT::get_collection_attribute(collection_id, namespace, b"theme");
+		assert_eq!(value, Some(b"space".to_vec()));
+		assert_eq!(NFT::get_collection_attribute(collection_id, namespace, b"other"), None);
+	})
+}
+
+mod nonfungibles {
+	use super::*;
+	use frame_support::{
+		traits::{
+			tokens::nonfungibles::{Create, Destroy, Inspect, InspectEnumerable, Mutate, Transfer},
+			Hooks,
+		},
+		weights::Weight,
+	};
+	use primitives::nfts::{CollectionMintConfig, MetadataMutability};
+
+	const COLLECTION_ID: CollectionId = 0;
+
+	// Creates a collection for ALICE holding one NFT and returns the NFT id.
+	fn prepare_tests() -> NFTId {
+		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+		NFT::create_collection(alice.clone(), BoundedVec::default(), None).unwrap();
+		NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), Some(COLLECTION_ID), false)
+			.unwrap();
+		NFT::next_nft_id() - 1
+	}
+
+	#[test]
+	fn inspect() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			assert_eq!(<NFT as Inspect<u64>>::owner(&COLLECTION_ID, &nft_id), Some(ALICE));
+			assert_eq!(<NFT as Inspect<u64>>::collection_owner(&COLLECTION_ID), Some(ALICE));
+			assert!(<NFT as Inspect<u64>>::can_transfer(&COLLECTION_ID, &nft_id));
+
+			// NFTs are only reachable through their own collection.
+			assert_eq!(<NFT as Inspect<u64>>::owner(&(COLLECTION_ID + 1), &nft_id), None);
+
+			// Locked NFTs cannot be transferred.
+			NFT::lock_nft(nft_id, TEST_LOCK).unwrap();
+			assert!(!<NFT as Inspect<u64>>::can_transfer(&COLLECTION_ID, &nft_id));
+		})
+	}
+
+	#[test]
+	fn inspect_enumerable() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			let collections: Vec<_> = <NFT as InspectEnumerable<u64>>::collections().collect();
+			let items: Vec<_> = <NFT as InspectEnumerable<u64>>::items(&COLLECTION_ID).collect();
+			let owned: Vec<_> = <NFT as InspectEnumerable<u64>>::owned(&ALICE).collect();
+			let owned_in_collection: Vec<_> =
+				<NFT as InspectEnumerable<u64>>::owned_in_collection(&COLLECTION_ID, &ALICE)
+					.collect();
+			assert_eq!(collections, vec![COLLECTION_ID]);
+			assert_eq!(items, vec![nft_id]);
+			assert_eq!(owned, vec![(COLLECTION_ID, nft_id)]);
+			assert_eq!(owned_in_collection, vec![nft_id]);
+			assert_eq!(<NFT as InspectEnumerable<u64>>::owned(&BOB).count(), 0);
+		})
+	}
+
+	#[test]
+	fn owned_follows_account_nfts() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+			// NFTs without a collection are not exposed.
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
+			NFT::transfer_nft(alice, nft_id, BOB).unwrap();
+
+			assert_eq!(<NFT as InspectEnumerable<u64>>::owned(&ALICE).count(), 0);
+			let owned: Vec<_> = <NFT as InspectEnumerable<u64>>::owned(&BOB).collect();
+			assert_eq!(owned, vec![(COLLECTION_ID, nft_id)]);
+		})
+	}
+
+	#[test]
+	fn transfer() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			assert_ok!(<NFT as Transfer<u64>>::transfer(&COLLECTION_ID, &nft_id, &BOB));
+			assert_eq!(NFT::nfts(nft_id).unwrap().owner, BOB);
+		})
+	}
+
+	#[test]
+	fn transfer_follows_transfer_nft_rules() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			let transfer = || <NFT as Transfer<u64>>::transfer(&COLLECTION_ID, &nft_id, &BOB);
+
+			// Wrong collection.
+			let err = <NFT as Transfer<u64>>::transfer(&(COLLECTION_ID + 1), &nft_id, &BOB);
+			assert_noop!(err, Error::<Test>::NFTNotFoundInCollection);
+
+			// Transfer to the owner.
+			let err = <NFT as Transfer<u64>>::transfer(&COLLECTION_ID, &nft_id, &ALICE);
+			assert_noop!(err, Error::<Test>::CannotTransferNFTsToYourself);
+
+			// Locked.
+			NFT::lock_nft(nft_id, TEST_LOCK).unwrap();
+			assert_noop!(transfer(), Error::<Test>::CannotTransferLockedNFTs);
+			NFT::unlock_nft(nft_id, TEST_LOCK).unwrap();
+
+			// Delegated.
+			let nft_state = NFTState::new(false, false, true, false, false, false);
+			NFT::set_nft_state(nft_id, nft_state).unwrap();
+			assert_noop!(transfer(), Error::<Test>::CannotTransferDelegatedNFTs);
+
+			// Secret syncing.
+			let nft_state = NFTState::new(false, true, false, false, true, false);
+			NFT::set_nft_state(nft_id, nft_state).unwrap();
+			assert_noop!(transfer(), Error::<Test>::CannotTransferNotSyncedSecretNFTs);
+
+			// Capsule syncing.
+			let nft_state = NFTState::new(true, false, false, false, false, true);
+			NFT::set_nft_state(nft_id, nft_state).unwrap();
+			assert_noop!(transfer(), Error::<Test>::CannotTransferNotSyncedCapsules);
+
+			// Soulbound not owned by its creator.
+			let mut nft = NFT::get_nft(nft_id).unwrap();
+			nft.state = NFTState::new(false, false, false, true, false, false);
+			nft.creator = BOB;
+			NFT::set_nft(nft_id, nft).unwrap();
+			let err = <NFT as Transfer<u64>>::transfer(&COLLECTION_ID, &nft_id, &CHARLIE);
+			assert_noop!(err, Error::<Test>::CannotTransferNotCreatedSoulboundNFTs);
+		})
+	}
+
+	#[test]
+	fn mint_into() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let nft_id = NFT::next_nft_id();
+			assert_ok!(<NFT as Mutate<u64>>::mint_into(&COLLECTION_ID, &nft_id, &BOB));
+			let nft = NFT::nfts(nft_id).unwrap();
+			assert_eq!(nft.owner, BOB);
+			assert_eq!(nft.creator, ALICE);
+			assert_eq!(nft.collection_id, Some(COLLECTION_ID));
+			// The recipient pays the NFT mint fee.
+			assert_eq!(Balances::free_balance(BOB), 1000 - NFT_MINT_FEE);
+			assert!(NFT::collection_nfts_of(COLLECTION_ID, nft_id).is_some());
+
+			// Only the next NFT id can be minted.
+			let err = <NFT as Mutate<u64>>::mint_into(&COLLECTION_ID, &nft_id, &BOB);
+			assert_noop!(err, Error::<Test>::InvalidNFTId);
+
+			// Closed collections cannot receive new NFTs.
+			let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+			NFT::close_collection(alice, COLLECTION_ID).unwrap();
+			let next_id = NFT::next_nft_id();
+			let err = <NFT as Mutate<u64>>::mint_into(&COLLECTION_ID, &next_id, &BOB);
+			assert_noop!(err, Error::<Test>::CollectionIsClosed);
+		})
+	}
+
+	#[test]
+	fn mint_into_reserves_deposit() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			StoragePayment::set(NFTStoragePayment::Deposit);
+			prepare_tests();
+			let nft_id = NFT::next_nft_id();
+			assert_ok!(<NFT as Mutate<u64>>::mint_into(&COLLECTION_ID, &nft_id, &BOB));
+
+			// Final state checks.
+			let deposit = NFTDepositBase::get();
+			assert_eq!(NFT::nft_deposits(nft_id), Some((BOB, deposit)));
+			assert_eq!(Balances::reserved_balance(BOB), deposit);
+			assert_eq!(Balances::free_balance(BOB), 1000 - deposit);
+		})
+	}
+
+	#[test]
+	fn burn() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+			assert_ok!(<NFT as Mutate<u64>>::burn(&COLLECTION_ID, &nft_id, Some(&ALICE)));
+			assert_eq!(NFT::nfts(nft_id), None);
+			assert_eq!(NFT::collections(COLLECTION_ID).unwrap().nfts_count, 0);
+		})
+	}
+
+	#[test]
+	fn burn_follows_burn_nft_rules() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let nft_id = prepare_tests();
+
+			// Not the owner.
+			let err = <NFT as Mutate<u64>>::burn(&COLLECTION_ID, &nft_id, Some(&BOB));
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+
+			// Locked.
+			NFT::lock_nft(nft_id, TEST_LOCK).unwrap();
+			let err = <NFT as Mutate<u64>>::burn(&COLLECTION_ID, &nft_id, None);
+			assert_noop!(err, Error::<Test>::CannotBurnLockedNFTs);
+			NFT::unlock_nft(nft_id, TEST_LOCK).unwrap();
+
+			// Delegated.
+			let nft_state = NFTState::new(false, false, true, false, false, false);
+			NFT::set_nft_state(nft_id, nft_state).unwrap();
+			let err = <NFT as Mutate<u64>>::burn(&COLLECTION_ID, &nft_id, None);
+			assert_noop!(err, Error::<Test>::CannotBurnDelegatedNFTs);
+		})
+	}
+
+	#[test]
+	fn create_and_destroy_collection() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let collection_id = NFT::next_collection_id();
+
+			// Only the next collection id can be created.
+			let err = <NFT as Create<u64>>::create_collection(&(collection_id + 1), &ALICE, &ALICE);
+			assert_noop!(err, Error::<Test>::InvalidCollectionId);
+
+			assert_ok!(<NFT as Create<u64>>::create_collection(&collection_id, &ALICE, &ALICE));
+			assert_eq!(NFT::collections(collection_id).unwrap().owner, ALICE);
+
+			let witness = <NFT as Destroy<u64>>::get_destroy_witness(&collection_id).unwrap();
+			let err = <NFT as Destroy<u64>>::destroy(collection_id, witness, Some(BOB));
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+
+			assert_ok!(<NFT as Destroy<u64>>::destroy(collection_id, witness, Some(ALICE)));
+			assert_eq!(NFT::collections(collection_id), None);
+		})
+	}
+
+	#[test]
+	fn destroy_releases_attribute_deposits() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+			NFT::create_collection(alice.clone(), BoundedVec::default(), None).unwrap();
+			let key = BoundedVec::try_from(b"theme".to_vec()).unwrap();
+			let value = BoundedVec::try_from(b"space".to_vec()).unwrap();
+			NFT::set_collection_attribute(alice, COLLECTION_ID, key, value).unwrap();
+			assert!(Balances::reserved_balance(ALICE) > 0);
+
+			let witness = <NFT as Destroy<u64>>::get_destroy_witness(&COLLECTION_ID).unwrap();
+			assert_ok!(<NFT as Destroy<u64>>::destroy(COLLECTION_ID, witness, Some(ALICE)));
+
+			// Final state checks.
+			let namespace = AttributeNamespace::CollectionOwner;
+			assert_eq!(NFT::get_collection_attribute(COLLECTION_ID, namespace, b"theme"), None);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn destroy_removes_collection_settings() {
+		ExtBuilder::new_build(vec![(ALICE, 1000)]).execute_with(|| {
+			let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+			NFT::create_collection(alice.clone(), BoundedVec::default(), None).unwrap();
+			NFT::add_minter(alice.clone(), COLLECTION_ID, BOB).unwrap();
+			let config = CollectionMintConfig {
+				price: 10,
+				start_block: None,
+				end_block: None,
+				max_per_account: None,
+				payout: Some(BOB),
+				allowlist_until: None,
+			};
+			NFT::set_mint_config(alice.clone(), COLLECTION_ID, Some(config)).unwrap();
+			let accounts = BoundedVec::try_from(vec![BOB]).unwrap();
+			NFT::set_mint_allowlist(alice.clone(), COLLECTION_ID, accounts, true).unwrap();
+			let mutability = MetadataMutability::CollectionOwner;
+			NFT::set_collection_metadata_mutability(alice.clone(), COLLECTION_ID, mutability)
+				.unwrap();
+			NFT::freeze_collection_metadata(alice, COLLECTION_ID).unwrap();
+
+			let witness = <NFT as Destroy<u64>>::get_destroy_witness(&COLLECTION_ID).unwrap();
+			assert_ok!(<NFT as Destroy<u64>>::destroy(COLLECTION_ID, witness, Some(ALICE)));
+
+			// Final state checks.
+			assert!(NFT::collection_minters(COLLECTION_ID).is_empty());
+			assert_eq!(NFT::collection_mint_configs(COLLECTION_ID), None);
+			assert_eq!(NFT::mint_allowlists_to_clear(COLLECTION_ID), Some(()));
+			NFT::on_idle(1, Weight::from_ref_time(u64::MAX));
+			assert_eq!(NFT::mint_allowlist(COLLECTION_ID, BOB), None);
+			assert_eq!(NFT::collection_metadata_mutability(COLLECTION_ID), Default::default());
+			assert_eq!(NFT::frozen_collection_metadata(COLLECTION_ID), None);
+		})
+	}
+}
@@ -0,0 +1,96 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use frame_support::{traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use primitives::nfts::NFTId;
+use scale_info::TypeInfo;
+use sp_std::{fmt::Debug, vec};
+
+/// Queue containing the nft id whose delegation expires at the specified block.
+#[derive(
+	Encode, Decode, CloneNoBound, Eq, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(Limit))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+pub struct Queue<BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd, Limit: Get<u32>>(
+	pub BoundedVec<(NFTId, BlockNumber), Limit>,
+);
+impl<BlockNumber, Limit> Queue<BlockNumber, Limit>
+where
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	Limit: Get<u32>,
+{
+	/// Returns an empty queue.
+	fn default() -> Self {
+		Self(BoundedVec::default())
+	}
+
+	/// Returns the current size of the queue.
+	pub fn size(&self) -> u32 {
+		self.0.len() as u32
+	}
+
+	/// Inserts a value in the queue in the correct position depending on the block number.
+	pub fn insert(&mut self, nft_id: NFTId, block_number: BlockNumber) -> Result<(), ()> {
+		let index = self.0.iter().position(|x| x.1 > block_number);
+		let index = index.unwrap_or_else(|| self.0.len());
+
+		self.0.try_insert(index, (nft_id, block_number))
+	}
+
+	/// Remove a value in the queue if it exists.
+	pub fn remove(&mut self, nft_id: NFTId) -> bool {
+		let index = self.0.iter().position(|x| x.0 == nft_id);
+		if let Some(index) = index {
+			self.0.remove(index);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Pop and return the first value of the queue.
+	pub fn pop_next(&mut self, block_number: BlockNumber) -> Option<NFTId> {
+		let front = self.0.get(0)?;
+		if front.1 <= block_number {
+			let nft_id = front.0;
+			self.remove(nft_id);
+			Some(nft_id)
+		} else {
+			None
+		}
+	}
+
+	// Benchmark / tests only
+	pub fn bulk_insert(
+		&mut self,
+		nft_id: NFTId,
+		block_number: BlockNumber,
+		number: u32,
+	) -> Result<(), ()> {
+		self.0.try_extend(vec![(nft_id, block_number); number as usize].into_iter())
+	}
+}
+impl<BlockNumber, Limit> Default for Queue<BlockNumber, Limit>
+where
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	Limit: Get<u32>,
+{
+	fn default() -> Self {
+		Self::default()
+	}
+}
@@ -18,10 +18,10 @@ use frame_support::weights::Weight;
 use sp_std::marker::PhantomData;
 
 pub trait WeightInfo {
-	fn create_nft(s: u32) -> Weight;
-	fn burn_nft(s: u32) -> Weight;
+	fn create_nft() -> Weight;
+	fn burn_nft() -> Weight;
 	fn transfer_nft() -> Weight;
-	fn delegate_nft() -> Weight;
+	fn delegate_nft(s: u32) -> Weight;
 	fn set_royalty() -> Weight;
 	fn set_nft_mint_fee() -> Weight;
 	fn create_collection() -> Weight;
@@ -37,24 +37,67 @@ pub trait WeightInfo {
 	fn create_capsule(s: u32) -> Weight;
 	fn set_capsule_offchaindata() -> Weight;
 	fn set_capsule_mint_fee() -> Weight;
+	fn revert_capsule() -> Weight;
 	fn add_capsule_shard() -> Weight;
 	fn notify_enclave_key_update() -> Weight;
 	fn set_collection_offchaindata() -> Weight;
+	fn approve_transfer() -> Weight;
+	fn cancel_approval() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn create_nfts_batch(s: u32) -> Weight;
+	fn transfer_nfts_batch(s: u32) -> Weight;
+	fn set_royalty_splits() -> Weight;
+	fn set_collection_royalty_splits() -> Weight;
+	fn set_collection_royalty() -> Weight;
+	fn add_minter(s: u32) -> Weight;
+	fn remove_minter(s: u32) -> Weight;
+	fn set_minter_attribution() -> Weight;
+	fn create_nft_for(s: u32) -> Weight;
+	fn set_mint_config() -> Weight;
+	fn set_mint_allowlist(s: u32) -> Weight;
+	fn public_mint(s: u32) -> Weight;
+	fn set_allowlist_root() -> Weight;
+	fn allowlist_mint(s: u32) -> Weight;
+	fn redeem_voucher(s: u32) -> Weight;
+	fn issue_soulbound(s: u32) -> Weight;
+	fn revoke_soulbound(s: u32) -> Weight;
+	fn reject_soulbound(s: u32) -> Weight;
+	fn nest_nft() -> Weight;
+	fn unnest_nft() -> Weight;
+	fn set_nft_attribute() -> Weight;
+	fn clear_nft_attribute() -> Weight;
+	fn set_collection_attribute() -> Weight;
+	fn clear_collection_attribute() -> Weight;
+	fn lock_collection_attributes() -> Weight;
+	fn set_nft_offchaindata() -> Weight;
+	fn set_collection_metadata_mutability() -> Weight;
+	fn freeze_nft_metadata() -> Weight;
+	fn freeze_collection_metadata() -> Weight;
+	fn retry_secret_sync() -> Weight;
+	fn cancel_secret_sync() -> Weight;
+	fn retry_capsule_sync() -> Weight;
+	fn cancel_capsule_sync() -> Weight;
+	fn transfer_collection() -> Weight;
+	fn accept_collection_transfer() -> Weight;
+	fn cancel_collection_transfer() -> Weight;
+	fn set_collection_roles() -> Weight;
+	fn rotate_secret() -> Weight;
 }
 
 /// Weight functions for `ternoa_nft`.
 pub struct TernoaWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for TernoaWeight<T> {
-	fn create_nft(_s: u32) -> Weight {
+	fn create_nft() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
-	fn burn_nft(_s: u32) -> Weight {
+	fn burn_nft() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
 	fn transfer_nft() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
-	fn delegate_nft() -> Weight {
+	fn delegate_nft(_s: u32) -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
 	fn set_royalty() -> Weight {
@@ -102,6 +145,9 @@ impl<T: frame_system::Config> WeightInfo for TernoaWeight<T> {
 	fn set_capsule_mint_fee() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
+	fn revert_capsule() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
 	fn add_capsule_shard() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
@@ -111,4 +157,133 @@ impl<T: frame_system::Config> WeightInfo for TernoaWeight<T> {
 	fn set_collection_offchaindata() -> Weight {
 		Weight::from_ref_time(10_000_000 as u64)
 	}
+	fn approve_transfer() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn cancel_approval() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_approval_for_all() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn transfer_from() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn create_nfts_batch(s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_000_000 as u64).saturating_mul(s as u64))
+	}
+	fn transfer_nfts_batch(s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(10_000_000 as u64).saturating_mul(s as u64))
+	}
+	fn set_royalty_splits() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_collection_royalty_splits() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_collection_royalty() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn add_minter(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn remove_minter(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_minter_attribution() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn create_nft_for(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_mint_config() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_mint_allowlist(s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_000_000 as u64).saturating_mul(s as u64))
+	}
+	fn public_mint(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_allowlist_root() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn allowlist_mint(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn redeem_voucher(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn issue_soulbound(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn revoke_soulbound(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn reject_soulbound(_s: u32) -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn nest_nft() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn unnest_nft() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_nft_attribute() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn clear_nft_attribute() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_collection_attribute() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn clear_collection_attribute() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn lock_collection_attributes() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_nft_offchaindata() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_collection_metadata_mutability() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn freeze_nft_metadata() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn freeze_collection_metadata() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn retry_secret_sync() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn cancel_secret_sync() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn retry_capsule_sync() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn cancel_capsule_sync() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn transfer_collection() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn accept_collection_transfer() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn cancel_collection_transfer() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn set_collection_roles() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn rotate_secret() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
 }
@@ -17,13 +17,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-	inherent::Vec, traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug,
-	RuntimeDebugNoBound,
+	traits::{ConstU32, Get},
+	BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
 };
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
-use sp_arithmetic::per_things::Permill;
-use sp_std::fmt::Debug;
+use sp_arithmetic::{
+	per_things::Permill,
+	traits::{AtLeast32BitUnsigned, Saturating, Zero},
+};
+use sp_std::{fmt::Debug, vec::Vec};
 
 use crate::U8BoundedVec;
 
@@ -33,26 +36,199 @@ pub type NFTId = u32;
 /// How collection IDs are encoded.
 pub type CollectionId = u32;
 
+/// Identifier of a pallet or of a reason used to name NFT locks.
+pub type NFTLockId = [u8; 8];
+
+/// Maximum number of locks an NFT can hold at the same time.
+pub const MAX_NFT_LOCKS: u32 = 4;
+
+/// Locks held on an NFT.
+pub type NFTLocks = BoundedVec<NFTLock, ConstU32<MAX_NFT_LOCKS>>;
+
+/// A named lock taken on an NFT by a consuming pallet (marketplace, auction, rent, ...).
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub struct NFTLock {
+	/// Pallet holding the lock.
+	pub pallet: NFTLockId,
+	/// Why the pallet locked the NFT.
+	pub reason: NFTLockId,
+}
+
+impl NFTLock {
+	pub const fn new(pallet: NFTLockId, reason: NFTLockId) -> Self {
+		Self { pallet, reason }
+	}
+}
+
+/// Why an operation is not allowed with the current NFT state.
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub enum NFTRestriction {
+	/// The NFT is locked by a consuming pallet.
+	Locked(NFTLock),
+	/// The NFT is delegated.
+	Delegated,
+	/// The NFT is soulbound and the owner is not the creator.
+	NotCreatedSoulbound,
+	/// The NFT secret shards are syncing.
+	SecretSyncing,
+	/// The NFT capsule shards are syncing.
+	CapsuleSyncing,
+}
+
+/// Who can set an attribute of an NFT or of a collection.
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub enum AttributeNamespace {
+	/// Set by the NFT owner.
+	Owner,
+	/// Set by the collection owner.
+	CollectionOwner,
+	/// Set by a pallet through `NFTExt`.
+	Pallet,
+}
+
+/// How the storage used by the NFTs is paid when they are minted.
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub enum NFTStoragePayment {
+	/// A flat mint fee is sent to the fees collector.
+	Fee,
+	/// A deposit is reserved from the minter and released when the NFT is burned.
+	Deposit,
+	/// A deposit is reserved from the minter and moved to each new owner of the NFT. The deposit
+	/// stays with the previous depositor when the new owner cannot afford it.
+	DepositFollowingOwner,
+}
+
+/// Who can update the offchain data of the NFTs of a collection.
+#[derive(
+	Encode, Decode, Eq, Default, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen,
+)]
+pub enum MetadataMutability {
+	/// The creator of the NFT, as long as they own it.
+	#[default]
+	CreatorOwner,
+	/// The collection owner.
+	CollectionOwner,
+}
+
+/// Who is recorded as the creator of the NFTs minted by a collection minter.
+#[derive(
+	Encode, Decode, Eq, Default, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen,
+)]
+pub enum MinterAttribution {
+	/// The minter is the creator.
+	#[default]
+	Minter,
+	/// The collection owner is the creator.
+	CollectionOwner,
+}
+
+/// Public mint configuration of a collection. The maximum supply is the collection limit.
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub struct CollectionMintConfig<AccountId, Balance, BlockNumber> {
+	/// Price of an NFT, paid to the payout account.
+	pub price: Balance,
+	/// Block from which NFTs can be minted.
+	pub start_block: Option<BlockNumber>,
+	/// Last block in which NFTs can be minted.
+	pub end_block: Option<BlockNumber>,
+	/// Maximum number of NFTs an account can mint.
+	pub max_per_account: Option<u32>,
+	/// Account receiving the proceeds, the collection owner if none.
+	pub payout: Option<AccountId>,
+	/// Until this block, only allowlisted accounts can mint.
+	pub allowlist_until: Option<BlockNumber>,
+}
+
+impl<AccountId, Balance, BlockNumber: PartialOrd + Copy>
+	CollectionMintConfig<AccountId, Balance, BlockNumber>
+{
+	/// Returns true if the mint has started at the given block.
+	pub fn has_started(&self, now: BlockNumber) -> bool {
+		self.start_block.map_or(true, |x| now >= x)
+	}
+
+	/// Returns true if the mint has ended at the given block.
+	pub fn has_ended(&self, now: BlockNumber) -> bool {
+		self.end_block.map_or(false, |x| now > x)
+	}
+
+	/// Returns true if only allowlisted accounts can mint at the given block.
+	pub fn is_allowlist_phase(&self, now: BlockNumber) -> bool {
+		self.allowlist_until.map_or(false, |x| now < x)
+	}
+}
+
+/// NFT signed off-chain by its creator and minted on-chain by the buyer redeeming it.
+#[derive(
+	Encode, Decode, Eq, TypeInfo, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(NFTOffchainDataLimit))]
+#[codec(mel_bound(
+	AccountId: MaxEncodedLen,
+	Balance: MaxEncodedLen,
+	BlockNumber: MaxEncodedLen
+))]
+pub struct NFTVoucher<AccountId, Balance, BlockNumber, NFTOffchainDataLimit>
+where
+	AccountId: Clone + PartialEq + Debug,
+	Balance: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug,
+	NFTOffchainDataLimit: Get<u32>,
+{
+	/// Creator of the NFT, who signs the voucher and receives the price
+	pub creator: AccountId,
+	/// NFT offchain_data
+	pub offchain_data: U8BoundedVec<NFTOffchainDataLimit>,
+	/// NFT royalty, the default royalty of the collection if None
+	pub royalty: Option<Permill>,
+	/// Collection in which the NFT is minted
+	pub collection_id: Option<CollectionId>,
+	/// Price paid by the buyer to the creator
+	pub price: Balance,
+	/// Last block at which the voucher can be redeemed
+	pub expiry: BlockNumber,
+	/// Number used once per creator to prevent the voucher from being redeemed twice
+	pub nonce: u64,
+}
+
+/// Hash of a node of a collection mint allowlist Merkle tree. Each leaf is the hash of an
+/// allowlisted account and the number of NFTs it can mint.
+pub type MerkleHash = [u8; 32];
+
+/// Beneficiaries of an NFT royalty with their share of it.
+pub type RoyaltySplits<AccountId, Limit> = BoundedVec<(AccountId, Permill), Limit>;
+
+/// Split a royalty amount between beneficiaries according to their share.
+/// Rounding leftovers are paid to the first beneficiary so that the whole amount is distributed.
+pub fn split_royalty<AccountId: Clone, Balance: AtLeast32BitUnsigned + Copy>(
+	splits: &[(AccountId, Permill)],
+	amount: Balance,
+) -> Vec<(AccountId, Balance)> {
+	let mut payouts: Vec<(AccountId, Balance)> = splits
+		.iter()
+		.map(|(account, share)| (account.clone(), *share * amount))
+		.collect();
+	let paid = payouts.iter().fold(Balance::zero(), |acc, (_, x)| acc.saturating_add(*x));
+	if let Some((_, first)) = payouts.first_mut() {
+		*first = first.saturating_add(amount.saturating_sub(paid));
+	}
+	payouts
+}
+
+/// How a batch call handles the items that fail.
 #[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
-pub enum NFTStateModifiers {
-	Capsule = 0x01,
-	IsListed = 0x02,
-	Secret = 0x03,
-	Delegated = 0x04,
-	Soulbound = 0x05,
-	SecretSyncing = 0x06,
-	Rented = 0x07,
-	CapsuleSyncing = 0x08,
-	IsTransmission = 0x09,
+pub enum BatchMode {
+	/// The whole batch is reverted if one item fails.
+	AllOrNothing,
+	/// Failing items are skipped and reported in an event.
+	BestEffort,
 }
 
-/// Data related to an NFT state, such as if it is listed for sale.
+/// Data related to an NFT state, such as if it is a capsule or locked by another pallet.
 #[derive(Encode, Decode, Eq, Default, TypeInfo, Clone, PartialEq, RuntimeDebug, MaxEncodedLen)]
 pub struct NFTState {
 	/// Is NFT converted to capsule.
 	pub is_capsule: bool,
-	/// Is NFT listed for sale.
-	pub is_listed: bool,
 	/// Is NFT contains secret.
 	pub is_secret: bool,
 	/// Is NFT delegated.
@@ -61,41 +237,59 @@ pub struct NFTState {
 	pub is_soulbound: bool,
 	/// Is NFT Secret syncing
 	pub is_syncing_secret: bool,
-	/// Is NFT Rented or available for rent.
-	pub is_rented: bool,
 	/// Is Capsule syncing.
 	pub is_syncing_capsule: bool,
-	/// Is Transmission.
-	pub is_transmission: bool,
+	/// Locks taken by other pallets.
+	pub locks: NFTLocks,
 }
 
 impl NFTState {
 	pub fn new(
 		is_capsule: bool,
-		is_listed: bool,
 		is_secret: bool,
 		is_delegated: bool,
 		is_soulbound: bool,
 		is_syncing_secret: bool,
-		is_rented: bool,
 		is_syncing_capsule: bool,
-		is_transmission: bool,
 	) -> Self {
 		Self {
 			is_capsule,
-			is_listed,
 			is_secret,
 			is_delegated,
 			is_soulbound,
 			is_syncing_secret,
-			is_rented,
 			is_syncing_capsule,
-			is_transmission,
+			locks: NFTLocks::default(),
 		}
 	}
 
 	pub fn new_default(is_soulbound: bool) -> Self {
-		Self::new(false, false, false, false, is_soulbound, false, false, false, false)
+		Self::new(false, false, false, is_soulbound, false, false)
+	}
+
+	/// Returns true if any pallet holds a lock on the NFT.
+	pub fn is_locked(&self) -> bool {
+		!self.locks.is_empty()
+	}
+
+	/// Returns true if the given lock is held on the NFT.
+	pub fn is_locked_by(&self, lock: &NFTLock) -> bool {
+		self.locks.contains(lock)
+	}
+
+	/// Adds a lock. Returns None if the lock is already held or if there is no room left.
+	pub fn lock(&mut self, lock: NFTLock) -> Option<()> {
+		if self.is_locked_by(&lock) {
+			return None
+		}
+		self.locks.try_push(lock).ok()
+	}
+
+	/// Removes a lock. Returns None if the lock was not held.
+	pub fn unlock(&mut self, lock: &NFTLock) -> Option<()> {
+		let index = self.locks.iter().position(|x| x == lock)?;
+		self.locks.remove(index);
+		Some(())
 	}
 }
 
@@ -165,56 +359,56 @@ where
 		)
 	}
 
-	pub fn not_in_state(&self, list: &Vec<NFTStateModifiers>) -> Result<(), NFTStateModifiers> {
-		for modifier in list {
-			let in_state = match modifier {
-				NFTStateModifiers::Capsule => self.state.is_capsule == true,
-				NFTStateModifiers::IsListed => self.state.is_listed == true,
-				NFTStateModifiers::Secret => self.state.is_secret == true,
-				NFTStateModifiers::Delegated => self.state.is_delegated == true,
-				NFTStateModifiers::Soulbound => self.state.is_soulbound == true,
-				NFTStateModifiers::SecretSyncing => self.state.is_syncing_secret == true,
-				NFTStateModifiers::Rented => self.state.is_rented == true,
-				NFTStateModifiers::CapsuleSyncing => self.state.is_syncing_capsule == true,
-				NFTStateModifiers::IsTransmission => self.state.is_transmission == true,
-			};
-			if in_state {
-				return Err(*modifier)
-			}
+	/// Checks that the NFT can be burned.
+	pub fn can_burn(&self) -> Result<(), NFTRestriction> {
+		self.can_burn_releasing(|_| false)
+	}
+
+	/// Checks that the NFT can be burned, ignoring the locks for which `is_released` returns
+	/// true because their holder releases them once the NFT is burned.
+	pub fn can_burn_releasing(
+		&self,
+		is_released: impl Fn(&NFTLock) -> bool,
+	) -> Result<(), NFTRestriction> {
+		if let Some(lock) = self.state.locks.iter().find(|x| !is_released(x)) {
+			return Err(NFTRestriction::Locked(*lock))
+		}
+		if self.state.is_delegated {
+			return Err(NFTRestriction::Delegated)
 		}
 
 		Ok(())
 	}
 
-	pub fn set_state(&mut self, state: NFTStateModifiers, active: bool) -> Option<()> {
-		let is_already_in_state = match state {
-			NFTStateModifiers::Capsule => self.state.is_capsule == active,
-			NFTStateModifiers::IsListed => self.state.is_listed == active,
-			NFTStateModifiers::Secret => self.state.is_secret == active,
-			NFTStateModifiers::Delegated => self.state.is_delegated == active,
-			NFTStateModifiers::Soulbound => self.state.is_soulbound == active,
-			NFTStateModifiers::SecretSyncing => self.state.is_syncing_secret == active,
-			NFTStateModifiers::Rented => self.state.is_rented == active,
-			NFTStateModifiers::CapsuleSyncing => self.state.is_syncing_capsule == active,
-			NFTStateModifiers::IsTransmission => self.state.is_transmission == active,
-		};
-		if is_already_in_state {
-			return None
+	/// Checks that the NFT can change owner.
+	pub fn can_transfer(&self) -> Result<(), NFTRestriction> {
+		self.can_transfer_releasing(|_| false)
+	}
+
+	/// Checks that the NFT can change owner, ignoring the locks for which `is_released` returns
+	/// true because their holder releases them once the NFT is transferred.
+	pub fn can_transfer_releasing(
+		&self,
+		is_released: impl Fn(&NFTLock) -> bool,
+	) -> Result<(), NFTRestriction> {
+		self.can_burn_releasing(is_released)?;
+		if self.state.is_soulbound && self.creator != self.owner {
+			return Err(NFTRestriction::NotCreatedSoulbound)
+		}
+		if self.state.is_syncing_secret {
+			return Err(NFTRestriction::SecretSyncing)
+		}
+		if self.state.is_syncing_capsule {
+			return Err(NFTRestriction::CapsuleSyncing)
 		}
 
-		match state {
-			NFTStateModifiers::Capsule => self.state.is_capsule = active,
-			NFTStateModifiers::IsListed => self.state.is_listed = active,
-			NFTStateModifiers::Secret => self.state.is_secret = active,
-			NFTStateModifiers::Delegated => self.state.is_delegated = active,
-			NFTStateModifiers::Soulbound => self.state.is_soulbound = active,
-			NFTStateModifiers::SecretSyncing => self.state.is_syncing_secret = active,
-			NFTStateModifiers::Rented => self.state.is_rented = active,
-			NFTStateModifiers::CapsuleSyncing => self.state.is_syncing_capsule = active,
-			NFTStateModifiers::IsTransmission => self.state.is_transmission = active,
-		};
+		Ok(())
+	}
 
-		Some(())
+	/// Checks that the NFT can be listed, or more generally locked by a consuming pallet.
+	/// Locks are exclusive so this answers the same as `can_transfer`.
+	pub fn can_list(&self) -> Result<(), NFTRestriction> {
+		self.can_transfer()
 	}
 
 	pub fn is_owner(&self, who: &AccountId) -> Option<()> {
@@ -234,38 +428,50 @@ where
 	RuntimeDebugNoBound,
 	MaxEncodedLen,
 )]
-#[scale_info(skip_type_params(CollectionOffChainDataLimit, CollectionSizeLimit,))]
+#[scale_info(skip_type_params(CollectionOffChainDataLimit))]
 #[codec(mel_bound(AccountId: MaxEncodedLen))]
-pub struct Collection<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
+pub struct Collection<AccountId, CollectionOffChainDataLimit>
 where
 	AccountId: Clone + PartialEq + Debug,
 	CollectionOffChainDataLimit: Get<u32>,
-	CollectionSizeLimit: Get<u32>,
 {
 	/// Collection owner
 	pub owner: AccountId,
 	/// Collection offchain_data
 	pub offchain_data: U8BoundedVec<CollectionOffChainDataLimit>,
-	/// NFTs in that collection
-	pub nfts: BoundedVec<NFTId, CollectionSizeLimit>,
+	/// Number of NFTs in that collection
+	pub nfts_count: u32,
 	/// Maximum length of the collection
 	pub limit: Option<u32>,
 	/// Is collection closed for adding new NFTs
 	pub is_closed: bool,
+	/// Royalty inherited by the NFTs of the collection that do not override it
+	pub royalty: Option<Permill>,
+	/// Maximum royalty of the NFTs of the collection
+	pub max_royalty: Option<Permill>,
+	/// Merkle root of the collection mint allowlist
+	pub allowlist_root: Option<MerkleHash>,
 }
 
-impl<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
-	Collection<AccountId, CollectionOffChainDataLimit, CollectionSizeLimit>
+impl<AccountId, CollectionOffChainDataLimit> Collection<AccountId, CollectionOffChainDataLimit>
 where
 	AccountId: Clone + PartialEq + Debug,
 	CollectionOffChainDataLimit: Get<u32>,
-	CollectionSizeLimit: Get<u32>,
 {
 	pub fn new(
 		owner: AccountId,
 		offchain_data: U8BoundedVec<CollectionOffChainDataLimit>,
 		limit: Option<u32>,
 	) -> Self {
-		Self { owner, offchain_data, nfts: BoundedVec::default(), limit, is_closed: false }
+		Self {
+			owner,
+			offchain_data,
+			nfts_count: 0,
+			limit,
+			is_closed: false,
+			royalty: None,
+			max_royalty: None,
+			allowlist_root: None,
+		}
 	}
 }
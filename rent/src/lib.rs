@@ -36,19 +36,17 @@ use frame_support::{
 		ExistenceRequirement::{AllowDeath, KeepAlive},
 		Get, StorageVersion, WithdrawReasons,
 	},
+	weights::Weight,
 	BoundedVec, PalletId,
 };
 use frame_system::pallet_prelude::*;
-use primitives::nfts::{
-	NFTId,
-	NFTStateModifiers::{self, *},
-};
+use primitives::nfts::{NFTData, NFTId, NFTLock};
 use sp_runtime::{
 	traits::{AccountIdConversion, CheckedSub, Saturating},
 	Permill,
 };
 use sp_std::prelude::*;
-use ternoa_common::traits::NFTExt;
+use ternoa_common::traits::{NFTExt, OnNFTBurn, OnNFTTransfer};
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -64,6 +62,9 @@ pub type RentContractDataOf<T> = RentContractData<
 
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
+/// Lock held on NFTs that are under a rent contract.
+pub const RENT_LOCK: NFTLock = NFTLock::new(*b"ter/rent", *b"rented__");
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -367,10 +368,7 @@ pub mod pallet {
 
 			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-			ensure!(
-				nft.not_in_state(&Self::invalid_state()).is_ok(),
-				Error::<T>::ContractNFTNotInAValidState
-			);
+			ensure!(Self::is_in_valid_state(&nft), Error::<T>::ContractNFTNotInAValidState);
 
 			let duration_limit: T::BlockNumber = T::MaximumContractDurationLimit::get().into();
 			let duration = duration.to_duration(duration_limit.clone());
@@ -425,7 +423,7 @@ pub mod pallet {
 			if let Some(id) = renter_cancellation_fee.get_nft() {
 				T::NFTExt::mutate_nft(id, |x| -> DispatchResult {
 					let nft = x.as_mut().ok_or(Error::<T>::CancellationNFTNotFound)?;
-					let is_valid = nft.not_in_state(&Self::invalid_state()).is_ok();
+					let is_valid = Self::is_in_valid_state(nft);
 					ensure!(nft.owner == who, Error::<T>::CallerDoesNotOwnCancellationNFT);
 					ensure!(is_valid, Error::<T>::CancellationNFTNotInValidState);
 
@@ -455,7 +453,7 @@ pub mod pallet {
 			);
 			Contracts::<T>::insert(nft_id, contract);
 
-			_ = nft.set_state(Rented, true);
+			nft.state.lock(RENT_LOCK).ok_or(Error::<T>::ContractNFTNotInAValidState)?;
 			T::NFTExt::set_nft(nft_id, nft)?;
 
 			// Event 🎁
@@ -551,7 +549,7 @@ pub mod pallet {
 			Contracts::<T>::remove(nft_id);
 			T::NFTExt::mutate_nft(nft_id, |x| -> DispatchResult {
 				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
-				_ = nft.set_state(Rented, false);
+				_ = nft.state.unlock(&RENT_LOCK);
 				Ok(())
 			})?;
 
@@ -664,18 +662,12 @@ pub mod pallet {
 			if let Some(nft_id) = &maybe_rent_nft {
 				let nft = T::NFTExt::get_nft(*nft_id).ok_or(Error::<T>::RentNFTNotFound)?;
 				ensure!(nft.owner == who, Error::<T>::CallerDoesNotOwnRentNFT);
-				ensure!(
-					nft.not_in_state(&Self::invalid_state()).is_ok(),
-					Error::<T>::RentNFTNotInValidState
-				);
+				ensure!(Self::is_in_valid_state(&nft), Error::<T>::RentNFTNotInValidState);
 			}
 			if let Some(nft_id) = &maybe_cancel_nft {
 				let nft = T::NFTExt::get_nft(*nft_id).ok_or(Error::<T>::CancellationNFTNotFound)?;
 				ensure!(nft.owner == who, Error::<T>::CallerDoesNotOwnCancellationNFT);
-				ensure!(
-					nft.not_in_state(&Self::invalid_state()).is_ok(),
-					Error::<T>::CancellationNFTNotInValidState
-				);
+				ensure!(Self::is_in_valid_state(&nft), Error::<T>::CancellationNFTNotInValidState);
 			}
 
 			// Storage Activity 📦
@@ -984,7 +976,7 @@ impl<T: Config> Pallet<T> {
 				}
 			}
 
-			_ = nft.set_state(Rented, false);
+			_ = nft.state.unlock(&RENT_LOCK);
 			Ok(())
 		})?;
 
@@ -992,6 +984,36 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Remove the contract of an NFT that changed owner or was burned, with its offers and queue
+	/// entry, and return the cancellation fees to the contract participants.
+	pub fn remove_contract(nft_id: NFTId) -> Weight {
+		let contract = match Contracts::<T>::take(nft_id) {
+			Some(x) => x,
+			None => return T::DbWeight::get().reads(1),
+		};
+
+		let queue_kind = match contract.rentee {
+			Some(_) => contract.duration.queue_kind(),
+			None => QueueKind::Available,
+		};
+		Queues::<T>::mutate(|x| {
+			x.remove(nft_id, queue_kind);
+		});
+		Offers::<T>::remove(nft_id);
+		_ = Self::return_cancellation_fee(&contract.renter_cancellation_fee, &contract.renter);
+		if let Some(rentee) = &contract.rentee {
+			_ = Self::return_cancellation_fee(&contract.rentee_cancellation_fee, rentee);
+		}
+
+		let event = match contract.rentee {
+			Some(_) => Event::ContractEnded { nft_id, revoked_by: None },
+			None => Event::ContractCanceled { nft_id },
+		};
+		Self::deposit_event(event);
+
+		T::DbWeight::get().reads_writes(5, 8)
+	}
+
 	pub fn balance_check(account: &T::AccountId, amount: BalanceOf<T>) -> bool {
 		let current_balance = T::Currency::free_balance(account);
 		let new_balance = current_balance.checked_sub(&amount);
@@ -1029,18 +1051,12 @@ impl<T: Config> Pallet<T> {
 		if let Some(nft_id) = &maybe_rent_nft {
 			let nft = T::NFTExt::get_nft(*nft_id).ok_or(Error::<T>::RentNFTNotFound)?;
 			ensure!(nft.owner == *rentee, Error::<T>::RenteeDoesNotOwnTheRentNFT);
-			ensure!(
-				nft.not_in_state(&Self::invalid_state()).is_ok(),
-				Error::<T>::RentNFTNotInValidState
-			);
+			ensure!(Self::is_in_valid_state(&nft), Error::<T>::RentNFTNotInValidState);
 		}
 		if let Some(nft_id) = &maybe_cancel_nft {
 			let nft = T::NFTExt::get_nft(*nft_id).ok_or(Error::<T>::CancellationNFTNotFound)?;
 			ensure!(nft.owner == *rentee, Error::<T>::RenteeDoesNotOwnTheCancellationNFT);
-			ensure!(
-				nft.not_in_state(&Self::invalid_state()).is_ok(),
-				Error::<T>::CancellationNFTNotInValidState
-			);
+			ensure!(Self::is_in_valid_state(&nft), Error::<T>::CancellationNFTNotInValidState);
 		}
 
 		// Rent and Rentee Cancellation NFT Taken 📦
@@ -1056,8 +1072,10 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	pub fn invalid_state() -> Vec<NFTStateModifiers> {
-		vec![IsListed, Delegated, Soulbound, SecretSyncing, Rented, CapsuleSyncing, IsTransmission]
+	/// Checks that an NFT can be used in a contract: it must not be locked, delegated, soulbound
+	/// or syncing.
+	pub fn is_in_valid_state<L: Get<u32>>(nft: &NFTData<T::AccountId, L>) -> bool {
+		nft.can_list().is_ok() && !nft.state.is_soulbound
 	}
 }
 
@@ -1088,3 +1106,28 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 }
+
+impl<T: Config> OnNFTTransfer<T::AccountId> for Pallet<T> {
+	fn on_nft_transfer(nft_id: NFTId, _from: &T::AccountId, _to: &T::AccountId) -> Weight {
+		if !Contracts::<T>::contains_key(nft_id) {
+			return T::DbWeight::get().reads(1)
+		}
+		_ = T::NFTExt::unlock_nft(nft_id, RENT_LOCK);
+
+		Self::remove_contract(nft_id).saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == RENT_LOCK
+	}
+}
+
+impl<T: Config> OnNFTBurn<T::AccountId> for Pallet<T> {
+	fn on_nft_burn(nft_id: NFTId, _owner: &T::AccountId) -> Weight {
+		Self::remove_contract(nft_id)
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == RENT_LOCK
+	}
+}
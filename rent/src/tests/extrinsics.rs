@@ -17,15 +17,17 @@
 use super::mock::*;
 use frame_support::{assert_noop, BoundedVec};
 use frame_system::RawOrigin;
-use primitives::nfts::{NFTId, NFTState};
+use primitives::nfts::{NFTId, NFTLock, NFTState};
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
 use crate::{
 	tests::mock, AcceptanceType, CancellationFee, Duration, DurationInput, Error,
-	Event as RentEvent, RentContractData, RentFee, SubscriptionInput,
+	Event as RentEvent, RentContractData, RentFee, SubscriptionInput, RENT_LOCK,
 };
 
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
+
 pub const BLOCK_DURATION: u64 = 10;
 pub const BLOCK_MAX_DURATION: u64 = 100;
 pub const TOKENS: Balance = 100;
@@ -68,19 +70,19 @@ pub fn prepare_tests() {
 	let bob: mock::RuntimeOrigin = origin(BOB);
 
 	//Create NFTs.
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(bob.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(bob.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(bob, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(bob.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(bob.clone(), BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(bob, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 
 	//Check existence
 	assert!(NFT::nfts(ALICE_NFT_ID_0).is_some());
@@ -224,7 +226,7 @@ mod create_contract {
 			let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
 			let nft = NFT::nfts(ALICE_NFT_ID_6).unwrap();
 			assert_eq!(contract, data.clone());
-			assert!(nft.state.is_rented);
+			assert!(nft.state.is_locked_by(&RENT_LOCK));
 
 			// Event check.
 			let event = RentEvent::ContractCreated {
@@ -253,7 +255,7 @@ mod create_contract {
 			let current_size = Rent::queues().size();
 			let nb_contract_to_create = max_contract - current_size;
 			for i in 13..13 + nb_contract_to_create {
-				NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false)
+				NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
 					.unwrap();
 				Rent::create_contract(
 					alice.clone(),
@@ -335,8 +337,7 @@ mod create_contract {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			// Set to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, true, false);
+			let nft_state = NFTState::new(false, false, false, false, false, true);
 			NFT::set_nft_state(ALICE_NFT_ID_6, nft_state).unwrap();
 			// Try to create a contract with an NFT in invalid state.
 			let err = Rent::create_contract(
@@ -351,9 +352,9 @@ mod create_contract {
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
-			// Set to listed.
-			let nft_state =
-				NFTState::new(false, true, false, false, false, false, false, false, false);
+			// Set to locked.
+			let mut nft_state = NFTState::new(false, false, false, false, false, false);
+			nft_state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft_state(ALICE_NFT_ID_6, nft_state).unwrap();
 			// Try to create a contract with an NFT in invalid state.
 			let err = Rent::create_contract(
@@ -369,8 +370,7 @@ mod create_contract {
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
 			// Set to delegated.
-			let nft_state =
-				NFTState::new(false, false, false, true, false, false, false, false, false);
+			let nft_state = NFTState::new(false, false, true, false, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID_6, nft_state).unwrap();
 			// Try to create a contract with an NFT in invalid state.
 			let err = Rent::create_contract(
@@ -386,8 +386,7 @@ mod create_contract {
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
 			// Set to soulbound.
-			let nft_state =
-				NFTState::new(false, false, false, false, true, false, false, false, false);
+			let nft_state = NFTState::new(false, false, false, true, false, false);
 			NFT::set_nft_state(ALICE_NFT_ID_6, nft_state).unwrap();
 			// Try to create a contract with an NFT in invalid state.
 			let err = Rent::create_contract(
@@ -403,8 +402,8 @@ mod create_contract {
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
 			// Set to rented.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, true, false, false);
+			let mut nft_state = NFTState::new(false, false, false, false, false, false);
+			nft_state.lock(RENT_LOCK).unwrap();
 			NFT::set_nft_state(ALICE_NFT_ID_6, nft_state).unwrap();
 			// Try to create a contract with an NFT in invalid state.
 			let err = Rent::create_contract(
@@ -736,9 +735,9 @@ mod create_contract {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
-			// Set cancellation fee NFT to capsule.
-			let nft_state =
-				NFTState::new(false, false, false, false, false, false, false, false, true);
+			// Set cancellation fee NFT to locked.
+			let mut nft_state = NFTState::new(false, false, false, false, false, false);
+			nft_state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft_state(ALICE_NFT_ID_7, nft_state).unwrap();
 
 			// Try to create a contract with invalid state cancellation fee NFT.
@@ -776,7 +775,7 @@ mod cancel_contract {
 				.available_queue
 				.get(FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK)
 				.is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			// Event check.
 			let event = RentEvent::ContractCanceled { nft_id: FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK };
 			let event = RuntimeEvent::Rent(event);
@@ -845,7 +844,7 @@ mod revoke_contract {
 			let nft = NFT::nfts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
 			assert!(Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance);
 			assert_eq!(Balances::free_balance(BOB), bob_balance + 2 * LESS_TOKENS);
 			// Event check.
@@ -876,7 +875,7 @@ mod revoke_contract {
 			let nft = NFT::nfts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
 			assert!(Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + 2 * LESS_TOKENS);
 			assert_eq!(Balances::free_balance(BOB), bob_balance);
 			// Event check.
@@ -908,7 +907,7 @@ mod revoke_contract {
 			let bob_cancellation_nft = NFT::nfts(BOB_NFT_ID_0).unwrap();
 			assert!(Rent::contracts(FIXED_MANU_REV_NFT_NFT_NFT).is_none());
 			assert!(Rent::queues().available_queue.get(FIXED_MANU_REV_NFT_NFT_NFT).is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(alice_cancellation_nft.owner, BOB);
 			assert_eq!(bob_cancellation_nft.owner, BOB);
 			// Event check.
@@ -940,7 +939,7 @@ mod revoke_contract {
 			let bob_cancellation_nft = NFT::nfts(BOB_NFT_ID_0).unwrap();
 			assert!(Rent::contracts(FIXED_MANU_REV_NFT_NFT_NFT).is_none());
 			assert!(Rent::queues().available_queue.get(FIXED_MANU_REV_NFT_NFT_NFT).is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(alice_cancellation_nft.owner, ALICE);
 			assert_eq!(bob_cancellation_nft.owner, ALICE);
 			// Event check.
@@ -977,7 +976,7 @@ mod revoke_contract {
 				.available_queue
 				.get(FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK)
 				.is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + 2);
 			assert_eq!(Balances::free_balance(BOB), bob_balance + LESS_TOKENS + 8);
 			// Event check.
@@ -1015,7 +1014,7 @@ mod revoke_contract {
 				.available_queue
 				.get(FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK)
 				.is_none());
-			assert!(!nft.state.is_rented);
+			assert!(!nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + LESS_TOKENS + 8);
 			assert_eq!(Balances::free_balance(BOB), bob_balance + 2);
 			// Event check.
@@ -1111,7 +1110,7 @@ mod rent {
 			assert!(contract.start_block.is_some());
 			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(Rent::queues().fixed_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_some());
-			assert!(nft.state.is_rented);
+			assert!(nft.state.is_locked_by(&RENT_LOCK));
 
 			// Event check.
 			let event = RentEvent::ContractStarted {
@@ -1348,7 +1347,8 @@ mod make_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			Rent::create_contract(
@@ -1377,7 +1377,8 @@ mod make_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			Rent::create_contract(
@@ -1406,7 +1407,8 @@ mod make_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), Some(PERCENT_0), None, false)
+				.unwrap();
 			let nft_id = NFT::next_nft_id() - 1;
 
 			Rent::create_contract(
@@ -1450,7 +1452,7 @@ mod make_rent_offer {
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_1).unwrap();
-			nft.state.is_listed = true;
+			nft.state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft(BOB_NFT_ID_1, nft).unwrap();
 
 			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
@@ -1480,7 +1482,7 @@ mod make_rent_offer {
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_0).unwrap();
-			nft.state.is_listed = true;
+			nft.state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft(BOB_NFT_ID_0, nft).unwrap();
 
 			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
@@ -1539,7 +1541,7 @@ mod accept_rent_offer {
 				.subscription_queue
 				.get(SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK)
 				.is_some());
-			assert!(nft.state.is_rented);
+			assert!(nft.state.is_locked_by(&RENT_LOCK));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS);
 			assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS - LESS_TOKENS);
 			// Event check.
@@ -1643,7 +1645,7 @@ mod accept_rent_offer {
 			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_1).unwrap();
-			nft.state.is_listed = true;
+			nft.state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft(BOB_NFT_ID_1, nft).unwrap();
 
 			// Try to accept rent offer for non existing contract.
@@ -1681,7 +1683,7 @@ mod accept_rent_offer {
 			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_0).unwrap();
-			nft.state.is_listed = true;
+			nft.state.lock(TEST_LOCK).unwrap();
 			NFT::set_nft(BOB_NFT_ID_0, nft).unwrap();
 
 			// Try to accept rent offer for non existing contract.
@@ -15,6 +15,7 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
+use frame_support::assert_ok;
 use frame_system::RawOrigin;
 use ternoa_common::traits::NFTExt;
 
@@ -257,3 +258,49 @@ fn remove_expired_contract() {
 		System::assert_has_event(event_5);
 	})
 }
+
+#[test]
+fn burn_removes_contract_and_offers() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		let bob: mock::RuntimeOrigin = origin(BOB);
+		let nft_id = SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK;
+		Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK).unwrap();
+		let alice_balance = Balances::free_balance(ALICE);
+		let queue_size = Rent::queues().size();
+
+		assert_ok!(NFT::burn_nft(origin(ALICE), nft_id));
+
+		// State check.
+		assert_eq!(NFT::get_nft(nft_id), None);
+		assert_eq!(Rent::contracts(nft_id), None);
+		assert_eq!(Rent::offers(nft_id), None);
+		assert_eq!(Rent::queues().size(), queue_size - 1);
+		assert_eq!(Balances::free_balance(ALICE), alice_balance + LESS_TOKENS);
+
+		// Event check.
+		let event = RuntimeEvent::Rent(RentEvent::ContractCanceled { nft_id });
+		System::assert_has_event(event);
+	})
+}
+
+#[test]
+fn transfer_removes_contract() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		let nft_id = FIXED_MANU_REV_NFT_NFT_NFT;
+
+		assert_ok!(NFT::transfer_nft(origin(ALICE), nft_id, BOB));
+
+		// State check.
+		assert_eq!(Rent::contracts(nft_id), None);
+		let nft = NFT::get_nft(nft_id).unwrap();
+		assert_eq!(nft.owner, BOB);
+		assert!(!nft.state.is_locked());
+		assert_eq!(NFT::get_nft(ALICE_NFT_ID_5).unwrap().owner, ALICE);
+
+		// Event check.
+		let event = RuntimeEvent::Rent(RentEvent::ContractCanceled { nft_id });
+		System::assert_has_event(event);
+	})
+}
@@ -79,6 +79,9 @@ pub mod pallet {
 		/// Size limit for lists
 		#[pallet::constant]
 		type ListSizeLimit: Get<u32>;
+
+		/// Handler called when an enclave is removed, e.g. to replace the shards it held.
+		type OnEnclaveRemoved: traits::OnEnclaveRemoved<Self::AccountId>;
 	}
 
 	/// Mapping of operator addresses who want to be registered as enclaves
@@ -457,6 +460,9 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 
+			// Read before the enclave is removed, to let the shards it held be replaced.
+			let cluster_id = EnclaveClusterId::<T>::get(&operator_address);
+
 			EnclaveData::<T>::try_mutate(&operator_address, |maybe_enclave| -> DispatchResult {
 				let enclave = maybe_enclave.as_mut().ok_or(Error::<T>::EnclaveNotFound)?;
 
@@ -510,6 +516,14 @@ pub mod pallet {
 				Ok(())
 			})?;
 
+			if let Some(cluster_id) = cluster_id {
+				let weight = T::OnEnclaveRemoved::on_enclave_removed(cluster_id, &operator_address);
+				frame_system::Pallet::<T>::register_extra_weight_unchecked(
+					weight,
+					DispatchClass::Normal,
+				);
+			}
+
 			Self::deposit_event(Event::EnclaveRemoved { operator_address });
 			Ok(().into())
 		}
@@ -641,4 +655,15 @@ impl<T: Config> traits::TEEExt for Pallet<T> {
 		})?;
 		Ok(())
 	}
+
+	fn cluster_exists(cluster_id: ClusterId) -> bool {
+		ClusterData::<T>::contains_key(cluster_id)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_cluster() -> ClusterId {
+		let id = Self::get_next_cluster_id();
+		ClusterData::<T>::insert(id, Cluster::new(Default::default()));
+		id
+	}
 }
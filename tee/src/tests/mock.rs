@@ -118,6 +118,7 @@ impl Config for Test {
 	type ClusterSize = ClusterSize;
 	type MaxUriLen = MaxUriLen;
 	type ListSizeLimit = ListSizeLimit;
+	type OnEnclaveRemoved = ();
 }
 
 // Do not use the `0` account id since this would be the default value
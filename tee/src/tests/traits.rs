@@ -46,3 +46,23 @@ fn ensure_enclave() {
 			assert!(res.is_some());
 		})
 }
+
+#[test]
+fn cluster_exists() {
+	ExtBuilder::default().tokens(vec![(ALICE, 10)]).build().execute_with(|| {
+		let cluster_id: ClusterId = 0;
+		assert!(!TEE::cluster_exists(cluster_id));
+
+		assert_ok!(TEE::create_cluster(root()));
+		assert!(TEE::cluster_exists(cluster_id));
+	})
+}
+
+#[test]
+fn create_cluster() {
+	ExtBuilder::default().tokens(vec![(ALICE, 10)]).build().execute_with(|| {
+		let cluster_id = <TEE as TEEExt>::create_cluster();
+		assert!(TEE::clusters(cluster_id).is_some());
+		assert_eq!(TEE::next_cluster_id(), cluster_id + 1);
+	})
+}
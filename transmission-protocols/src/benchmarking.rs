@@ -73,7 +73,7 @@ benchmarks! {
 	}: _(alice_origin, benchmark_data.alice_nft_id, bob, protocol, cancellation)
 	verify {
 		let nft = T::NFTExt::get_nft(benchmark_data.alice_nft_id).unwrap();
-		assert!(nft.state.is_transmission);
+		assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::<T>::transmissions(benchmark_data.alice_nft_id).is_some());
 		assert_eq!(TransmissionProtocols::<T>::at_block_queue().get(benchmark_data.alice_nft_id), Some(10u32.into()));
 	}
@@ -93,7 +93,7 @@ benchmarks! {
 	}: _(alice_origin, benchmark_data.alice_nft_id)
 	verify {
 		let nft = T::NFTExt::get_nft(benchmark_data.alice_nft_id).unwrap();
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::<T>::transmissions(benchmark_data.alice_nft_id).is_none());
 		assert_eq!(TransmissionProtocols::<T>::at_block_queue().get(benchmark_data.alice_nft_id), None);
 	}
@@ -113,7 +113,7 @@ benchmarks! {
 	}: _(alice_origin, benchmark_data.alice_nft_id, 200u32.into())
 	verify {
 		let nft = T::NFTExt::get_nft(benchmark_data.alice_nft_id).unwrap();
-		assert!(nft.state.is_transmission);
+		assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::<T>::transmissions(benchmark_data.alice_nft_id).is_some());
 		assert_eq!(TransmissionProtocols::<T>::at_block_queue().get(benchmark_data.alice_nft_id), Some(200u32.into()));
 	}
@@ -141,7 +141,7 @@ benchmarks! {
 	}: _(alice_origin, benchmark_data.alice_nft_id)
 	verify {
 		let nft = T::NFTExt::get_nft(benchmark_data.alice_nft_id).unwrap();
-		assert!(nft.state.is_transmission);
+		assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::<T>::transmissions(benchmark_data.alice_nft_id).is_some());
 		assert_eq!(TransmissionProtocols::<T>::at_block_queue().get(benchmark_data.alice_nft_id), Some(10u32.into()));
 	}
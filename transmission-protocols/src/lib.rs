@@ -31,15 +31,19 @@ pub use weights::WeightInfo;
 use frame_support::{
 	dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo},
 	traits::{ExistenceRequirement::KeepAlive, OnUnbalanced, StorageVersion, WithdrawReasons},
+	weights::Weight,
 	BoundedVec,
 };
-use primitives::nfts::NFTId;
+use primitives::nfts::{NFTId, NFTLock, NFTRestriction};
 use sp_runtime::SaturatedConversion;
 use sp_std::vec;
-use ternoa_common::traits::NFTExt;
+use ternoa_common::traits::{NFTExt, OnNFTBurn, OnNFTTransfer};
 
 const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
+/// Lock held on NFTs that have a transmission protocol set.
+pub const TRANSMISSION_LOCK: NFTLock = NFTLock::new(*b"ter/tran", *b"transmit");
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -244,12 +248,10 @@ pub mod pallet {
 		NotTheNFTOwner,
 		/// Operation is not permitted because the recipient is the caller
 		InvalidRecipient,
-		/// Operation is not permitted because the NFT is listed.
-		CannotSetTransmissionForListedNFTs,
+		/// Operation is not permitted because the NFT is locked by another pallet.
+		CannotSetTransmissionForLockedNFTs,
 		/// Operation is not permitted because the NFT is delegated.
 		CannotSetTransmissionForDelegatedNFTs,
-		/// Operation is not permitted because the NFT is rented.
-		CannotSetTransmissionForRentedNFTs,
 		/// Operation is not permitted because the NFT is souldbound and user is not the creator.
 		CannotSetTransmissionForNotCreatedSoulboundNFTs,
 		/// Operation is not permitted because the NFT secret is syncing.
@@ -310,25 +312,7 @@ pub mod pallet {
 			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
 			ensure!(who != recipient, Error::<T>::InvalidRecipient);
-			ensure!(!nft.state.is_listed, Error::<T>::CannotSetTransmissionForListedNFTs);
-			ensure!(!nft.state.is_delegated, Error::<T>::CannotSetTransmissionForDelegatedNFTs);
-			ensure!(!nft.state.is_rented, Error::<T>::CannotSetTransmissionForRentedNFTs);
-			ensure!(
-				!(nft.state.is_soulbound && nft.owner != nft.creator),
-				Error::<T>::CannotSetTransmissionForNotCreatedSoulboundNFTs
-			);
-			ensure!(
-				!nft.state.is_syncing_secret,
-				Error::<T>::CannotSetTransmissionForSyncingSecretNFTs
-			);
-			ensure!(
-				!nft.state.is_syncing_capsule,
-				Error::<T>::CannotSetTransmissionForSyncingCapsules
-			);
-			ensure!(
-				!nft.state.is_transmission,
-				Error::<T>::CannotSetTransmissionForNFTsInTransmission
-			);
+			nft.can_transfer().map_err(Self::transmission_restriction_error)?;
 
 			if let Some(end_block) = protocol.get_end_block() {
 				let now = frame_system::Pallet::<T>::block_number();
@@ -389,7 +373,9 @@ pub mod pallet {
 				TransmissionData::new(recipient.clone(), protocol.clone(), cancellation.clone());
 			Transmissions::<T>::insert(nft_id, transmission_data);
 
-			nft.state.is_transmission = true;
+			nft.state
+				.lock(TRANSMISSION_LOCK)
+				.ok_or(Error::<T>::CannotSetTransmissionForLockedNFTs)?;
 			T::NFTExt::set_nft(nft_id, nft)?;
 
 			if has_change {
@@ -414,7 +400,7 @@ pub mod pallet {
 			// Checks
 			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-			ensure!(nft.state.is_transmission, Error::<T>::NFTIsNotInTransmission);
+			ensure!(nft.state.is_locked_by(&TRANSMISSION_LOCK), Error::<T>::NFTIsNotInTransmission);
 
 			let transmission_data =
 				Transmissions::<T>::get(nft_id).ok_or(Error::<T>::TransmissionNotFound)?;
@@ -425,7 +411,7 @@ pub mod pallet {
 			);
 
 			// Execute
-			nft.state.is_transmission = false;
+			_ = nft.state.unlock(&TRANSMISSION_LOCK);
 			T::NFTExt::set_nft(nft_id, nft)?;
 
 			AtBlockQueue::<T>::mutate(|x| {
@@ -454,7 +440,7 @@ pub mod pallet {
 			// Checks
 			let nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
 			ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
-			ensure!(nft.state.is_transmission, Error::<T>::NFTIsNotInTransmission);
+			ensure!(nft.state.is_locked_by(&TRANSMISSION_LOCK), Error::<T>::NFTIsNotInTransmission);
 
 			let mut transmission_data =
 				Transmissions::<T>::get(nft_id).ok_or(Error::<T>::TransmissionNotFound)?;
@@ -493,7 +479,7 @@ pub mod pallet {
 
 			// Checks
 			let nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
-			ensure!(nft.state.is_transmission, Error::<T>::NFTIsNotInTransmission);
+			ensure!(nft.state.is_locked_by(&TRANSMISSION_LOCK), Error::<T>::NFTIsNotInTransmission);
 
 			let transmission_data =
 				Transmissions::<T>::get(nft_id).ok_or(Error::<T>::TransmissionNotFound)?;
@@ -609,13 +595,41 @@ impl<T: Config> Pallet<T> {
 		T::NFTExt::mutate_nft(nft_id, |x| -> DispatchResult {
 			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
 			nft.owner = transmission_data.recipient;
-			nft.state.is_transmission = false;
+			_ = nft.state.unlock(&TRANSMISSION_LOCK);
 			Ok(())
 		})?;
 		Transmissions::<T>::remove(nft_id);
 		Ok(())
 	}
 
+	/// Remove the transmission protocol of an NFT that changed owner or was burned, with its
+	/// queue entry and consents.
+	fn remove_transmission(nft_id: NFTId) -> Weight {
+		if Transmissions::<T>::take(nft_id).is_none() {
+			return T::DbWeight::get().reads(1)
+		}
+		AtBlockQueue::<T>::mutate(|x| {
+			x.remove(nft_id);
+		});
+		OnConsentData::<T>::remove(nft_id);
+		Self::deposit_event(Event::ProtocolRemoved { nft_id });
+
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+
+	fn transmission_restriction_error(restriction: NFTRestriction) -> Error<T> {
+		match restriction {
+			NFTRestriction::Locked(lock) if lock == TRANSMISSION_LOCK =>
+				Error::<T>::CannotSetTransmissionForNFTsInTransmission,
+			NFTRestriction::Locked(_) => Error::<T>::CannotSetTransmissionForLockedNFTs,
+			NFTRestriction::Delegated => Error::<T>::CannotSetTransmissionForDelegatedNFTs,
+			NFTRestriction::NotCreatedSoulbound =>
+				Error::<T>::CannotSetTransmissionForNotCreatedSoulboundNFTs,
+			NFTRestriction::SecretSyncing => Error::<T>::CannotSetTransmissionForSyncingSecretNFTs,
+			NFTRestriction::CapsuleSyncing => Error::<T>::CannotSetTransmissionForSyncingCapsules,
+		}
+	}
+
 	/// Get the protocol additional setup fee
 	fn get_protocol_fee(protocol_kind: TransmissionProtocolKind) -> BalanceOf<T> {
 		match protocol_kind {
@@ -652,3 +666,28 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 }
+
+impl<T: Config> OnNFTTransfer<T::AccountId> for Pallet<T> {
+	fn on_nft_transfer(nft_id: NFTId, _from: &T::AccountId, _to: &T::AccountId) -> Weight {
+		if !Transmissions::<T>::contains_key(nft_id) {
+			return T::DbWeight::get().reads(1)
+		}
+		_ = T::NFTExt::unlock_nft(nft_id, TRANSMISSION_LOCK);
+
+		Self::remove_transmission(nft_id).saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == TRANSMISSION_LOCK
+	}
+}
+
+impl<T: Config> OnNFTBurn<T::AccountId> for Pallet<T> {
+	fn on_nft_burn(nft_id: NFTId, _owner: &T::AccountId) -> Weight {
+		Self::remove_transmission(nft_id)
+	}
+
+	fn releases_lock(lock: &NFTLock) -> bool {
+		*lock == TRANSMISSION_LOCK
+	}
+}
@@ -20,18 +20,19 @@ use frame_support::{
 };
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
-use primitives::nfts::NFTId;
+use primitives::nfts::{NFTId, NFTLock};
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
 use crate::{
 	tests::mock, CancellationPeriod, Error, Event as TransmissionProtocolEvent,
-	TransmissionProtocol, TransmissionProtocolKind,
+	TransmissionProtocol, TransmissionProtocolKind, TRANSMISSION_LOCK,
 };
 
 pub const ALICE_NFT_ID: NFTId = 0;
 pub const BOB_NFT_ID: NFTId = 1;
 const INVALID_NFT_ID: NFTId = 1001;
+const TEST_LOCK: NFTLock = NFTLock::new(*b"ter/test", *b"testlock");
 const PERCENT_0: Permill = Permill::from_parts(0);
 
 fn origin(account: u64) -> mock::RuntimeOrigin {
@@ -46,8 +47,8 @@ pub fn prepare_tests() {
 	let alice: mock::RuntimeOrigin = origin(ALICE);
 	let bob: mock::RuntimeOrigin = origin(BOB);
 
-	NFT::create_nft(alice, BoundedVec::default(), PERCENT_0, None, false).unwrap();
-	NFT::create_nft(bob, BoundedVec::default(), PERCENT_0, None, false).unwrap();
+	NFT::create_nft(alice, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
+	NFT::create_nft(bob, BoundedVec::default(), Some(PERCENT_0), None, false).unwrap();
 
 	assert_eq!(NFT::nfts(ALICE_NFT_ID).is_some(), true);
 	assert_eq!(NFT::nfts(BOB_NFT_ID).is_some(), true);
@@ -79,7 +80,7 @@ mod set_transmission_protocol {
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			let transmission_data = TransmissionProtocols::transmissions(ALICE_NFT_ID);
 			let mut queue = TransmissionProtocols::at_block_queue();
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(transmission_data.is_some());
 			assert_eq!(queue.get(ALICE_NFT_ID), Some(10));
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -123,7 +124,7 @@ mod set_transmission_protocol {
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			let transmission_data = TransmissionProtocols::transmissions(ALICE_NFT_ID);
 			let mut queue = TransmissionProtocols::at_block_queue();
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(transmission_data.is_some());
 			assert_eq!(queue.get(ALICE_NFT_ID), Some(10));
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -171,7 +172,7 @@ mod set_transmission_protocol {
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			let transmission_data = TransmissionProtocols::transmissions(ALICE_NFT_ID);
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(transmission_data.is_some());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -220,7 +221,7 @@ mod set_transmission_protocol {
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			let transmission_data = TransmissionProtocols::transmissions(ALICE_NFT_ID);
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(transmission_data.is_some());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -300,14 +301,14 @@ mod set_transmission_protocol {
 	}
 
 	#[test]
-	fn cannot_set_transmission_for_listed_nfts() {
+	fn cannot_set_transmission_for_locked_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
 			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
 				let nft = x.as_mut().unwrap();
-				nft.state.is_listed = true;
+				nft.state.lock(TEST_LOCK).unwrap();
 				Ok(())
 			})
 			.unwrap();
@@ -321,7 +322,7 @@ mod set_transmission_protocol {
 				protocol,
 				cancellation,
 			);
-			assert_noop!(err, Error::<Test>::CannotSetTransmissionForListedNFTs);
+			assert_noop!(err, Error::<Test>::CannotSetTransmissionForLockedNFTs);
 		})
 	}
 
@@ -351,32 +352,6 @@ mod set_transmission_protocol {
 		})
 	}
 
-	#[test]
-	fn cannot_set_transmission_for_rented_nfts() {
-		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
-			prepare_tests();
-			let alice: mock::RuntimeOrigin = origin(ALICE);
-
-			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
-				let nft = x.as_mut().unwrap();
-				nft.state.is_rented = true;
-				Ok(())
-			})
-			.unwrap();
-
-			let protocol = TransmissionProtocol::AtBlock(10);
-			let cancellation = CancellationPeriod::None;
-			let err = TransmissionProtocols::set_transmission_protocol(
-				alice,
-				ALICE_NFT_ID,
-				BOB,
-				protocol,
-				cancellation,
-			);
-			assert_noop!(err, Error::<Test>::CannotSetTransmissionForRentedNFTs);
-		})
-	}
-
 	#[test]
 	fn cannot_set_transmission_for_not_created_soulbound_nfts() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -464,7 +439,7 @@ mod set_transmission_protocol {
 
 			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
 				let nft = x.as_mut().unwrap();
-				nft.state.is_transmission = true;
+				nft.state.lock(TRANSMISSION_LOCK).unwrap();
 				Ok(())
 			})
 			.unwrap();
@@ -681,7 +656,7 @@ mod remove_transmission_protocol {
 
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
-			assert!(!nft.state.is_transmission);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -714,7 +689,7 @@ mod remove_transmission_protocol {
 
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
-			assert!(!nft.state.is_transmission);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -751,7 +726,7 @@ mod remove_transmission_protocol {
 
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
-			assert!(!nft.state.is_transmission);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -789,7 +764,7 @@ mod remove_transmission_protocol {
 
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
-			assert!(!nft.state.is_transmission);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -842,7 +817,7 @@ mod remove_transmission_protocol {
 
 			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
 				let nft = x.as_mut().unwrap();
-				nft.state.is_transmission = true;
+				nft.state.lock(TRANSMISSION_LOCK).unwrap();
 				Ok(())
 			})
 			.unwrap();
@@ -920,7 +895,7 @@ mod reset_timer {
 
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_some());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), Some(20));
 
@@ -975,7 +950,7 @@ mod reset_timer {
 
 			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
 				let nft = x.as_mut().unwrap();
-				nft.state.is_transmission = true;
+				nft.state.lock(TRANSMISSION_LOCK).unwrap();
 				Ok(())
 			})
 			.unwrap();
@@ -1078,7 +1053,7 @@ mod add_consent {
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			let consent_data = TransmissionProtocols::on_consent_data(ALICE_NFT_ID).unwrap();
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_some());
 			assert_eq!(consent_data.len(), 1);
 			assert!(consent_data.contains(&BOB));
@@ -1115,7 +1090,7 @@ mod add_consent {
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			assert!(nft.owner == BOB);
-			assert!(!nft.state.is_transmission);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 			assert!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID).is_none());
 
@@ -1172,7 +1147,7 @@ mod add_consent {
 			// Final state checks.
 			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 			assert!(nft.owner == ALICE);
-			assert!(nft.state.is_transmission);
+			assert!(nft.state.is_locked_by(&TRANSMISSION_LOCK));
 			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_some());
 			assert!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID).is_none());
 			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), Some(10));
@@ -1226,7 +1201,7 @@ mod add_consent {
 
 			NFT::mutate_nft(ALICE_NFT_ID, |x| -> DispatchResult {
 				let nft = x.as_mut().unwrap();
-				nft.state.is_transmission = true;
+				nft.state.lock(TRANSMISSION_LOCK).unwrap();
 				Ok(())
 			})
 			.unwrap();
@@ -1479,3 +1454,73 @@ mod set_protocol_fee {
 		})
 	}
 }
+
+mod nft_hooks {
+	use super::*;
+
+	#[test]
+	fn transfer_removes_transmission() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let protocol = TransmissionProtocol::AtBlock(10);
+			let cancellation = CancellationPeriod::None;
+			TransmissionProtocols::set_transmission_protocol(
+				alice.clone(),
+				ALICE_NFT_ID,
+				BOB,
+				protocol,
+				cancellation,
+			)
+			.unwrap();
+
+			assert_ok!(NFT::transfer_nft(alice, ALICE_NFT_ID, CHARLIE));
+
+			// Final state checks.
+			let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
+			assert_eq!(nft.owner, CHARLIE);
+			assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
+			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
+			assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
+
+			// Events checks.
+			let event = TransmissionProtocolEvent::ProtocolRemoved { nft_id: ALICE_NFT_ID };
+			let event = RuntimeEvent::TransmissionProtocols(event);
+			assert!(System::events().iter().any(|x| x.event == event));
+		})
+	}
+
+	#[test]
+	fn burn_removes_transmission() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let protocol = TransmissionProtocol::OnConsent {
+				consent_list: BoundedVec::try_from(vec![BOB, CHARLIE]).unwrap(),
+				threshold: 2,
+			};
+			let cancellation = CancellationPeriod::None;
+			TransmissionProtocols::set_transmission_protocol(
+				alice.clone(),
+				ALICE_NFT_ID,
+				BOB,
+				protocol,
+				cancellation,
+			)
+			.unwrap();
+			TransmissionProtocols::add_consent(origin(BOB), ALICE_NFT_ID).unwrap();
+
+			assert_ok!(NFT::burn_nft(alice, ALICE_NFT_ID));
+
+			// Final state checks.
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID), None);
+			assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
+			assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
+
+			// Events checks.
+			let event = TransmissionProtocolEvent::ProtocolRemoved { nft_id: ALICE_NFT_ID };
+			let event = RuntimeEvent::TransmissionProtocols(event);
+			assert!(System::events().iter().any(|x| x.event == event));
+		})
+	}
+}
@@ -18,6 +18,7 @@ use super::mock::*;
 use crate::{
 	tests::{extrinsics::*, mock},
 	CancellationPeriod, Event as TransmissionProtocolEvent, TransmissionProtocol,
+	TRANSMISSION_LOCK,
 };
 use frame_support::BoundedVec;
 use frame_system::RawOrigin;
@@ -49,7 +50,7 @@ fn transmit_at_block() {
 		// State check
 		let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 		assert_eq!(nft.owner, BOB);
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 		assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 		assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -86,7 +87,7 @@ fn transmit_at_block_with_reset() {
 		// State check
 		let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 		assert_eq!(nft.owner, BOB);
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 		assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 		assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -126,7 +127,7 @@ fn transmit_on_consent_at_block() {
 		// State check
 		let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 		assert_eq!(nft.owner, BOB);
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 		assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 		assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
@@ -169,14 +170,14 @@ fn multiple_transmit_at_block() {
 		// State check
 		let nft = NFT::get_nft(ALICE_NFT_ID).unwrap();
 		assert_eq!(nft.owner, BOB);
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::transmissions(ALICE_NFT_ID).is_none());
 		assert_eq!(TransmissionProtocols::at_block_queue().get(ALICE_NFT_ID), None);
 		assert_eq!(TransmissionProtocols::on_consent_data(ALICE_NFT_ID), None);
 
 		let nft = NFT::get_nft(BOB_NFT_ID).unwrap();
 		assert_eq!(nft.owner, ALICE);
-		assert!(!nft.state.is_transmission);
+		assert!(!nft.state.is_locked_by(&TRANSMISSION_LOCK));
 		assert!(TransmissionProtocols::transmissions(BOB_NFT_ID).is_none());
 		assert_eq!(TransmissionProtocols::at_block_queue().get(BOB_NFT_ID), None);
 		assert_eq!(TransmissionProtocols::on_consent_data(BOB_NFT_ID), None);